@@ -0,0 +1,168 @@
+// Publishing cost of one FIX snapshot with and without a stalled price consumer.
+// Both cases should be on par: the publishing path only pushes into bounded queues.
+// fan_out - BroadCastData only. app_context - the whole path the FIX handler calls
+use std::{collections::HashMap, hint::black_box, sync::Arc, time::Duration};
+
+use criterion::{criterion_group, criterion_main, BatchSize, Criterion};
+use rust_extensions::date_time::DateTimeAsMicroseconds;
+use tokio::runtime::Runtime;
+use your_bourse_bridge::{
+    app::{BroadCastData, PriceSubscriber, StaticPriceFeedSource},
+    settings::{SettingsModel, SettingsReader},
+    your_bourse::{QuoteTimeSource, YbMarketData},
+    AppContext,
+};
+
+const EXTERNAL_SYMBOL: &'static str = "EUR/USD";
+const OUR_SYMBOL: &'static str = "EURUSD";
+const CONSUMERS: usize = 100;
+const SOFT_QUEUE_LIMIT: usize = 1_000;
+const HARD_QUEUE_LIMIT: usize = 1_000_000;
+
+fn market_data() -> YbMarketData {
+    let now = DateTimeAsMicroseconds::now();
+
+    YbMarketData {
+        instrument_id: EXTERNAL_SYMBOL.to_string(),
+        date: now,
+        date_source: QuoteTimeSource::SendingTime,
+        bid: 1.08345,
+        ask: 1.08355,
+        bid_size: None,
+        ask_size: None,
+        sending_time: now,
+        entry_time: None,
+        receive_time: now,
+    }
+}
+
+fn add_consumers(rt: &Runtime, broadcast_data: &mut BroadCastData, with_stalled_consumer: bool) {
+    broadcast_data
+        .maps
+        .insert(EXTERNAL_SYMBOL.to_string(), vec![OUR_SYMBOL.to_string()]);
+
+    for id in 0..CONSUMERS {
+        let subscriber = Arc::new(PriceSubscriber::new(
+            id as i32,
+            "fast".to_string(),
+            SOFT_QUEUE_LIMIT,
+            HARD_QUEUE_LIMIT,
+        ));
+        broadcast_data.add_subscriber(subscriber.clone());
+
+        rt.spawn(async move { while subscriber.next_batch(512).await.is_some() {} });
+    }
+
+    if with_stalled_consumer {
+        let subscriber = Arc::new(PriceSubscriber::new(
+            CONSUMERS as i32,
+            "stalled".to_string(),
+            SOFT_QUEUE_LIMIT,
+            HARD_QUEUE_LIMIT,
+        ));
+        broadcast_data.add_subscriber(subscriber.clone());
+
+        rt.spawn(async move {
+            while subscriber.next_batch(1).await.is_some() {
+                tokio::time::sleep(Duration::from_secs(1)).await;
+            }
+        });
+    }
+}
+
+fn create_broadcast_data(rt: &Runtime, with_stalled_consumer: bool) -> BroadCastData {
+    let mut broadcast_data = BroadCastData::new("BENCH".to_string());
+    add_consumers(rt, &mut broadcast_data, with_stalled_consumer);
+    broadcast_data
+}
+
+// Default settings and a static map. No nosql behind it
+fn create_app(rt: &Runtime, with_stalled_consumer: bool) -> AppContext {
+    rt.block_on(async {
+        let settings_reader = SettingsReader::from_model(SettingsModel {
+            liquidity_provider_id: "BENCH".to_string(),
+            ..Default::default()
+        });
+
+        let mut instrument_map = HashMap::new();
+        instrument_map.insert(OUR_SYMBOL.to_string(), EXTERNAL_SYMBOL.to_string());
+
+        let feed_source = StaticPriceFeedSource {
+            instrument_map,
+            yb_settings: None,
+        };
+
+        let app =
+            AppContext::with_feed_source(Arc::new(settings_reader), Arc::new(feed_source)).await;
+
+        add_consumers(
+            rt,
+            &mut *app.broadcast_data.lock().await,
+            with_stalled_consumer,
+        );
+
+        app
+    })
+}
+
+fn bench_fan_out(c: &mut Criterion) {
+    let rt = tokio::runtime::Builder::new_multi_thread()
+        .worker_threads(2)
+        .enable_all()
+        .build()
+        .unwrap();
+
+    let market_data = market_data();
+    let instruments_settings = HashMap::new();
+
+    let mut group = c.benchmark_group("fan_out");
+
+    for (name, with_stalled_consumer) in [
+        ("fast_consumers", false),
+        ("fast_consumers_and_stalled_consumer", true),
+    ] {
+        let mut broadcast_data = create_broadcast_data(&rt, with_stalled_consumer);
+
+        group.bench_function(name, |b| {
+            b.iter(|| {
+                broadcast_data.broad_cast_bid_ask(
+                    black_box(&market_data),
+                    &instruments_settings,
+                    DateTimeAsMicroseconds::now(),
+                )
+            })
+        });
+    }
+
+    group.finish();
+}
+
+fn bench_app_context(c: &mut Criterion) {
+    let rt = tokio::runtime::Builder::new_multi_thread()
+        .worker_threads(2)
+        .enable_all()
+        .build()
+        .unwrap();
+
+    let mut group = c.benchmark_group("app_context");
+
+    for (name, with_stalled_consumer) in [
+        ("fast_consumers", false),
+        ("fast_consumers_and_stalled_consumer", true),
+    ] {
+        let app = create_app(&rt, with_stalled_consumer);
+
+        group.bench_function(name, |b| {
+            b.iter_batched(
+                market_data,
+                |market_data| rt.block_on(app.broad_cast_bid_ask(black_box(market_data))),
+                BatchSize::SmallInput,
+            )
+        });
+    }
+
+    group.finish();
+}
+
+criterion_group!(benches, bench_fan_out, bench_app_context);
+criterion_main!(benches);
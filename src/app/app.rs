@@ -1,31 +1,60 @@
 use std::{collections::HashMap, sync::Arc};
 
-use my_nosql_contracts::{
-    price_src::BidAskPriceSrc, InstrumentMappingEntity, ProductSettings, YbPriceFeedSettings,
-};
+use my_nosql_contracts::{price_src::BidAskPriceSrc, YbPriceFeedSettings};
 
 use my_tcp_sockets::TcpClientSocketSettings;
+use rust_extensions::date_time::DateTimeAsMicroseconds;
 use service_sdk::{
-    my_no_sql_sdk::{
-        data_writer::{CreateTableParams, MyNoSqlDataWriter},
-        reader::MyNoSqlDataReaderTcp,
-    },
+    my_logger::LogEventCtx,
+    my_no_sql_sdk::data_writer::{CreateTableParams, MyNoSqlDataWriter},
     ServiceContext,
 };
 use tokio::sync::Mutex;
 
-use crate::{settings::SettingsReader, your_bourse::YbMarketData};
+use crate::{
+    fix_journal::FixJournal,
+    nosql::{BidAskCandleNoSqlEntity, BidAskRawPriceSrcNoSqlEntity},
+    settings::{InstrumentSettingsModel, SettingsReader, SyntheticInstrumentSettingsModel},
+    tick_recorder::TickRecorder,
+    your_bourse::YbMarketData,
+    FixSocketConnection,
+};
 
-use super::{BroadCastData, PriceCache};
+use super::{
+    BroadCastData, CandlesAggregator, ClockSkewEstimator, CrossedQuoteAction, FixSessionStats,
+    ForcedReconnectsLog, LastPriceStore, LatencyStats, NoSqlPriceFeedSource, PriceFeedSource,
+    QuoteActivityTracker, QuoteCorrectionsLog, SpikeCheckResult, SpikeFilter, SymbolLatencySummary,
+    SyntheticPrice, SyntheticSkipReason,
+};
+
+struct CalculatedSynthetic {
+    instrument_id: String,
+    settings: SyntheticInstrumentSettingsModel,
+    price: SyntheticPrice,
+    instrument_settings: Option<InstrumentSettingsModel>,
+}
 
 pub struct AppContext {
     pub broadcast_data: Mutex<BroadCastData>,
     pub bid_ask_price_src: MyNoSqlDataWriter<BidAskPriceSrc>,
     //pub tcp_client: TcpClient,
-    pub product_settings: Arc<MyNoSqlDataReaderTcp<ProductSettings>>,
-    pub instrument_mapping: Arc<MyNoSqlDataReaderTcp<InstrumentMappingEntity>>,
-    pub prices_cache: PriceCache,
-    settings_reader: Arc<SettingsReader>,
+    pub feed_source: Arc<dyn PriceFeedSource>,
+    pub last_prices: LastPriceStore,
+    pub raw_bid_ask_price_src: MyNoSqlDataWriter<BidAskRawPriceSrcNoSqlEntity>,
+    pub candles: CandlesAggregator,
+    pub bid_ask_candles: MyNoSqlDataWriter<BidAskCandleNoSqlEntity>,
+    pub fix_journal: Arc<FixJournal>,
+    pub fix_connection: Mutex<Option<Arc<FixSocketConnection>>>,
+    pub fix_session_stats: FixSessionStats,
+    pub quote_activity: QuoteActivityTracker,
+    pub forced_reconnects: ForcedReconnectsLog,
+    pub latency_stats: LatencyStats,
+    // Summary of the last finished latency window. Served by /api/latency
+    pub last_latency_report: Mutex<Vec<SymbolLatencySummary>>,
+    pub clock_skew: Arc<ClockSkewEstimator>,
+    pub spike_filter: SpikeFilter,
+    pub quote_corrections: QuoteCorrectionsLog,
+    pub settings_reader: Arc<SettingsReader>,
     pub lp_id: String,
 }
 
@@ -33,6 +62,19 @@ impl AppContext {
     pub async fn new(
         settings_reader: Arc<SettingsReader>,
         service_content: &ServiceContext,
+    ) -> AppContext {
+        let feed_source = NoSqlPriceFeedSource {
+            product_settings: service_content.get_ns_reader().await,
+            instrument_mapping: service_content.get_ns_reader().await,
+        };
+
+        Self::with_feed_source(settings_reader, Arc::new(feed_source)).await
+    }
+
+    // No nosql readers behind it. Writers only talk to nosql once the upload timers run
+    pub async fn with_feed_source(
+        settings_reader: Arc<SettingsReader>,
+        feed_source: Arc<dyn PriceFeedSource>,
     ) -> AppContext {
         let lp_id = settings_reader.get_liquidity_provider_id().await;
 
@@ -45,14 +87,56 @@ impl AppContext {
             }),
             service_sdk::my_no_sql_sdk::abstractions::DataSynchronizationPeriod::Sec5,
         );
+        let raw_bid_ask_price_src = MyNoSqlDataWriter::new(
+            settings_reader.clone(),
+            Some(CreateTableParams {
+                persist: false,
+                max_partitions_amount: None,
+                max_rows_per_partition_amount: None,
+            }),
+            service_sdk::my_no_sql_sdk::abstractions::DataSynchronizationPeriod::Sec5,
+        );
+
+        // Partition per instrument and candle type. Keeps a day of minute candles
+        let bid_ask_candles = MyNoSqlDataWriter::new(
+            settings_reader.clone(),
+            Some(CreateTableParams {
+                persist: true,
+                max_partitions_amount: None,
+                max_rows_per_partition_amount: Some(1440),
+            }),
+            service_sdk::my_no_sql_sdk::abstractions::DataSynchronizationPeriod::Sec5,
+        );
+
         //  let tcp_client = TcpClient::new("yourbourse - fix-client".to_string(), settings.clone());
 
+        let fix_journal = FixJournal::new(settings_reader.get_fix_journal_settings().await);
+        let clock_skew_settings = settings_reader.get_clock_skew_settings().await;
+
+        let mut broadcast_data = BroadCastData::new(lp_id.clone());
+        broadcast_data.tick_recorder = settings_reader
+            .get_tick_recorder_settings()
+            .await
+            .map(TickRecorder::new);
+
         AppContext {
-            lp_id: lp_id.clone(),
-            broadcast_data: Mutex::new(BroadCastData::new(lp_id)),
-            product_settings: service_content.get_ns_reader().await,
-            instrument_mapping: service_content.get_ns_reader().await,
-            prices_cache: PriceCache::new(),
+            lp_id,
+            broadcast_data: Mutex::new(broadcast_data),
+            feed_source,
+            last_prices: LastPriceStore::new(),
+            raw_bid_ask_price_src,
+            candles: CandlesAggregator::new(),
+            bid_ask_candles,
+            fix_journal: Arc::new(fix_journal),
+            fix_connection: Mutex::new(None),
+            fix_session_stats: FixSessionStats::default(),
+            quote_activity: QuoteActivityTracker::new(),
+            forced_reconnects: ForcedReconnectsLog::new(),
+            latency_stats: LatencyStats::new(),
+            last_latency_report: Mutex::new(Vec::new()),
+            clock_skew: Arc::new(ClockSkewEstimator::new(clock_skew_settings.window_sec)),
+            spike_filter: SpikeFilter::new(),
+            quote_corrections: QuoteCorrectionsLog::new(),
             bid_ask_price_src,
             settings_reader,
         }
@@ -66,41 +150,281 @@ impl AppContext {
 
         println!("Found YouBourse configuration in settings app");
 
-        self.product_settings.get_enum_case_model().await
+        self.feed_source.get_yb_settings().await
     }
 
     pub async fn broad_cast_bid_ask(&self, market_data: YbMarketData) {
-        let broadcast_data = self.broadcast_data.lock().await;
-        if let Some(instruments) = broadcast_data.broad_cast_bid_ask(&market_data).await {
-            let mut to_upload = Vec::with_capacity(instruments.len());
-            for instrument_id in instruments {
-                let price_src = BidAskPriceSrc {
-                    partition_key: self.lp_id.clone(),
-                    row_key: instrument_id.clone(),
-                    src_id: market_data.instrument_id.clone(),
-                    time_stamp: "".to_string(),
-                    bid: market_data.bid,
-                    ask: market_data.ask,
-                    dt: market_data.date.to_rfc3339(),
-                };
-
-                to_upload.push(price_src);
+        self.broad_cast_bid_ask_at(market_data, DateTimeAsMicroseconds::now())
+            .await;
+    }
+
+    // Synthetic leg ages, spike windows and latency are measured against now.
+    // Replays pass the recorded receive time so old journals behave as they did live
+    pub async fn broad_cast_bid_ask_at(
+        &self,
+        mut market_data: YbMarketData,
+        now: DateTimeAsMicroseconds,
+    ) {
+        let started = std::time::Instant::now();
+
+        if market_data.date_source.is_venue_time() {
+            self.correct_clock_skew(&mut market_data).await;
+        }
+
+        if !self.apply_crossed_quote_policy(&mut market_data).await {
+            return;
+        }
+
+        if !self.pass_spike_filter(&market_data, now).await {
+            return;
+        }
+
+        self.last_prices.update_external(&market_data).await;
+
+        let mapped_instruments = {
+            let broadcast_data = self.broadcast_data.lock().await;
+            broadcast_data
+                .get_mapped_instruments(market_data.instrument_id.as_str())
+                .cloned()
+        };
+
+        let instruments_settings = match mapped_instruments.as_ref() {
+            Some(instruments) => {
+                self.settings_reader
+                    .get_instruments_settings(instruments)
+                    .await
+            }
+            None => HashMap::new(),
+        };
+
+        let synthetics = self.calculate_synthetics(&market_data, now).await;
+
+        if mapped_instruments.is_none() && synthetics.is_empty() {
+            return;
+        }
+
+        // Only pushes into subscriber queues below. No client I/O under the lock
+        let mut published = Vec::new();
+
+        {
+            let mut broadcast_data = self.broadcast_data.lock().await;
+
+            if let Some(items) =
+                broadcast_data.broad_cast_bid_ask(&market_data, &instruments_settings, now)
+            {
+                published.extend(items);
+            }
+
+            for synthetic in synthetics {
+                let item = broadcast_data.broad_cast_synthetic(
+                    synthetic.instrument_id.as_str(),
+                    &synthetic.settings,
+                    &synthetic.price,
+                    market_data.date_source,
+                    synthetic.instrument_settings.as_ref(),
+                    now,
+                );
+
+                if let Some(item) = item {
+                    published.push(item);
+                }
+            }
+
+            // Under the broadcast lock so the stale timer sees the store and clients in sync
+            self.last_prices
+                .update_instruments(&published, market_data.receive_time)
+                .await;
+        }
+
+        if !published.is_empty() {
+            let publish_time = DateTimeAsMicroseconds::new(
+                now.unix_microseconds + started.elapsed().as_micros() as i64,
+            );
+
+            self.latency_stats.record(&market_data, publish_time).await;
+
+            self.candles.on_prices(&published, now).await;
+        }
+    }
+
+    async fn calculate_synthetics(
+        &self,
+        market_data: &YbMarketData,
+        now: DateTimeAsMicroseconds,
+    ) -> Vec<CalculatedSynthetic> {
+        let synthetics = self
+            .settings_reader
+            .get_synthetic_instruments_by_leg(market_data.instrument_id.as_str())
+            .await;
+
+        let mut result = Vec::with_capacity(synthetics.len());
+
+        for (instrument_id, synthetic) in synthetics {
+            let synthetic_price = match self.last_prices.calculate_synthetic(&synthetic, now).await
+            {
+                Ok(synthetic_price) => synthetic_price,
+                // Missing and stale legs are expected after (re)connect. Watchdogs report silence
+                Err(SyntheticSkipReason::NoQuote(_))
+                | Err(SyntheticSkipReason::StaleLeg { .. }) => continue,
+                Err(SyntheticSkipReason::Invalid(err)) => {
+                    service_sdk::my_logger::LOGGER.write_warning(
+                        String::from("SyntheticInstruments"),
+                        format!("Can not calculate {}. {}", instrument_id, err),
+                        LogEventCtx::new().add("instrumentId", instrument_id.as_str()),
+                    );
+                    continue;
+                }
+            };
+
+            let mut instrument_settings = self
+                .settings_reader
+                .get_instruments_settings(&[instrument_id.clone()])
+                .await;
+
+            result.push(CalculatedSynthetic {
+                instrument_settings: instrument_settings.remove(&instrument_id),
+                instrument_id,
+                settings: synthetic,
+                price: synthetic_price,
+            });
+        }
+
+        result
+    }
+
+    async fn correct_clock_skew(&self, market_data: &mut YbMarketData) {
+        let settings = self.settings_reader.get_clock_skew_settings().await;
+
+        if !settings.correct_quote_time {
+            return;
+        }
+
+        let correction = self
+            .clock_skew
+            .get_correction_micros(settings.warn_threshold_ms)
+            .await;
+
+        if let Some(skew_micros) = correction {
+            market_data.date =
+                DateTimeAsMicroseconds::new(market_data.date.unix_microseconds + skew_micros);
+        }
+    }
+
+    // Returns false if the quote has to be dropped
+    async fn apply_crossed_quote_policy(&self, market_data: &mut YbMarketData) -> bool {
+        let policy = self
+            .settings_reader
+            .get_crossed_quote_policy(market_data.instrument_id.as_str())
+            .await;
+
+        let correction = match super::apply_crossed_quote_policy(&policy, market_data) {
+            Some(correction) => correction,
+            None => return true,
+        };
+
+        let message = format!(
+            "{:?} quote on {} bid:{} ask:{}. Applied action: {:?}",
+            correction.kind,
+            correction.external_symbol,
+            correction.original_bid,
+            correction.original_ask,
+            correction.action
+        );
+
+        let ctx = LogEventCtx::new().add("externalSymbol", correction.external_symbol.as_str());
+
+        if correction.action == CrossedQuoteAction::DropAndAlert {
+            service_sdk::my_logger::LOGGER.write_error(String::from("CrossedQuotes"), message, ctx);
+        } else {
+            service_sdk::my_logger::LOGGER.write_info(String::from("CrossedQuotes"), message, ctx);
+        }
+
+        let is_dropped = correction.is_dropped();
+        self.quote_corrections.add(correction).await;
+
+        !is_dropped
+    }
+
+    async fn pass_spike_filter(
+        &self,
+        market_data: &YbMarketData,
+        now: DateTimeAsMicroseconds,
+    ) -> bool {
+        let rule = self
+            .settings_reader
+            .get_spike_filter_rule(market_data.instrument_id.as_str())
+            .await;
+
+        let rule = match rule {
+            Some(rule) => rule,
+            None => return true,
+        };
+
+        let result = self
+            .spike_filter
+            .check(
+                market_data.instrument_id.as_str(),
+                &rule,
+                market_data.bid,
+                market_data.ask,
+                now,
+            )
+            .await;
+
+        match result {
+            SpikeCheckResult::Accepted => true,
+            SpikeCheckResult::Confirmed => {
+                service_sdk::my_logger::LOGGER.write_warning(
+                    String::from("SpikeFilter"),
+                    format!(
+                        "{} moved to a new level bid:{} ask:{} confirmed by the following quotes",
+                        market_data.instrument_id, market_data.bid, market_data.ask
+                    ),
+                    LogEventCtx::new().add("externalSymbol", market_data.instrument_id.as_str()),
+                );
+                true
             }
+            SpikeCheckResult::Suppressed {
+                move_percent,
+                move_pips,
+            } => {
+                let not_logged = self
+                    .spike_filter
+                    .try_log_suppressed(market_data.instrument_id.as_str(), now)
+                    .await;
+
+                if let Some(not_logged) = not_logged {
+                    service_sdk::my_logger::LOGGER.write_warning(
+                        String::from("SpikeFilter"),
+                        format!(
+                            "Suppressed spike on {} bid:{} ask:{}. Move: {:.4}% / {:.1} pips. {} more suppressed since the last warning",
+                            market_data.instrument_id,
+                            market_data.bid,
+                            market_data.ask,
+                            move_percent,
+                            move_pips,
+                            not_logged
+                        ),
+                        LogEventCtx::new()
+                            .add("externalSymbol", market_data.instrument_id.as_str()),
+                    );
+                }
 
-            self.prices_cache.update(to_upload.into_iter()).await;
+                false
+            }
         }
     }
 
     pub async fn get_map(&self) -> HashMap<String, Vec<String>> {
-        let map_entity = self
-            .instrument_mapping
-            .get_entity(InstrumentMappingEntity::PARTITION_KEY, self.lp_id.as_str())
+        let instrument_map = self
+            .feed_source
+            .get_instrument_map(self.lp_id.as_str())
             .await
             .unwrap();
 
         let mut map = HashMap::<String, Vec<String>>::new();
 
-        for (our_symbol, external_symbol) in map_entity.map.iter() {
+        for (our_symbol, external_symbol) in instrument_map.iter() {
             if !map.contains_key(external_symbol.as_str()) {
                 map.insert(external_symbol.to_string(), Vec::new());
             }
@@ -119,12 +443,12 @@ impl AppContext {
 #[async_trait::async_trait]
 impl TcpClientSocketSettings for AppContext {
     async fn get_host_port(&self) -> Option<String> {
-        let map_entity = self
-            .instrument_mapping
-            .get_entity(InstrumentMappingEntity::PARTITION_KEY, self.lp_id.as_str())
+        let instrument_map = self
+            .feed_source
+            .get_instrument_map(self.lp_id.as_str())
             .await;
 
-        if map_entity.is_none() {
+        if instrument_map.is_none() {
             println!("There is no Map configuration. Skipping connection to Fix YourBourse.");
             return None;
         }
@@ -1,56 +1,548 @@
 use std::{collections::HashMap, sync::Arc};
 
-use prices_tcp_contracts::{BidAskDataTcpModel, BidAskDateTimeTcpModel, BidAskTcpMessage};
+use rust_extensions::date_time::DateTimeAsMicroseconds;
+use service_sdk::my_logger::LogEventCtx;
 
-use crate::{your_bourse::YbMarketData, BidAskTcpSocketConnection};
+use crate::{
+    settings::{InstrumentSettingsModel, SyntheticInstrumentSettingsModel},
+    tick_recorder::TickRecorder,
+    your_bourse::{QuoteTimeSource, YbMarketData},
+};
+
+use super::{
+    ClientSubscription, Conflator, LogThrottle, OutboundMessage, OutboundTick, PriceSubscriber,
+    PriceSubscriberStatus, PublishedBidAsk, SyntheticPrice, SUBSCRIBE_ALL,
+};
 
 pub struct BroadCastData {
     pub maps: HashMap<String, Vec<String>>,
-    pub connections: HashMap<i32, Arc<BidAskTcpSocketConnection>>,
+    subscribers: HashMap<i32, Arc<PriceSubscriber>>,
+    // Subscribers without an entry receive every instrument
+    pub subscriptions: HashMap<i32, ClientSubscription>,
+    // Last tick sent per instrument. Source of snapshots for new subscribers
+    last_ticks: HashMap<String, Arc<OutboundTick>>,
+    // Stale instrument -> time of its last update
+    stale_instruments: HashMap<String, DateTimeAsMicroseconds>,
     pub lp_id: String,
+    conflator: Conflator,
+    // Records every tick sent to subscribers. None - recording is off
+    pub tick_recorder: Option<TickRecorder>,
+    // Misconfigured pricing fails on every quote. Keeps the log readable
+    skipped_price_log: LogThrottle,
 }
 
+const SKIPPED_PRICE_LOG_INTERVAL_SEC: i64 = 10;
+
 impl BroadCastData {
     pub fn new(lp_id: String) -> Self {
         Self {
             maps: HashMap::new(),
-            connections: HashMap::new(),
+            subscribers: HashMap::new(),
+            subscriptions: HashMap::new(),
+            last_ticks: HashMap::new(),
+            stale_instruments: HashMap::new(),
             lp_id,
+            conflator: Conflator::new(),
+            tick_recorder: None,
+            skipped_price_log: LogThrottle::new(SKIPPED_PRICE_LOG_INTERVAL_SEC),
         }
     }
 
-    pub async fn broad_cast_bid_ask(&self, market_data: &YbMarketData) -> Option<Vec<String>> {
-        let map = self.maps.get(market_data.instrument_id.as_str());
+    pub fn add_subscriber(&mut self, subscriber: Arc<PriceSubscriber>) {
+        self.subscribers.insert(subscriber.id, subscriber);
+    }
 
-        if map.is_none() {
-            return None;
+    pub fn remove_subscriber(&mut self, subscriber_id: i32) {
+        if let Some(subscriber) = self.subscribers.remove(&subscriber_id) {
+            subscriber.close();
         }
 
-        let map = map.unwrap();
+        self.subscriptions.remove(&subscriber_id);
+    }
+
+    // Sends a snapshot of the requested instruments. "*" - of every subscribed one
+    pub fn subscribe(&mut self, subscriber_id: i32, instruments: &[String]) {
+        let first_control_line = self.enable_control_messages(subscriber_id);
+
+        self.subscriptions
+            .entry(subscriber_id)
+            .or_default()
+            .subscribe(instruments);
+
+        if first_control_line || instruments.iter().any(|id| id == SUBSCRIBE_ALL) {
+            self.send_snapshot(subscriber_id, None);
+        } else {
+            self.send_snapshot(subscriber_id, Some(instruments));
+        }
+    }
+
+    pub fn unsubscribe(&mut self, subscriber_id: i32, instruments: &[String]) {
+        let first_control_line = self.enable_control_messages(subscriber_id);
+
+        self.subscriptions
+            .entry(subscriber_id)
+            .or_default()
+            .unsubscribe(instruments);
+
+        if first_control_line {
+            self.send_snapshot(subscriber_id, None);
+        }
+    }
+
+    // A client sending control lines understands them in return. The snapshot it got on connect
+    // was unmarked, so the first control line is answered with a marked snapshot of the whole
+    // subscription. true - this was the first control line
+    fn enable_control_messages(&self, subscriber_id: i32) -> bool {
+        match self.subscribers.get(&subscriber_id) {
+            Some(subscriber) => subscriber.enable_control_messages(),
+            None => false,
+        }
+    }
+
+    pub fn get_subscribers(&self) -> Vec<Arc<PriceSubscriber>> {
+        self.subscribers.values().cloned().collect()
+    }
+
+    pub fn get_subscribers_status(
+        &self,
+        now: DateTimeAsMicroseconds,
+    ) -> Vec<PriceSubscriberStatus> {
+        self.subscribers
+            .values()
+            .map(|subscriber| subscriber.get_status(now))
+            .collect()
+    }
+
+    pub fn get_mapped_instruments(&self, external_symbol: &str) -> Option<&Vec<String>> {
+        let map = self.maps.get(external_symbol)?;
 
         if map.len() == 0 {
             return None;
         }
 
-        for instrument_id in map {
-            let tcp_date_time = BidAskDateTimeTcpModel::Source(market_data.date);
+        Some(map)
+    }
+
+    pub fn broad_cast_bid_ask(
+        &mut self,
+        market_data: &YbMarketData,
+        instruments_settings: &HashMap<String, InstrumentSettingsModel>,
+        now: DateTimeAsMicroseconds,
+    ) -> Option<Vec<PublishedBidAsk>> {
+        let map = self
+            .get_mapped_instruments(market_data.instrument_id.as_str())?
+            .clone();
+
+        let mut result = Vec::with_capacity(map.len());
+
+        for instrument_id in map.iter() {
+            let instrument_settings = instruments_settings.get(instrument_id);
+
+            let price = super::calculate_instrument_price(
+                instrument_settings,
+                market_data.bid,
+                market_data.ask,
+            );
+
+            let price = match price {
+                Ok(price) => price,
+                Err(err) => {
+                    self.log_skipped_price(instrument_id, err, market_data.receive_time);
+                    continue;
+                }
+            };
+
+            let tick = OutboundTick {
+                instrument_id: instrument_id.to_string(),
+                bid: price.bid,
+                ask: price.ask,
+                date: market_data.date,
+                date_source: market_data.date_source,
+                receive_time: market_data.receive_time,
+                bid_size: market_data.bid_size,
+                ask_size: market_data.ask_size,
+            };
+
+            self.publish_tick(tick, instrument_settings, now);
+
+            result.push(PublishedBidAsk {
+                instrument_id: instrument_id.to_string(),
+                src_id: market_data.instrument_id.clone(),
+                raw_bid: price.raw_bid,
+                raw_ask: price.raw_ask,
+                bid: price.bid,
+                ask: price.ask,
+                date: market_data.date,
+            });
+        }
+
+        Some(result)
+    }
+
+    pub fn broad_cast_synthetic(
+        &mut self,
+        instrument_id: &str,
+        synthetic: &SyntheticInstrumentSettingsModel,
+        synthetic_price: &SyntheticPrice,
+        date_source: QuoteTimeSource,
+        instrument_settings: Option<&InstrumentSettingsModel>,
+        now: DateTimeAsMicroseconds,
+    ) -> Option<PublishedBidAsk> {
+        let price = super::calculate_instrument_price(
+            instrument_settings,
+            synthetic_price.bid,
+            synthetic_price.ask,
+        );
+
+        let price = match price {
+            Ok(price) => price,
+            Err(err) => {
+                let err = format!("Synthetic. {}", err);
+                self.log_skipped_price(instrument_id, err, synthetic_price.receive_time);
+                return None;
+            }
+        };
+
+        let tick = OutboundTick {
+            instrument_id: instrument_id.to_string(),
+            bid: price.bid,
+            ask: price.ask,
+            date: synthetic_price.date,
+            date_source,
+            receive_time: synthetic_price.receive_time,
+            bid_size: None,
+            ask_size: None,
+        };
+
+        self.publish_tick(tick, instrument_settings, now);
+
+        let src_id: Vec<&str> = synthetic
+            .legs
+            .iter()
+            .map(|leg| leg.external_symbol.as_str())
+            .collect();
+
+        Some(PublishedBidAsk {
+            instrument_id: instrument_id.to_string(),
+            src_id: src_id.join(","),
+            raw_bid: price.raw_bid,
+            raw_ask: price.raw_ask,
+            bid: price.bid,
+            ask: price.ask,
+            date: synthetic_price.date,
+        })
+    }
+
+    // Sends ticks held back by conflation whose interval is over
+    pub fn flush_conflated(&mut self, now: DateTimeAsMicroseconds) {
+        for tick in self.conflator.flush_due(now) {
+            self.send_bid_ask(tick);
+        }
+    }
+
+    fn publish_tick(
+        &mut self,
+        tick: OutboundTick,
+        instrument_settings: Option<&InstrumentSettingsModel>,
+        now: DateTimeAsMicroseconds,
+    ) {
+        // Before conflation. An unchanged price still proves the instrument is live
+        self.set_active(tick.instrument_id.as_str());
+
+        let conflation = instrument_settings.and_then(|settings| settings.conflation.as_ref());
+
+        if let Some(tick) = self.conflator.on_tick(conflation, tick, now) {
+            self.send_bid_ask(tick);
+        }
+    }
+
+    // Notifies subscribers once. The next quote of the instrument makes it active again,
+    // even if conflation drops it
+    pub fn set_stale(
+        &mut self,
+        instrument_id: &str,
+        last_update: DateTimeAsMicroseconds,
+        now: DateTimeAsMicroseconds,
+    ) {
+        if self.stale_instruments.contains_key(instrument_id) {
+            return;
+        }
+
+        self.stale_instruments
+            .insert(instrument_id.to_string(), last_update);
 
-            let tcp_message = BidAskDataTcpModel {
-                exchange_id: self.lp_id.clone(),
+        self.push_to_subscribed(
+            instrument_id,
+            OutboundMessage::Stale {
                 instrument_id: instrument_id.to_string(),
-                bid: market_data.bid,
-                ask: market_data.ask,
-                volume: 0.0,
-                date_time: tcp_date_time,
+                age_ms: (now.unix_microseconds - last_update.unix_microseconds) / 1000,
+            },
+        );
+    }
+
+    // Last known prices framed by SNAPSHOT_BEGIN/SNAPSHOT_END. None - every subscribed instrument.
+    // Clients which did not send a control line yet get the prices only, unmarked.
+    // See PriceTcpContract
+    pub fn send_snapshot(&self, subscriber_id: i32, instruments: Option<&[String]>) {
+        let subscriber = match self.subscribers.get(&subscriber_id) {
+            Some(subscriber) => subscriber,
+            None => return,
+        };
+
+        let subscription = self.subscriptions.get(&subscriber_id);
+        let now = DateTimeAsMicroseconds::now();
+
+        subscriber.push(OutboundMessage::SnapshotBegin);
+
+        for tick in self.last_ticks.values() {
+            let is_requested = match instruments {
+                Some(instruments) => instruments.contains(&tick.instrument_id),
+                None => subscription.map_or(true, |subscription| {
+                    subscription.is_subscribed(tick.instrument_id.as_str())
+                }),
             };
 
-            let to_send = BidAskTcpMessage::BidAsk(tcp_message);
+            if !is_requested {
+                continue;
+            }
+
+            subscriber.push(OutboundMessage::Tick(tick.clone()));
 
-            for connection in self.connections.values() {
-                connection.send(&to_send).await;
+            if let Some(last_update) = self.stale_instruments.get(&tick.instrument_id) {
+                subscriber.push(OutboundMessage::Stale {
+                    instrument_id: tick.instrument_id.to_string(),
+                    age_ms: (now.unix_microseconds - last_update.unix_microseconds) / 1000,
+                });
             }
         }
 
-        Some(map.to_vec())
+        subscriber.push(OutboundMessage::SnapshotEnd);
+    }
+
+    fn log_skipped_price(&mut self, instrument_id: &str, err: String, now: DateTimeAsMicroseconds) {
+        let not_logged = match self.skipped_price_log.try_log(instrument_id, now) {
+            Some(not_logged) => not_logged,
+            None => return,
+        };
+
+        service_sdk::my_logger::LOGGER.write_warning(
+            String::from("BroadCastData"),
+            format!(
+                "Skipping {} price. {}. {} more skipped since the last warning",
+                instrument_id, err, not_logged
+            ),
+            LogEventCtx::new().add("instrumentId", instrument_id),
+        );
+    }
+
+    fn set_active(&mut self, instrument_id: &str) {
+        if self.stale_instruments.remove(instrument_id).is_some() {
+            self.push_to_subscribed(
+                instrument_id,
+                OutboundMessage::Active {
+                    instrument_id: instrument_id.to_string(),
+                },
+            );
+        }
+    }
+
+    fn send_bid_ask(&mut self, tick: OutboundTick) {
+        let tick = Arc::new(tick);
+
+        if let Some(tick_recorder) = self.tick_recorder.as_ref() {
+            tick_recorder.write(tick.clone());
+        }
+
+        self.push_to_subscribed(
+            tick.instrument_id.as_str(),
+            OutboundMessage::Tick(tick.clone()),
+        );
+
+        self.last_ticks.insert(tick.instrument_id.to_string(), tick);
+    }
+
+    fn push_to_subscribed(&self, instrument_id: &str, message: OutboundMessage) {
+        for (subscriber_id, subscriber) in self.subscribers.iter() {
+            if let Some(subscription) = self.subscriptions.get(subscriber_id) {
+                if !subscription.is_subscribed(instrument_id) {
+                    continue;
+                }
+            }
+
+            subscriber.push(message.clone());
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{collections::HashMap, sync::Arc};
+
+    use rust_extensions::date_time::DateTimeAsMicroseconds;
+
+    use super::BroadCastData;
+    use crate::{
+        app::{OutboundMessage, PriceSubscriber},
+        settings::{ConflationSettingsModel, InstrumentSettingsModel},
+        your_bourse::{QuoteTimeSource, YbMarketData},
+    };
+
+    fn market_data(bid: f64, ask: f64) -> YbMarketData {
+        let now = DateTimeAsMicroseconds::now();
+
+        YbMarketData {
+            instrument_id: "EUR/USD".to_string(),
+            date: now,
+            date_source: QuoteTimeSource::ReceiveTime,
+            bid,
+            ask,
+            bid_size: None,
+            ask_size: None,
+            sending_time: now,
+            entry_time: None,
+            receive_time: now,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_unchanged_tick_after_stale_sends_active() {
+        let mut broadcast_data = BroadCastData::new("TEST".to_string());
+        broadcast_data
+            .maps
+            .insert("EUR/USD".to_string(), vec!["EURUSD".to_string()]);
+
+        let subscriber = Arc::new(PriceSubscriber::new(1, "test".to_string(), 10, 20));
+        subscriber.enable_control_messages();
+        broadcast_data.add_subscriber(subscriber.clone());
+
+        let mut instruments_settings = HashMap::new();
+        instruments_settings.insert(
+            "EURUSD".to_string(),
+            InstrumentSettingsModel {
+                transform: None,
+                markup: None,
+                precision: None,
+                conflation: Some(ConflationSettingsModel {
+                    max_updates_per_sec: None,
+                    drop_unchanged: true,
+                }),
+            },
+        );
+
+        broadcast_data.broad_cast_bid_ask(
+            &market_data(1.1, 1.2),
+            &instruments_settings,
+            DateTimeAsMicroseconds::now(),
+        );
+
+        let now = DateTimeAsMicroseconds::now();
+        broadcast_data.set_stale("EURUSD", now, now);
+
+        // Dropped by conflation, but the instrument is live again
+        broadcast_data.broad_cast_bid_ask(
+            &market_data(1.1, 1.2),
+            &instruments_settings,
+            DateTimeAsMicroseconds::now(),
+        );
+
+        let batch = subscriber.next_batch(10).await.unwrap();
+        assert_eq!(batch.len(), 3);
+        assert!(matches!(&batch[0], OutboundMessage::Tick(_)));
+        assert!(matches!(&batch[1], OutboundMessage::Stale { .. }));
+        assert!(matches!(&batch[2], OutboundMessage::Active { .. }));
+
+        // Stale again on the next timeout, and the snapshot is not marked stale
+        broadcast_data.send_snapshot(1, None);
+        let batch = subscriber.next_batch(10).await.unwrap();
+        assert!(!batch
+            .iter()
+            .any(|message| matches!(message, OutboundMessage::Stale { .. })));
+
+        broadcast_data.set_stale("EURUSD", now, now);
+        let batch = subscriber.next_batch(10).await.unwrap();
+        assert!(matches!(&batch[0], OutboundMessage::Stale { .. }));
+    }
+
+    #[tokio::test]
+    async fn test_first_control_line_gets_marked_snapshot() {
+        let mut broadcast_data = BroadCastData::new("TEST".to_string());
+        broadcast_data
+            .maps
+            .insert("EUR/USD".to_string(), vec!["EURUSD".to_string()]);
+
+        broadcast_data.broad_cast_bid_ask(
+            &market_data(1.1, 1.2),
+            &HashMap::new(),
+            DateTimeAsMicroseconds::now(),
+        );
+
+        let now = DateTimeAsMicroseconds::now();
+        broadcast_data.set_stale("EURUSD", now, now);
+
+        let subscriber = Arc::new(PriceSubscriber::new(1, "test".to_string(), 10, 20));
+        broadcast_data.add_subscriber(subscriber.clone());
+
+        // On connect
+        broadcast_data.send_snapshot(1, None);
+        let batch = subscriber.next_batch(10).await.unwrap();
+        assert_eq!(batch.len(), 1);
+        assert!(matches!(&batch[0], OutboundMessage::Tick(_)));
+
+        broadcast_data.unsubscribe(1, &["GBPUSD".to_string()]);
+        let batch = subscriber.next_batch(10).await.unwrap();
+        assert_eq!(batch.len(), 4);
+        assert!(matches!(&batch[0], OutboundMessage::SnapshotBegin));
+        assert!(matches!(&batch[1], OutboundMessage::Tick(tick) if tick.instrument_id == "EURUSD"));
+        assert!(matches!(&batch[2], OutboundMessage::Stale { .. }));
+        assert!(matches!(&batch[3], OutboundMessage::SnapshotEnd));
+
+        // Only the first control line
+        broadcast_data.unsubscribe(1, &["USDJPY".to_string()]);
+        broadcast_data.set_stale("EURUSD", now, now);
+        broadcast_data.broad_cast_bid_ask(
+            &market_data(1.3, 1.4),
+            &HashMap::new(),
+            DateTimeAsMicroseconds::now(),
+        );
+        let batch = subscriber.next_batch(10).await.unwrap();
+        assert!(matches!(&batch[0], OutboundMessage::Active { .. }));
+    }
+
+    #[tokio::test]
+    async fn test_conflation_runs_on_the_callers_clock() {
+        let mut broadcast_data = BroadCastData::new("TEST".to_string());
+        broadcast_data
+            .maps
+            .insert("EUR/USD".to_string(), vec!["EURUSD".to_string()]);
+
+        let subscriber = Arc::new(PriceSubscriber::new(1, "test".to_string(), 10, 20));
+        broadcast_data.add_subscriber(subscriber.clone());
+
+        let mut instruments_settings = HashMap::new();
+        instruments_settings.insert(
+            "EURUSD".to_string(),
+            InstrumentSettingsModel {
+                transform: None,
+                markup: None,
+                precision: None,
+                conflation: Some(ConflationSettingsModel {
+                    max_updates_per_sec: Some(1),
+                    drop_unchanged: false,
+                }),
+            },
+        );
+
+        // Replayed ticks two seconds apart are both sent, even though the wall clock barely moved
+        let now = DateTimeAsMicroseconds::now();
+        broadcast_data.broad_cast_bid_ask(&market_data(1.1, 1.2), &instruments_settings, now);
+        broadcast_data.broad_cast_bid_ask(
+            &market_data(1.3, 1.4),
+            &instruments_settings,
+            DateTimeAsMicroseconds::new(now.unix_microseconds + 2_000_000),
+        );
+
+        let batch = subscriber.next_batch(10).await.unwrap();
+        assert_eq!(batch.len(), 2);
+        assert!(matches!(&batch[1], OutboundMessage::Tick(tick) if tick.bid == 1.3));
     }
 }
@@ -0,0 +1,406 @@
+use std::collections::{HashMap, HashSet};
+
+use rust_extensions::date_time::DateTimeAsMicroseconds;
+use tokio::sync::Mutex;
+
+use super::PublishedBidAsk;
+
+// Ticks of one instrument come slightly out of order. Candle is closed this long after its end
+const CLOSE_DELAY_MICROS: i64 = 2_000_000;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum CandleType {
+    Minute,
+    FiveMinutes,
+    Hour,
+    Day,
+}
+
+impl CandleType {
+    pub const ALL: [CandleType; 4] = [
+        CandleType::Minute,
+        CandleType::FiveMinutes,
+        CandleType::Hour,
+        CandleType::Day,
+    ];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            CandleType::Minute => "1m",
+            CandleType::FiveMinutes => "5m",
+            CandleType::Hour => "1h",
+            CandleType::Day => "1d",
+        }
+    }
+
+    pub fn get_duration_micros(&self) -> i64 {
+        match self {
+            CandleType::Minute => 60_000_000,
+            CandleType::FiveMinutes => 5 * 60_000_000,
+            CandleType::Hour => 60 * 60_000_000,
+            CandleType::Day => 24 * 60 * 60_000_000,
+        }
+    }
+
+    // Buckets are aligned to UTC
+    pub fn get_bucket_start(&self, date: DateTimeAsMicroseconds) -> DateTimeAsMicroseconds {
+        let duration = self.get_duration_micros();
+        DateTimeAsMicroseconds::new(date.unix_microseconds.div_euclid(duration) * duration)
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Ohlc {
+    pub open: f64,
+    pub high: f64,
+    pub low: f64,
+    pub close: f64,
+}
+
+impl Ohlc {
+    fn new(price: f64) -> Self {
+        Self {
+            open: price,
+            high: price,
+            low: price,
+            close: price,
+        }
+    }
+
+    fn update(&mut self, price: f64) {
+        self.high = self.high.max(price);
+        self.low = self.low.min(price);
+        self.close = price;
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct BidAskCandle {
+    pub instrument_id: String,
+    pub candle_type: CandleType,
+    pub start: DateTimeAsMicroseconds,
+    pub bid: Ohlc,
+    pub ask: Ohlc,
+    pub ticks: u64,
+    pub is_closed: bool,
+}
+
+impl BidAskCandle {
+    pub fn get_end(&self) -> DateTimeAsMicroseconds {
+        DateTimeAsMicroseconds::new(
+            self.start.unix_microseconds + self.candle_type.get_duration_micros(),
+        )
+    }
+}
+
+type CandleKey = (String, CandleType);
+
+// Latest venue time of an instrument and when we got it
+struct VenueClock {
+    date: DateTimeAsMicroseconds,
+    received_at: DateTimeAsMicroseconds,
+}
+
+impl VenueClock {
+    // Venue time moved on by what passed on our clock since the latest tick.
+    // Keeps closing candles of a quiet instrument on the clock they are bucketed by
+    fn get_now(&self, now: DateTimeAsMicroseconds) -> i64 {
+        let elapsed = (now.unix_microseconds - self.received_at.unix_microseconds).max(0);
+        self.date.unix_microseconds + elapsed
+    }
+}
+
+struct CandlesAggregatorInner {
+    // Last candle per instrument and type. Stays here closed until the next bucket starts
+    current: HashMap<CandleKey, BidAskCandle>,
+    // Closed candles not uploaded yet
+    closed: Vec<BidAskCandle>,
+    // In-progress candles changed since the last upload
+    dirty: HashSet<CandleKey>,
+    venue_clocks: HashMap<String, VenueClock>,
+}
+
+// Bid and ask candles of published prices. Buckets without ticks get no candle,
+// the first tick after a gap opens the new candle at its own price
+pub struct CandlesAggregator {
+    inner: Mutex<CandlesAggregatorInner>,
+}
+
+impl CandlesAggregator {
+    pub fn new() -> Self {
+        Self {
+            inner: Mutex::new(CandlesAggregatorInner {
+                current: HashMap::new(),
+                closed: Vec::new(),
+                dirty: HashSet::new(),
+                venue_clocks: HashMap::new(),
+            }),
+        }
+    }
+
+    pub async fn on_prices(&self, published: &[PublishedBidAsk], now: DateTimeAsMicroseconds) {
+        let mut inner = self.inner.lock().await;
+
+        for price in published {
+            inner.update_venue_clock(price, now);
+
+            for candle_type in CandleType::ALL {
+                inner.on_price(candle_type, price);
+            }
+        }
+    }
+
+    // Closes candles whose bucket is over on the venue clock of the instrument
+    // and returns everything changed since the previous call
+    pub async fn take_updates(&self, now: DateTimeAsMicroseconds) -> Vec<BidAskCandle> {
+        let mut inner = self.inner.lock().await;
+        let inner = &mut *inner;
+
+        for (key, candle) in inner.current.iter_mut() {
+            if candle.is_closed {
+                continue;
+            }
+
+            let venue_now = match inner.venue_clocks.get(&candle.instrument_id) {
+                Some(venue_clock) => venue_clock.get_now(now),
+                None => continue,
+            };
+
+            if candle.get_end().unix_microseconds + CLOSE_DELAY_MICROS <= venue_now {
+                candle.is_closed = true;
+                inner.closed.push(candle.clone());
+                inner.dirty.remove(key);
+            }
+        }
+
+        let mut result = std::mem::take(&mut inner.closed);
+
+        for key in inner.dirty.drain() {
+            if let Some(candle) = inner.current.get(&key) {
+                result.push(candle.clone());
+            }
+        }
+
+        result
+    }
+
+    // Candles go with the next upload. E.g. upload failed
+    pub async fn restore(&self, candles: Vec<BidAskCandle>) {
+        let mut inner = self.inner.lock().await;
+
+        for candle in candles {
+            if candle.is_closed {
+                inner.closed.push(candle);
+            } else {
+                inner
+                    .dirty
+                    .insert((candle.instrument_id, candle.candle_type));
+            }
+        }
+    }
+}
+
+impl CandlesAggregatorInner {
+    fn update_venue_clock(&mut self, price: &PublishedBidAsk, now: DateTimeAsMicroseconds) {
+        match self.venue_clocks.get_mut(&price.instrument_id) {
+            Some(venue_clock) => {
+                if price.date.unix_microseconds > venue_clock.date.unix_microseconds {
+                    venue_clock.date = price.date;
+                    venue_clock.received_at = now;
+                }
+            }
+            None => {
+                self.venue_clocks.insert(
+                    price.instrument_id.to_string(),
+                    VenueClock {
+                        date: price.date,
+                        received_at: now,
+                    },
+                );
+            }
+        }
+    }
+
+    fn on_price(&mut self, candle_type: CandleType, price: &PublishedBidAsk) {
+        let start = candle_type.get_bucket_start(price.date);
+        let key = (price.instrument_id.to_string(), candle_type);
+
+        if let Some(candle) = self.current.get_mut(&key) {
+            if start.unix_microseconds < candle.start.unix_microseconds {
+                // Late tick of an already replaced bucket
+                return;
+            }
+
+            if start.unix_microseconds == candle.start.unix_microseconds {
+                // Closed candle is already uploaded as final
+                if !candle.is_closed {
+                    candle.bid.update(price.bid);
+                    candle.ask.update(price.ask);
+                    candle.ticks += 1;
+                    self.dirty.insert(key);
+                }
+                return;
+            }
+        }
+
+        let candle = BidAskCandle {
+            instrument_id: price.instrument_id.to_string(),
+            candle_type,
+            start,
+            bid: Ohlc::new(price.bid),
+            ask: Ohlc::new(price.ask),
+            ticks: 1,
+            is_closed: false,
+        };
+
+        if let Some(mut previous) = self.current.insert(key.clone(), candle) {
+            if !previous.is_closed {
+                previous.is_closed = true;
+                self.closed.push(previous);
+            }
+        }
+
+        self.dirty.insert(key);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rust_extensions::date_time::DateTimeAsMicroseconds;
+
+    use super::{CandleType, CandlesAggregator};
+    use crate::app::PublishedBidAsk;
+
+    const SEC: i64 = 1_000_000;
+    const MINUTE: i64 = 60 * SEC;
+
+    fn published(bid: f64, date: i64) -> PublishedBidAsk {
+        PublishedBidAsk {
+            instrument_id: "EURUSD".to_string(),
+            src_id: "EUR/USD".to_string(),
+            raw_bid: bid,
+            raw_ask: bid + 0.0001,
+            bid,
+            ask: bid + 0.0001,
+            date: DateTimeAsMicroseconds::new(date),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_next_bucket_closes_candle() {
+        let aggregator = CandlesAggregator::new();
+
+        aggregator
+            .on_prices(
+                &[
+                    published(1.10, 10 * MINUTE),
+                    published(1.12, 10 * MINUTE + 1),
+                    published(1.09, 11 * MINUTE - 1),
+                    // Gap of two minutes. No candles in between
+                    published(1.20, 13 * MINUTE + 5),
+                ],
+                DateTimeAsMicroseconds::new(13 * MINUTE + 5),
+            )
+            .await;
+
+        let minutes: Vec<_> = aggregator
+            .take_updates(DateTimeAsMicroseconds::new(13 * MINUTE + 10))
+            .await
+            .into_iter()
+            .filter(|candle| candle.candle_type == CandleType::Minute)
+            .collect();
+
+        assert_eq!(minutes.len(), 2);
+
+        let closed = minutes.iter().find(|candle| candle.is_closed).unwrap();
+        assert_eq!(closed.start.unix_microseconds, 10 * MINUTE);
+        assert_eq!(closed.bid.open, 1.10);
+        assert_eq!(closed.bid.high, 1.12);
+        assert_eq!(closed.bid.low, 1.09);
+        assert_eq!(closed.bid.close, 1.09);
+        assert_eq!(closed.ticks, 3);
+
+        let current = minutes.iter().find(|candle| !candle.is_closed).unwrap();
+        assert_eq!(current.start.unix_microseconds, 13 * MINUTE);
+        assert_eq!(current.bid.open, 1.20);
+    }
+
+    #[tokio::test]
+    async fn test_candle_is_closed_by_time() {
+        let aggregator = CandlesAggregator::new();
+
+        aggregator
+            .on_prices(
+                &[published(1.10, 10 * MINUTE)],
+                DateTimeAsMicroseconds::new(10 * MINUTE),
+            )
+            .await;
+        aggregator
+            .take_updates(DateTimeAsMicroseconds::new(10 * MINUTE + 1))
+            .await;
+
+        let updates = aggregator
+            .take_updates(DateTimeAsMicroseconds::new(12 * MINUTE))
+            .await;
+
+        assert!(updates
+            .iter()
+            .any(|candle| candle.candle_type == CandleType::Minute && candle.is_closed));
+        assert!(updates
+            .iter()
+            .all(|candle| candle.candle_type == CandleType::Minute));
+
+        // Late tick does not reopen the uploaded candle
+        aggregator
+            .on_prices(
+                &[published(1.30, 11 * MINUTE - 1)],
+                DateTimeAsMicroseconds::new(12 * MINUTE),
+            )
+            .await;
+
+        let updates = aggregator
+            .take_updates(DateTimeAsMicroseconds::new(12 * MINUTE))
+            .await;
+
+        assert!(updates
+            .iter()
+            .all(|candle| candle.candle_type != CandleType::Minute));
+    }
+
+    #[tokio::test]
+    async fn test_candle_is_closed_on_venue_clock() {
+        let aggregator = CandlesAggregator::new();
+
+        // Venue clock is 5 sec behind ours
+        aggregator
+            .on_prices(
+                &[published(1.10, 10 * MINUTE + 30 * SEC)],
+                DateTimeAsMicroseconds::new(10 * MINUTE + 35 * SEC),
+            )
+            .await;
+
+        let updates = aggregator
+            .take_updates(DateTimeAsMicroseconds::new(11 * MINUTE + 3 * SEC))
+            .await;
+        assert!(updates.iter().all(|candle| !candle.is_closed));
+
+        // Still in time on the venue clock
+        aggregator
+            .on_prices(
+                &[published(1.12, 11 * MINUTE - 1)],
+                DateTimeAsMicroseconds::new(11 * MINUTE + 4 * SEC),
+            )
+            .await;
+
+        let updates = aggregator
+            .take_updates(DateTimeAsMicroseconds::new(11 * MINUTE + 8 * SEC))
+            .await;
+
+        let closed = updates
+            .iter()
+            .find(|candle| candle.candle_type == CandleType::Minute && candle.is_closed)
+            .unwrap();
+        assert_eq!(closed.ticks, 2);
+        assert_eq!(closed.bid.close, 1.12);
+    }
+}
@@ -0,0 +1,118 @@
+use std::collections::HashSet;
+
+pub const SUBSCRIBE_ALL: &'static str = "*";
+
+#[derive(Debug, Clone)]
+pub enum ClientSubscription {
+    // Every instrument except the listed ones. requested = false - client never sent a request
+    All {
+        except: HashSet<String>,
+        requested: bool,
+    },
+    Instruments(HashSet<String>),
+}
+
+impl Default for ClientSubscription {
+    // Clients which never subscribe receive everything, as before subscriptions existed
+    fn default() -> Self {
+        Self::All {
+            except: HashSet::new(),
+            requested: false,
+        }
+    }
+}
+
+impl ClientSubscription {
+    pub fn subscribe(&mut self, instruments: &[String]) {
+        if instruments.iter().any(|id| id == SUBSCRIBE_ALL) {
+            *self = Self::All {
+                except: HashSet::new(),
+                requested: true,
+            };
+            return;
+        }
+
+        match self {
+            // First request narrows the default "everything" down to the listed instruments
+            Self::All {
+                requested: false, ..
+            } => {
+                *self = Self::Instruments(instruments.iter().cloned().collect());
+            }
+            Self::All { except, .. } => {
+                for instrument_id in instruments {
+                    except.remove(instrument_id);
+                }
+            }
+            Self::Instruments(subscribed) => {
+                subscribed.extend(instruments.iter().cloned());
+            }
+        }
+    }
+
+    pub fn unsubscribe(&mut self, instruments: &[String]) {
+        if instruments.iter().any(|id| id == SUBSCRIBE_ALL) {
+            *self = Self::Instruments(HashSet::new());
+            return;
+        }
+
+        match self {
+            Self::All { except, requested } => {
+                except.extend(instruments.iter().cloned());
+                *requested = true;
+            }
+            Self::Instruments(subscribed) => {
+                for instrument_id in instruments {
+                    subscribed.remove(instrument_id);
+                }
+            }
+        }
+    }
+
+    pub fn is_subscribed(&self, instrument_id: &str) -> bool {
+        match self {
+            Self::All { except, .. } => !except.contains(instrument_id),
+            Self::Instruments(subscribed) => subscribed.contains(instrument_id),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::ClientSubscription;
+
+    fn ids(src: &[&str]) -> Vec<String> {
+        src.iter().map(|id| id.to_string()).collect()
+    }
+
+    #[test]
+    fn test_first_subscribe_narrows_default() {
+        let mut subscription = ClientSubscription::default();
+        assert!(subscription.is_subscribed("EURUSD"));
+
+        subscription.subscribe(&ids(&["EURUSD"]));
+        assert!(subscription.is_subscribed("EURUSD"));
+        assert!(!subscription.is_subscribed("GBPUSD"));
+
+        subscription.subscribe(&ids(&["GBPUSD"]));
+        subscription.unsubscribe(&ids(&["EURUSD"]));
+        assert!(!subscription.is_subscribed("EURUSD"));
+        assert!(subscription.is_subscribed("GBPUSD"));
+    }
+
+    #[test]
+    fn test_wildcard() {
+        let mut subscription = ClientSubscription::default();
+
+        subscription.unsubscribe(&ids(&["*"]));
+        assert!(!subscription.is_subscribed("EURUSD"));
+
+        subscription.subscribe(&ids(&["*"]));
+        subscription.unsubscribe(&ids(&["GBPUSD"]));
+        assert!(subscription.is_subscribed("EURUSD"));
+        assert!(!subscription.is_subscribed("GBPUSD"));
+
+        subscription.subscribe(&ids(&["GBPUSD"]));
+        assert!(subscription.is_subscribed("GBPUSD"));
+    }
+}
@@ -0,0 +1,156 @@
+use std::collections::VecDeque;
+
+use rust_extensions::date_time::DateTimeAsMicroseconds;
+use rust_fix::utils::FIX_DELIMITER;
+use tokio::sync::Mutex;
+
+struct SkewSecondSample {
+    second: i64,
+    min_offset_micros: i64,
+}
+
+// Offset is receive time - SendingTime. Network and queueing delays only make it bigger,
+// so the minimum over the window is the best estimate of the clock difference.
+// It still includes the fastest one-way network latency seen in the window: with in-sync
+// clocks the estimate is the latency, not 0.
+pub struct ClockSkewEstimator {
+    samples: Mutex<VecDeque<SkewSecondSample>>,
+    window_sec: i64,
+}
+
+impl ClockSkewEstimator {
+    pub fn new(window_sec: u64) -> Self {
+        Self {
+            samples: Mutex::new(VecDeque::new()),
+            window_sec: window_sec as i64,
+        }
+    }
+
+    pub async fn add_sample(
+        &self,
+        sending_time: DateTimeAsMicroseconds,
+        receive_time: DateTimeAsMicroseconds,
+    ) {
+        let offset_micros = receive_time.unix_microseconds - sending_time.unix_microseconds;
+        let second = receive_time.unix_microseconds / 1_000_000;
+
+        let mut write_access = self.samples.lock().await;
+
+        match write_access.back_mut() {
+            Some(last) if last.second == second => {
+                if offset_micros < last.min_offset_micros {
+                    last.min_offset_micros = offset_micros;
+                }
+            }
+            _ => write_access.push_back(SkewSecondSample {
+                second,
+                min_offset_micros: offset_micros,
+            }),
+        }
+
+        while let Some(first) = write_access.front() {
+            if second - first.second < self.window_sec {
+                break;
+            }
+            write_access.pop_front();
+        }
+    }
+
+    // Positive value - our clock is ahead of the venue one
+    pub async fn get_estimate_micros(&self) -> Option<i64> {
+        let read_access = self.samples.lock().await;
+        read_access.iter().map(|itm| itm.min_offset_micros).min()
+    }
+
+    // Estimate to shift quote times by. None until it crosses the threshold,
+    // below it the estimate is mostly network latency
+    pub async fn get_correction_micros(&self, threshold_ms: i64) -> Option<i64> {
+        let skew_micros = self.get_estimate_micros().await?;
+
+        if skew_micros.abs() < threshold_ms * 1000 {
+            return None;
+        }
+
+        Some(skew_micros)
+    }
+
+    pub async fn add_sample_from_payload(
+        &self,
+        fix_payload: &[u8],
+        receive_time: DateTimeAsMicroseconds,
+    ) {
+        if let Some(sending_time) = find_sending_time(fix_payload) {
+            self.add_sample(sending_time, receive_time).await;
+        }
+    }
+}
+
+// Cheap lookup of SendingTime (52) without parsing the whole message
+fn find_sending_time(fix_payload: &[u8]) -> Option<DateTimeAsMicroseconds> {
+    const TAG: &[u8] = b"\x0152=";
+
+    let start = fix_payload
+        .windows(TAG.len())
+        .position(|window| window == TAG)?
+        + TAG.len();
+
+    let len = fix_payload[start..]
+        .iter()
+        .position(|b| *b == FIX_DELIMITER)?;
+
+    let value = std::str::from_utf8(&fix_payload[start..start + len]).ok()?;
+    crate::date_utils::try_parse_fix_date(value)
+}
+
+#[cfg(test)]
+mod tests {
+    use rust_extensions::date_time::DateTimeAsMicroseconds;
+
+    use super::ClockSkewEstimator;
+
+    #[tokio::test]
+    async fn test_estimate_is_min_offset_inside_window() {
+        let estimator = ClockSkewEstimator::new(10);
+
+        let sending_time = DateTimeAsMicroseconds::create(2024, 4, 25, 14, 3, 0, 0);
+
+        for (receive_sec, delay_micros) in [(0, 5_000), (1, 2_000), (2, 9_000), (15, 7_000)] {
+            let receive_time = DateTimeAsMicroseconds::new(
+                sending_time.unix_microseconds + receive_sec * 1_000_000 + delay_micros,
+            );
+            let sent = DateTimeAsMicroseconds::new(
+                sending_time.unix_microseconds + receive_sec * 1_000_000,
+            );
+            estimator.add_sample(sent, receive_time).await;
+        }
+
+        // Samples of seconds 0..2 left the window
+        assert_eq!(estimator.get_estimate_micros().await, Some(7_000));
+    }
+
+    #[tokio::test]
+    async fn test_correction_only_over_threshold() {
+        let estimator = ClockSkewEstimator::new(10);
+        let sent = DateTimeAsMicroseconds::create(2024, 4, 25, 14, 3, 0, 0);
+
+        // Network latency only
+        let receive_time = DateTimeAsMicroseconds::new(sent.unix_microseconds + 3_000);
+        estimator.add_sample(sent, receive_time).await;
+        assert_eq!(estimator.get_correction_micros(500).await, None);
+
+        // Venue clock is 800 ms behind
+        let estimator = ClockSkewEstimator::new(10);
+        let receive_time = DateTimeAsMicroseconds::new(sent.unix_microseconds + 800_000);
+        estimator.add_sample(sent, receive_time).await;
+        assert_eq!(estimator.get_correction_micros(500).await, Some(800_000));
+    }
+
+    #[test]
+    fn test_find_sending_time() {
+        let payload = b"8=FIX.4.4\x019=60\x0135=0\x0152=20240425-17:28:02.629\x0110=000\x01";
+        let sending_time = super::find_sending_time(payload).unwrap();
+
+        let expected = DateTimeAsMicroseconds::create(2024, 4, 25, 17, 28, 2, 629000);
+        assert_eq!(sending_time.unix_microseconds, expected.unix_microseconds);
+    }
+}
@@ -0,0 +1,190 @@
+use std::collections::HashMap;
+
+use rust_extensions::date_time::DateTimeAsMicroseconds;
+
+use crate::{settings::ConflationSettingsModel, your_bourse::QuoteTimeSource};
+
+#[derive(Debug, Clone)]
+pub struct OutboundTick {
+    pub instrument_id: String,
+    pub bid: f64,
+    pub ask: f64,
+    pub date: DateTimeAsMicroseconds,
+    pub date_source: QuoteTimeSource,
+    pub receive_time: DateTimeAsMicroseconds,
+    // MDEntrySize of the venue quote. None for synthetics
+    pub bid_size: Option<f64>,
+    pub ask_size: Option<f64>,
+}
+
+struct InstrumentConflation {
+    last_sent: Option<(f64, f64)>,
+    last_sent_at: i64,
+    interval_micros: i64,
+    // Latest tick which did not fit into the current interval
+    pending: Option<OutboundTick>,
+}
+
+impl InstrumentConflation {
+    fn is_interval_passed(&self, now: DateTimeAsMicroseconds) -> bool {
+        now.unix_microseconds - self.last_sent_at >= self.interval_micros
+    }
+
+    fn mark_sent(&mut self, tick: &OutboundTick, now: DateTimeAsMicroseconds) {
+        self.last_sent = Some((tick.bid, tick.ask));
+        self.last_sent_at = now.unix_microseconds;
+    }
+}
+
+// Limits outbound ticks per instrument. Held ticks are delivered by flush_due
+pub struct Conflator {
+    instruments: HashMap<String, InstrumentConflation>,
+}
+
+impl Conflator {
+    pub fn new() -> Self {
+        Self {
+            instruments: HashMap::new(),
+        }
+    }
+
+    // Returns the tick if it has to be sent right now
+    pub fn on_tick(
+        &mut self,
+        settings: Option<&ConflationSettingsModel>,
+        tick: OutboundTick,
+        now: DateTimeAsMicroseconds,
+    ) -> Option<OutboundTick> {
+        let settings = match settings {
+            Some(settings) => settings,
+            None => {
+                self.instruments.remove(&tick.instrument_id);
+                return Some(tick);
+            }
+        };
+
+        let interval_micros = match settings.max_updates_per_sec {
+            Some(max_updates_per_sec) if max_updates_per_sec > 0 => {
+                1_000_000 / max_updates_per_sec as i64
+            }
+            _ => 0,
+        };
+
+        let state = self
+            .instruments
+            .entry(tick.instrument_id.to_string())
+            .or_insert_with(|| InstrumentConflation {
+                last_sent: None,
+                last_sent_at: 0,
+                interval_micros,
+                pending: None,
+            });
+
+        state.interval_micros = interval_micros;
+
+        if settings.drop_unchanged && state.last_sent == Some((tick.bid, tick.ask)) {
+            // Price came back to what consumers already have
+            state.pending = None;
+            return None;
+        }
+
+        if state.is_interval_passed(now) {
+            state.pending = None;
+            state.mark_sent(&tick, now);
+            return Some(tick);
+        }
+
+        state.pending = Some(tick);
+        None
+    }
+
+    pub fn flush_due(&mut self, now: DateTimeAsMicroseconds) -> Vec<OutboundTick> {
+        let mut result = Vec::new();
+
+        for state in self.instruments.values_mut() {
+            if state.pending.is_none() || !state.is_interval_passed(now) {
+                continue;
+            }
+
+            if let Some(tick) = state.pending.take() {
+                state.mark_sent(&tick, now);
+                result.push(tick);
+            }
+        }
+
+        result
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rust_extensions::date_time::DateTimeAsMicroseconds;
+
+    use super::{Conflator, OutboundTick};
+    use crate::{settings::ConflationSettingsModel, your_bourse::QuoteTimeSource};
+
+    fn tick(bid: f64, ask: f64) -> OutboundTick {
+        OutboundTick {
+            instrument_id: "EURUSD".to_string(),
+            bid,
+            ask,
+            date: DateTimeAsMicroseconds::new(0),
+            date_source: QuoteTimeSource::ReceiveTime,
+            receive_time: DateTimeAsMicroseconds::new(0),
+            bid_size: None,
+            ask_size: None,
+        }
+    }
+
+    fn at(millis: i64) -> DateTimeAsMicroseconds {
+        DateTimeAsMicroseconds::new(1_000_000_000 + millis * 1000)
+    }
+
+    #[test]
+    fn test_latest_tick_is_delivered_at_interval_end() {
+        let settings = ConflationSettingsModel {
+            max_updates_per_sec: Some(4),
+            drop_unchanged: true,
+        };
+
+        let mut conflator = Conflator::new();
+
+        assert!(conflator
+            .on_tick(Some(&settings), tick(1.1, 1.2), at(0))
+            .is_some());
+        assert!(conflator
+            .on_tick(Some(&settings), tick(1.3, 1.4), at(100))
+            .is_none());
+        assert!(conflator
+            .on_tick(Some(&settings), tick(1.5, 1.6), at(200))
+            .is_none());
+
+        assert!(conflator.flush_due(at(240)).is_empty());
+
+        let flushed = conflator.flush_due(at(250));
+        assert_eq!(flushed.len(), 1);
+        assert_eq!(flushed[0].bid, 1.5);
+
+        assert!(conflator.flush_due(at(600)).is_empty());
+    }
+
+    #[test]
+    fn test_unchanged_price_is_dropped() {
+        let settings = ConflationSettingsModel {
+            max_updates_per_sec: None,
+            drop_unchanged: true,
+        };
+
+        let mut conflator = Conflator::new();
+
+        assert!(conflator
+            .on_tick(Some(&settings), tick(1.1, 1.2), at(0))
+            .is_some());
+        assert!(conflator
+            .on_tick(Some(&settings), tick(1.1, 1.2), at(10))
+            .is_none());
+        assert!(conflator
+            .on_tick(Some(&settings), tick(1.1, 1.3), at(20))
+            .is_some());
+    }
+}
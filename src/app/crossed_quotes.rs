@@ -0,0 +1,200 @@
+use std::collections::VecDeque;
+
+use rust_extensions::date_time::DateTimeAsMicroseconds;
+use serde::{Deserialize, Serialize};
+use tokio::sync::Mutex;
+
+use crate::{settings::CrossedQuotePolicyModel, your_bourse::YbMarketData};
+
+const MAX_AUDIT_RECORDS: usize = 1000;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum CrossedQuoteAction {
+    #[default]
+    Pass,
+    Swap,
+    Drop,
+    DropAndAlert,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CrossedQuoteKind {
+    // bid > ask
+    Crossed,
+    // bid == ask
+    ZeroSpread,
+}
+
+#[derive(Debug, Clone)]
+pub struct QuoteCorrection {
+    pub date: DateTimeAsMicroseconds,
+    pub external_symbol: String,
+    pub kind: CrossedQuoteKind,
+    pub action: CrossedQuoteAction,
+    pub original_bid: f64,
+    pub original_ask: f64,
+}
+
+impl QuoteCorrection {
+    pub fn is_dropped(&self) -> bool {
+        match self.action {
+            CrossedQuoteAction::Drop | CrossedQuoteAction::DropAndAlert => true,
+            CrossedQuoteAction::Pass | CrossedQuoteAction::Swap => false,
+        }
+    }
+}
+
+// Returns applied correction. None - quote is not crossed or the policy is to pass it as is
+pub fn apply_crossed_quote_policy(
+    policy: &CrossedQuotePolicyModel,
+    market_data: &mut YbMarketData,
+) -> Option<QuoteCorrection> {
+    let (kind, action) = if market_data.bid > market_data.ask {
+        (CrossedQuoteKind::Crossed, policy.crossed)
+    } else if market_data.bid == market_data.ask {
+        // Swapping equal prices changes nothing
+        let action = match policy.zero_spread {
+            CrossedQuoteAction::Swap => CrossedQuoteAction::Pass,
+            action => action,
+        };
+        (CrossedQuoteKind::ZeroSpread, action)
+    } else {
+        return None;
+    };
+
+    if action == CrossedQuoteAction::Pass {
+        return None;
+    }
+
+    let correction = QuoteCorrection {
+        date: DateTimeAsMicroseconds::now(),
+        external_symbol: market_data.instrument_id.clone(),
+        kind,
+        action,
+        original_bid: market_data.bid,
+        original_ask: market_data.ask,
+    };
+
+    if action == CrossedQuoteAction::Swap {
+        std::mem::swap(&mut market_data.bid, &mut market_data.ask);
+    }
+
+    Some(correction)
+}
+
+pub struct QuoteCorrectionsLog {
+    items: Mutex<VecDeque<QuoteCorrection>>,
+}
+
+impl QuoteCorrectionsLog {
+    pub fn new() -> Self {
+        Self {
+            items: Mutex::new(VecDeque::new()),
+        }
+    }
+
+    pub async fn add(&self, correction: QuoteCorrection) {
+        let mut write_access = self.items.lock().await;
+
+        write_access.push_back(correction);
+
+        while write_access.len() > MAX_AUDIT_RECORDS {
+            write_access.pop_front();
+        }
+    }
+
+    pub async fn get_all(&self) -> Vec<QuoteCorrection> {
+        let read_access = self.items.lock().await;
+        read_access.iter().cloned().collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rust_extensions::date_time::DateTimeAsMicroseconds;
+
+    use super::{apply_crossed_quote_policy, CrossedQuoteAction, CrossedQuoteKind};
+    use crate::{
+        settings::CrossedQuotePolicyModel,
+        your_bourse::{QuoteTimeSource, YbMarketData},
+    };
+
+    fn market_data(bid: f64, ask: f64) -> YbMarketData {
+        YbMarketData {
+            instrument_id: "EUR/USD".to_string(),
+            date: DateTimeAsMicroseconds::new(0),
+            date_source: QuoteTimeSource::SendingTime,
+            bid,
+            ask,
+            bid_size: None,
+            ask_size: None,
+            sending_time: DateTimeAsMicroseconds::new(0),
+            entry_time: None,
+            receive_time: DateTimeAsMicroseconds::new(0),
+        }
+    }
+
+    fn policy(crossed: CrossedQuoteAction) -> CrossedQuotePolicyModel {
+        CrossedQuotePolicyModel {
+            crossed,
+            zero_spread: CrossedQuoteAction::Pass,
+        }
+    }
+
+    #[test]
+    fn test_crossed_quote_actions() {
+        let mut quote = market_data(1.1002, 1.1001);
+        assert!(
+            apply_crossed_quote_policy(&policy(CrossedQuoteAction::Pass), &mut quote).is_none()
+        );
+        assert_eq!((quote.bid, quote.ask), (1.1002, 1.1001));
+
+        let mut quote = market_data(1.1002, 1.1001);
+        let correction =
+            apply_crossed_quote_policy(&policy(CrossedQuoteAction::Swap), &mut quote).unwrap();
+        assert_eq!(correction.kind, CrossedQuoteKind::Crossed);
+        assert!(!correction.is_dropped());
+        assert_eq!((quote.bid, quote.ask), (1.1001, 1.1002));
+        assert_eq!(
+            (correction.original_bid, correction.original_ask),
+            (1.1002, 1.1001)
+        );
+
+        for action in [CrossedQuoteAction::Drop, CrossedQuoteAction::DropAndAlert] {
+            let mut quote = market_data(1.1002, 1.1001);
+            let correction = apply_crossed_quote_policy(&policy(action), &mut quote).unwrap();
+            assert_eq!(correction.action, action);
+            assert!(correction.is_dropped());
+        }
+    }
+
+    #[test]
+    fn test_zero_spread_and_normal_quotes() {
+        let zero_spread_policy = |zero_spread| CrossedQuotePolicyModel {
+            crossed: CrossedQuoteAction::Drop,
+            zero_spread,
+        };
+
+        // Swapping equal prices is a no-op, so it is passed without a correction
+        let mut quote = market_data(1.1, 1.1);
+        assert!(apply_crossed_quote_policy(
+            &zero_spread_policy(CrossedQuoteAction::Swap),
+            &mut quote
+        )
+        .is_none());
+
+        let mut quote = market_data(1.1, 1.1);
+        let correction =
+            apply_crossed_quote_policy(&zero_spread_policy(CrossedQuoteAction::Drop), &mut quote)
+                .unwrap();
+        assert_eq!(correction.kind, CrossedQuoteKind::ZeroSpread);
+        assert!(correction.is_dropped());
+
+        let mut quote = market_data(1.1001, 1.1002);
+        assert!(apply_crossed_quote_policy(
+            &zero_spread_policy(CrossedQuoteAction::DropAndAlert),
+            &mut quote
+        )
+        .is_none());
+    }
+}
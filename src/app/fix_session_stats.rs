@@ -0,0 +1,23 @@
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+// Inbound FIX messages the bridge did not turn into prices
+#[derive(Default)]
+pub struct FixSessionStats {
+    pub rejects: AtomicUsize,
+    pub market_data_rejects: AtomicUsize,
+    pub skipped: AtomicUsize,
+}
+
+impl FixSessionStats {
+    pub fn get_rejects(&self) -> usize {
+        self.rejects.load(Ordering::Relaxed)
+    }
+
+    pub fn get_market_data_rejects(&self) -> usize {
+        self.market_data_rejects.load(Ordering::Relaxed)
+    }
+
+    pub fn get_skipped(&self) -> usize {
+        self.skipped.load(Ordering::Relaxed)
+    }
+}
@@ -0,0 +1,42 @@
+use std::collections::VecDeque;
+
+use rust_extensions::date_time::DateTimeAsMicroseconds;
+use tokio::sync::Mutex;
+
+const MAX_RECORDS: usize = 100;
+
+#[derive(Debug, Clone)]
+pub struct ForcedReconnect {
+    pub date: DateTimeAsMicroseconds,
+    pub reason: String,
+}
+
+pub struct ForcedReconnectsLog {
+    items: Mutex<VecDeque<ForcedReconnect>>,
+}
+
+impl ForcedReconnectsLog {
+    pub fn new() -> Self {
+        Self {
+            items: Mutex::new(VecDeque::new()),
+        }
+    }
+
+    pub async fn add(&self, reason: String) {
+        let mut write_access = self.items.lock().await;
+
+        write_access.push_back(ForcedReconnect {
+            date: DateTimeAsMicroseconds::now(),
+            reason,
+        });
+
+        while write_access.len() > MAX_RECORDS {
+            write_access.pop_front();
+        }
+    }
+
+    pub async fn get_all(&self) -> Vec<ForcedReconnect> {
+        let read_access = self.items.lock().await;
+        read_access.iter().cloned().collect()
+    }
+}
@@ -0,0 +1,134 @@
+use crate::settings::InstrumentSettingsModel;
+
+use super::RoundingMode;
+
+pub struct InstrumentPrice {
+    // LP price after transform, before markup and rounding
+    pub raw_bid: f64,
+    pub raw_ask: f64,
+    pub bid: f64,
+    pub ask: f64,
+}
+
+// Price of one of our instruments from the external quote. Err - price must not be published
+pub fn calculate_instrument_price(
+    instrument_settings: Option<&InstrumentSettingsModel>,
+    bid: f64,
+    ask: f64,
+) -> Result<InstrumentPrice, String> {
+    let instrument_settings = match instrument_settings {
+        Some(instrument_settings) => instrument_settings,
+        None => {
+            return Ok(InstrumentPrice {
+                raw_bid: bid,
+                raw_ask: ask,
+                bid,
+                ask,
+            })
+        }
+    };
+
+    let (raw_bid, raw_ask) = match instrument_settings.transform.as_ref() {
+        Some(transform) => super::apply_price_transform(transform, bid, ask)?,
+        None => (bid, ask),
+    };
+
+    // Tick size is in our instrument units, so alignment is checked after the transform
+    if let Some(precision) = instrument_settings.precision.as_ref() {
+        if precision.reject_misaligned
+            && (!precision.is_aligned(raw_bid) || !precision.is_aligned(raw_ask))
+        {
+            return Err(format!(
+                "bid:{} or ask:{} is not aligned with tick size {}",
+                raw_bid,
+                raw_ask,
+                precision.get_step()
+            ));
+        }
+    }
+
+    let (mut bid, mut ask) = (raw_bid, raw_ask);
+
+    if let Some(markup) = instrument_settings.markup.as_ref() {
+        (bid, ask) = super::apply_markup(markup, bid, ask);
+    }
+
+    if let Some(precision) = instrument_settings.precision.as_ref() {
+        let (unrounded_bid, unrounded_ask) = (bid, ask);
+
+        bid = precision.round(bid, precision.bid_rounding);
+        ask = precision.round(ask, precision.ask_rounding);
+
+        // Rounding must not close or cross a spread the quote had. Ask goes one tick above bid
+        if bid >= ask && unrounded_bid < unrounded_ask {
+            ask = precision.round(bid + precision.get_step(), RoundingMode::Nearest);
+        }
+    }
+
+    Ok(InstrumentPrice {
+        raw_bid,
+        raw_ask,
+        bid,
+        ask,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        app::RoundingMode,
+        settings::{InstrumentSettingsModel, PrecisionSettingsModel, PriceTransformSettingsModel},
+    };
+
+    fn settings(
+        rounding: RoundingMode,
+        transform: Option<PriceTransformSettingsModel>,
+    ) -> InstrumentSettingsModel {
+        InstrumentSettingsModel {
+            transform,
+            markup: None,
+            precision: Some(PrecisionSettingsModel {
+                digits: 2,
+                tick_size: Some(0.25),
+                bid_rounding: rounding,
+                ask_rounding: rounding,
+                reject_misaligned: true,
+            }),
+            conflation: None,
+        }
+    }
+
+    #[test]
+    fn test_alignment_is_checked_on_the_transformed_price() {
+        // Cents -> dollars. Tick size is in dollars
+        let transform = PriceTransformSettingsModel {
+            invert: false,
+            multiplier: Some(0.01),
+            offset: None,
+        };
+        let settings = settings(RoundingMode::Nearest, Some(transform));
+
+        let price = super::calculate_instrument_price(Some(&settings), 450175.0, 450200.0).unwrap();
+        assert_eq!((price.bid, price.ask), (4501.75, 4502.0));
+
+        // Whole cents, but 4501.70 is not a multiple of 0.25
+        let price = super::calculate_instrument_price(Some(&settings), 450170.0, 450200.0);
+        assert!(price.is_err());
+    }
+
+    #[test]
+    fn test_rounding_keeps_the_spread() {
+        let mut settings = settings(RoundingMode::Nearest, None);
+        settings.precision.as_mut().unwrap().reject_misaligned = false;
+
+        // Both sides round to 4501.25. Ask is widened by one tick
+        let price = super::calculate_instrument_price(Some(&settings), 4501.30, 4501.36).unwrap();
+        assert_eq!((price.bid, price.ask), (4501.25, 4501.5));
+
+        settings.precision.as_mut().unwrap().bid_rounding = RoundingMode::Down;
+        settings.precision.as_mut().unwrap().ask_rounding = RoundingMode::Up;
+
+        let price = super::calculate_instrument_price(Some(&settings), 4501.30, 4501.36).unwrap();
+        assert_eq!((price.bid, price.ask), (4501.25, 4501.5));
+    }
+}
@@ -0,0 +1,299 @@
+use std::collections::{HashMap, HashSet};
+
+use rust_extensions::date_time::DateTimeAsMicroseconds;
+use tokio::sync::Mutex;
+
+use crate::{settings::SyntheticInstrumentSettingsModel, your_bourse::YbMarketData};
+
+use super::{PublishedBidAsk, SyntheticPrice, SyntheticSkipReason};
+
+// Stale age in nosql is refreshed this often. It only has to be roughly right
+const STALE_AGE_REFRESH_SEC: i64 = 60;
+
+// Last published price of one of our instruments
+#[derive(Debug, Clone)]
+pub struct InstrumentLastPrice {
+    pub instrument_id: String,
+    pub src_id: String,
+    pub raw_bid: f64,
+    pub raw_ask: f64,
+    pub bid: f64,
+    pub ask: f64,
+    pub source_time: DateTimeAsMicroseconds,
+    pub receive_time: DateTimeAsMicroseconds,
+    pub update_count: u64,
+    // Some - no updates for longer than the stale threshold. Cleared by the next price
+    pub stale_since: Option<DateTimeAsMicroseconds>,
+}
+
+impl InstrumentLastPrice {
+    pub fn get_age_ms(&self, now: DateTimeAsMicroseconds) -> i64 {
+        (now.unix_microseconds - self.receive_time.unix_microseconds) / 1000
+    }
+}
+
+// Last accepted quote of an external symbol
+#[derive(Debug, Clone)]
+pub struct ExternalLastPrice {
+    pub external_symbol: String,
+    pub bid: f64,
+    pub ask: f64,
+    pub source_time: DateTimeAsMicroseconds,
+    pub receive_time: DateTimeAsMicroseconds,
+    pub update_count: u64,
+}
+
+struct LastPriceStoreInner {
+    instruments: HashMap<String, InstrumentLastPrice>,
+    external: HashMap<String, ExternalLastPrice>,
+    // Instruments updated since the last upload to nosql
+    dirty: HashSet<String>,
+    // Stale instrument -> when its stale age was last put to the dirty set
+    stale_refreshed: HashMap<String, DateTimeAsMicroseconds>,
+}
+
+pub struct LastPriceStore {
+    inner: Mutex<LastPriceStoreInner>,
+}
+
+impl LastPriceStore {
+    pub fn new() -> Self {
+        Self {
+            inner: Mutex::new(LastPriceStoreInner {
+                instruments: HashMap::new(),
+                external: HashMap::new(),
+                dirty: HashSet::new(),
+                stale_refreshed: HashMap::new(),
+            }),
+        }
+    }
+
+    pub async fn update_external(&self, market_data: &YbMarketData) {
+        let mut inner = self.inner.lock().await;
+
+        let update_count = match inner.external.get(&market_data.instrument_id) {
+            Some(last_price) => last_price.update_count + 1,
+            None => 1,
+        };
+
+        inner.external.insert(
+            market_data.instrument_id.to_string(),
+            ExternalLastPrice {
+                external_symbol: market_data.instrument_id.to_string(),
+                bid: market_data.bid,
+                ask: market_data.ask,
+                source_time: market_data.date,
+                receive_time: market_data.receive_time,
+                update_count,
+            },
+        );
+    }
+
+    pub async fn update_instruments(
+        &self,
+        published: &[PublishedBidAsk],
+        receive_time: DateTimeAsMicroseconds,
+    ) {
+        let mut inner = self.inner.lock().await;
+
+        for price in published {
+            let update_count = match inner.instruments.get(&price.instrument_id) {
+                Some(last_price) => last_price.update_count + 1,
+                None => 1,
+            };
+
+            inner.instruments.insert(
+                price.instrument_id.to_string(),
+                InstrumentLastPrice {
+                    instrument_id: price.instrument_id.to_string(),
+                    src_id: price.src_id.to_string(),
+                    raw_bid: price.raw_bid,
+                    raw_ask: price.raw_ask,
+                    bid: price.bid,
+                    ask: price.ask,
+                    source_time: price.date,
+                    receive_time,
+                    update_count,
+                    stale_since: None,
+                },
+            );
+
+            inner.dirty.insert(price.instrument_id.to_string());
+            inner.stale_refreshed.remove(price.instrument_id.as_str());
+        }
+    }
+
+    pub async fn get_instrument(&self, instrument_id: &str) -> Option<InstrumentLastPrice> {
+        let inner = self.inner.lock().await;
+        inner.instruments.get(instrument_id).cloned()
+    }
+
+    pub async fn get_instruments(&self) -> Vec<InstrumentLastPrice> {
+        let inner = self.inner.lock().await;
+        inner.instruments.values().cloned().collect()
+    }
+
+    pub async fn get_external(&self, external_symbol: &str) -> Option<ExternalLastPrice> {
+        let inner = self.inner.lock().await;
+        inner.external.get(external_symbol).cloned()
+    }
+
+    pub async fn get_externals(&self) -> Vec<ExternalLastPrice> {
+        let inner = self.inner.lock().await;
+        inner.external.values().cloned().collect()
+    }
+
+    // Prices changed since the previous call. Dirty set is cleared
+    pub async fn take_dirty(&self) -> Vec<InstrumentLastPrice> {
+        let mut inner = self.inner.lock().await;
+
+        if inner.dirty.is_empty() {
+            return Vec::new();
+        }
+
+        let dirty = std::mem::take(&mut inner.dirty);
+
+        dirty
+            .iter()
+            .filter_map(|instrument_id| inner.instruments.get(instrument_id).cloned())
+            .collect()
+    }
+
+    // Puts instruments back to the dirty set. E.g. upload failed
+    pub async fn mark_dirty(&self, instrument_ids: impl Iterator<Item = String>) {
+        let mut inner = self.inner.lock().await;
+        inner.dirty.extend(instrument_ids);
+    }
+
+    // Marks instruments not updated for longer than their threshold. Returns newly stale ones.
+    // Stale ones go back to the dirty set every STALE_AGE_REFRESH_SEC to refresh their age
+    pub async fn mark_stale(
+        &self,
+        now: DateTimeAsMicroseconds,
+        get_stale_after_sec: impl Fn(&str) -> u64,
+    ) -> Vec<InstrumentLastPrice> {
+        let mut inner = self.inner.lock().await;
+        let inner = &mut *inner;
+
+        let mut result = Vec::new();
+
+        for last_price in inner.instruments.values_mut() {
+            let stale_after_ms = get_stale_after_sec(last_price.instrument_id.as_str()) * 1000;
+
+            if last_price.get_age_ms(now) <= stale_after_ms as i64 {
+                continue;
+            }
+
+            if last_price.stale_since.is_none() {
+                last_price.stale_since = Some(now);
+                result.push(last_price.clone());
+            } else if let Some(refreshed) =
+                inner.stale_refreshed.get(last_price.instrument_id.as_str())
+            {
+                if now.unix_microseconds - refreshed.unix_microseconds
+                    < STALE_AGE_REFRESH_SEC * 1_000_000
+                {
+                    continue;
+                }
+            }
+
+            inner
+                .stale_refreshed
+                .insert(last_price.instrument_id.to_string(), now);
+            inner.dirty.insert(last_price.instrument_id.to_string());
+        }
+
+        result
+    }
+
+    pub async fn calculate_synthetic(
+        &self,
+        synthetic: &SyntheticInstrumentSettingsModel,
+        now: DateTimeAsMicroseconds,
+    ) -> Result<SyntheticPrice, SyntheticSkipReason> {
+        let inner = self.inner.lock().await;
+        super::calculate_synthetic_price(synthetic, &inner.external, now)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rust_extensions::date_time::DateTimeAsMicroseconds;
+
+    use super::LastPriceStore;
+    use crate::app::PublishedBidAsk;
+
+    fn published(instrument_id: &str, bid: f64) -> PublishedBidAsk {
+        PublishedBidAsk {
+            instrument_id: instrument_id.to_string(),
+            src_id: "EUR/USD".to_string(),
+            raw_bid: bid,
+            raw_ask: bid + 0.0001,
+            bid,
+            ask: bid + 0.0001,
+            date: DateTimeAsMicroseconds::new(1_000_000),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_dirty_set_keeps_last_price() {
+        let store = LastPriceStore::new();
+        let now = DateTimeAsMicroseconds::new(2_000_000);
+
+        store
+            .update_instruments(&[published("EURUSD", 1.08)], now)
+            .await;
+        store
+            .update_instruments(&[published("EURUSD", 1.09)], now)
+            .await;
+
+        let dirty = store.take_dirty().await;
+        assert_eq!(dirty.len(), 1);
+        assert_eq!(dirty[0].bid, 1.09);
+        assert_eq!(dirty[0].update_count, 2);
+
+        assert!(store.take_dirty().await.is_empty());
+
+        // Store is not drained by the upload
+        let last_price = store.get_instrument("EURUSD").await.unwrap();
+        assert_eq!(last_price.bid, 1.09);
+    }
+
+    #[tokio::test]
+    async fn test_stale_flag_is_cleared_by_next_price() {
+        let store = LastPriceStore::new();
+
+        store
+            .update_instruments(
+                &[published("EURUSD", 1.08)],
+                DateTimeAsMicroseconds::new(2_000_000),
+            )
+            .await;
+        store.take_dirty().await;
+
+        let now = DateTimeAsMicroseconds::new(40_000_000);
+
+        let stale = store.mark_stale(now, |_| 30).await;
+        assert_eq!(stale.len(), 1);
+        assert_eq!(stale[0].get_age_ms(now), 38_000);
+
+        // Reported once. Age is refreshed on a coarse interval only
+        assert!(store.mark_stale(now, |_| 30).await.is_empty());
+        assert_eq!(store.take_dirty().await.len(), 1);
+
+        let now = DateTimeAsMicroseconds::new(99_000_000);
+        store.mark_stale(now, |_| 30).await;
+        assert!(store.take_dirty().await.is_empty());
+
+        let now = DateTimeAsMicroseconds::new(100_000_000);
+        store.mark_stale(now, |_| 30).await;
+        assert_eq!(store.take_dirty().await.len(), 1);
+
+        store
+            .update_instruments(&[published("EURUSD", 1.09)], now)
+            .await;
+
+        let last_price = store.get_instrument("EURUSD").await.unwrap();
+        assert!(last_price.stale_since.is_none());
+    }
+}
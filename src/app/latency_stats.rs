@@ -0,0 +1,239 @@
+use std::collections::HashMap;
+
+use rust_extensions::date_time::DateTimeAsMicroseconds;
+use serde::Serialize;
+use tokio::sync::Mutex;
+
+use crate::your_bourse::YbMarketData;
+
+const FIRST_BUCKET_MICROS: f64 = 10.0;
+const BUCKET_FACTOR: f64 = 1.25;
+const LAST_BUCKET_MICROS: f64 = 60_000_000.0;
+
+// Exponential buckets: percentiles are accurate up to BUCKET_FACTOR
+pub struct LatencyHistogram {
+    bounds: Vec<i64>,
+    counts: Vec<u64>,
+    total: u64,
+    max: i64,
+}
+
+impl LatencyHistogram {
+    pub fn new() -> Self {
+        let mut bounds = Vec::new();
+        let mut bound = FIRST_BUCKET_MICROS;
+
+        while bound < LAST_BUCKET_MICROS {
+            bounds.push(bound as i64);
+            bound *= BUCKET_FACTOR;
+        }
+
+        bounds.push(i64::MAX);
+
+        Self {
+            counts: vec![0; bounds.len()],
+            bounds,
+            total: 0,
+            max: 0,
+        }
+    }
+
+    // Negative values mean clocks are out of sync - they are counted as zero
+    pub fn add(&mut self, latency_micros: i64) {
+        let latency_micros = latency_micros.max(0);
+
+        let index = match self.bounds.binary_search(&latency_micros) {
+            Ok(index) => index,
+            Err(index) => index,
+        };
+
+        self.counts[index] += 1;
+        self.total += 1;
+
+        if latency_micros > self.max {
+            self.max = latency_micros;
+        }
+    }
+
+    pub fn get_percentile(&self, percentile: f64) -> i64 {
+        if self.total == 0 {
+            return 0;
+        }
+
+        let threshold = (self.total as f64 * percentile).ceil() as u64;
+        let mut accumulated = 0;
+
+        for (index, count) in self.counts.iter().enumerate() {
+            accumulated += count;
+
+            if accumulated >= threshold {
+                return self.bounds[index].min(self.max);
+            }
+        }
+
+        self.max
+    }
+
+    pub fn get_summary(&self) -> LatencySummary {
+        LatencySummary {
+            count: self.total,
+            p50_micros: self.get_percentile(0.5),
+            p99_micros: self.get_percentile(0.99),
+            max_micros: self.max,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct LatencySummary {
+    pub count: u64,
+    pub p50_micros: i64,
+    pub p99_micros: i64,
+    pub max_micros: i64,
+}
+
+impl LatencySummary {
+    pub fn to_log_string(&self) -> String {
+        format!(
+            "p50={}us p99={}us max={}us n={}",
+            self.p50_micros, self.p99_micros, self.max_micros, self.count
+        )
+    }
+}
+
+pub struct SymbolLatency {
+    pub sending_to_receive: LatencyHistogram,
+    pub entry_to_receive: LatencyHistogram,
+    pub receive_to_publish: LatencyHistogram,
+    pub sending_to_publish: LatencyHistogram,
+}
+
+impl SymbolLatency {
+    pub fn new() -> Self {
+        Self {
+            sending_to_receive: LatencyHistogram::new(),
+            entry_to_receive: LatencyHistogram::new(),
+            receive_to_publish: LatencyHistogram::new(),
+            sending_to_publish: LatencyHistogram::new(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct SymbolLatencySummary {
+    pub external_symbol: String,
+    pub sending_to_receive: LatencySummary,
+    pub entry_to_receive: LatencySummary,
+    pub receive_to_publish: LatencySummary,
+    pub sending_to_publish: LatencySummary,
+}
+
+pub struct LatencyStats {
+    items: Mutex<HashMap<String, SymbolLatency>>,
+}
+
+impl LatencyStats {
+    pub fn new() -> Self {
+        Self {
+            items: Mutex::new(HashMap::new()),
+        }
+    }
+
+    pub async fn record(&self, market_data: &YbMarketData, publish_time: DateTimeAsMicroseconds) {
+        let mut write_access = self.items.lock().await;
+
+        let symbol_latency = match write_access.get_mut(market_data.instrument_id.as_str()) {
+            Some(symbol_latency) => symbol_latency,
+            None => {
+                write_access.insert(market_data.instrument_id.clone(), SymbolLatency::new());
+                write_access
+                    .get_mut(market_data.instrument_id.as_str())
+                    .unwrap()
+            }
+        };
+
+        let receive_time = market_data.receive_time.unix_microseconds;
+        let sending_time = market_data.sending_time.unix_microseconds;
+        let publish_time = publish_time.unix_microseconds;
+
+        symbol_latency
+            .sending_to_receive
+            .add(receive_time - sending_time);
+
+        if let Some(entry_time) = market_data.entry_time {
+            symbol_latency
+                .entry_to_receive
+                .add(receive_time - entry_time.unix_microseconds);
+        }
+
+        symbol_latency
+            .receive_to_publish
+            .add(publish_time - receive_time);
+
+        symbol_latency
+            .sending_to_publish
+            .add(publish_time - sending_time);
+    }
+
+    pub async fn get_summary(&self) -> Vec<SymbolLatencySummary> {
+        let read_access = self.items.lock().await;
+        to_summary(&read_access)
+    }
+
+    // Returns the summary of the finished window and starts a new one
+    pub async fn get_summary_and_reset(&self) -> Vec<SymbolLatencySummary> {
+        let items = {
+            let mut write_access = self.items.lock().await;
+            std::mem::take(&mut *write_access)
+        };
+
+        to_summary(&items)
+    }
+}
+
+fn to_summary(items: &HashMap<String, SymbolLatency>) -> Vec<SymbolLatencySummary> {
+    let mut result: Vec<SymbolLatencySummary> = items
+        .iter()
+        .map(|(external_symbol, itm)| SymbolLatencySummary {
+            external_symbol: external_symbol.to_string(),
+            sending_to_receive: itm.sending_to_receive.get_summary(),
+            entry_to_receive: itm.entry_to_receive.get_summary(),
+            receive_to_publish: itm.receive_to_publish.get_summary(),
+            sending_to_publish: itm.sending_to_publish.get_summary(),
+        })
+        .collect();
+
+    result.sort_by(|a, b| a.external_symbol.cmp(&b.external_symbol));
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::LatencyHistogram;
+
+    #[test]
+    fn test_percentiles() {
+        let mut histogram = LatencyHistogram::new();
+
+        for latency in 1..=1000 {
+            histogram.add(latency * 100);
+        }
+
+        let summary = histogram.get_summary();
+
+        assert_eq!(summary.count, 1000);
+        assert_eq!(summary.max_micros, 100_000);
+
+        // Bucket bounds are within 25% of the exact value
+        assert!(summary.p50_micros >= 50_000 && summary.p50_micros <= 62_500);
+        assert!(summary.p99_micros >= 99_000 && summary.p99_micros <= 100_000);
+    }
+
+    #[test]
+    fn test_negative_latency_counted_as_zero() {
+        let mut histogram = LatencyHistogram::new();
+        histogram.add(-500);
+
+        assert_eq!(histogram.get_percentile(0.5), 0);
+    }
+}
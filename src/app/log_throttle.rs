@@ -0,0 +1,72 @@
+use std::collections::HashMap;
+
+use rust_extensions::date_time::DateTimeAsMicroseconds;
+
+struct ThrottledKey {
+    last_logged: DateTimeAsMicroseconds,
+    held_back: u64,
+}
+
+// Lets one message per key through every interval and counts the ones held back
+pub struct LogThrottle {
+    interval_micros: i64,
+    items: HashMap<String, ThrottledKey>,
+}
+
+impl LogThrottle {
+    pub fn new(interval_sec: i64) -> Self {
+        Self {
+            interval_micros: interval_sec * 1_000_000,
+            items: HashMap::new(),
+        }
+    }
+
+    // Some(messages held back since the last logged one) - log it. None - skip it
+    pub fn try_log(&mut self, key: &str, now: DateTimeAsMicroseconds) -> Option<u64> {
+        let item = match self.items.get_mut(key) {
+            Some(item) => item,
+            None => {
+                self.items.insert(
+                    key.to_string(),
+                    ThrottledKey {
+                        last_logged: now,
+                        held_back: 0,
+                    },
+                );
+                return Some(0);
+            }
+        };
+
+        if now.unix_microseconds - item.last_logged.unix_microseconds < self.interval_micros {
+            item.held_back += 1;
+            return None;
+        }
+
+        let held_back = item.held_back;
+        item.last_logged = now;
+        item.held_back = 0;
+
+        Some(held_back)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rust_extensions::date_time::DateTimeAsMicroseconds;
+
+    use super::LogThrottle;
+
+    #[test]
+    fn test_one_message_per_interval() {
+        let mut throttle = LogThrottle::new(10);
+        let at_sec = |sec: i64| DateTimeAsMicroseconds::new(sec * 1_000_000);
+
+        assert_eq!(throttle.try_log("EURUSD", at_sec(0)), Some(0));
+        assert_eq!(throttle.try_log("EURUSD", at_sec(1)), None);
+        assert_eq!(throttle.try_log("EURUSD", at_sec(9)), None);
+        // Other keys are throttled on their own
+        assert_eq!(throttle.try_log("GBPUSD", at_sec(9)), Some(0));
+        assert_eq!(throttle.try_log("EURUSD", at_sec(10)), Some(2));
+        assert_eq!(throttle.try_log("EURUSD", at_sec(11)), None);
+    }
+}
@@ -0,0 +1,92 @@
+use crate::settings::MarkupSettingsModel;
+
+// Returns (bid, ask) widened by the markup. Bid only goes down and ask only goes up
+pub fn apply_markup(markup: &MarkupSettingsModel, bid: f64, ask: f64) -> (f64, f64) {
+    let mut bid = bid;
+    let mut ask = ask;
+
+    if let Some(bid_pips) = markup.bid_pips {
+        bid -= bid_pips * markup.pip_size;
+    }
+
+    if let Some(ask_pips) = markup.ask_pips {
+        ask += ask_pips * markup.pip_size;
+    }
+
+    if let Some(bid_percent) = markup.bid_percent {
+        bid -= bid * bid_percent / 100.0;
+    }
+
+    if let Some(ask_percent) = markup.ask_percent {
+        ask += ask * ask_percent / 100.0;
+    }
+
+    if let Some(min_spread_pips) = markup.min_spread_pips {
+        let min_spread = min_spread_pips * markup.pip_size;
+
+        if ask - bid < min_spread {
+            let mid = (bid + ask) / 2.0;
+            bid = mid - min_spread / 2.0;
+            ask = mid + min_spread / 2.0;
+        }
+    }
+
+    (bid, ask)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::settings::MarkupSettingsModel;
+
+    fn markup() -> MarkupSettingsModel {
+        MarkupSettingsModel {
+            pip_size: 0.0001,
+            bid_pips: None,
+            ask_pips: None,
+            bid_percent: None,
+            ask_percent: None,
+            min_spread_pips: None,
+        }
+    }
+
+    #[test]
+    fn test_asymmetric_pips() {
+        let markup = MarkupSettingsModel {
+            bid_pips: Some(1.0),
+            ask_pips: Some(2.0),
+            ..markup()
+        };
+
+        let (bid, ask) = super::apply_markup(&markup, 1.1, 1.1001);
+
+        assert!((bid - 1.0999).abs() < 1e-9);
+        assert!((ask - 1.1003).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_min_spread_is_applied_around_mid() {
+        let markup = MarkupSettingsModel {
+            min_spread_pips: Some(4.0),
+            ..markup()
+        };
+
+        let (bid, ask) = super::apply_markup(&markup, 1.1, 1.1002);
+
+        assert!((bid - 1.0999).abs() < 1e-9);
+        assert!((ask - 1.1003).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_percent() {
+        let markup = MarkupSettingsModel {
+            bid_percent: Some(1.0),
+            ask_percent: Some(1.0),
+            ..markup()
+        };
+
+        let (bid, ask) = super::apply_markup(&markup, 100.0, 100.0);
+
+        assert!((bid - 99.0).abs() < 1e-9);
+        assert!((ask - 101.0).abs() < 1e-9);
+    }
+}
@@ -2,5 +2,43 @@ mod app;
 pub use app::*;
 mod broadcast_data;
 pub use broadcast_data::*;
-mod price_cache;
-pub use price_cache::*;
+mod last_price_store;
+pub use last_price_store::*;
+mod quote_activity;
+pub use quote_activity::*;
+mod forced_reconnects;
+pub use forced_reconnects::*;
+mod latency_stats;
+pub use latency_stats::*;
+mod clock_skew;
+pub use clock_skew::*;
+mod spike_filter;
+pub use spike_filter::*;
+mod crossed_quotes;
+pub use crossed_quotes::*;
+mod markup;
+pub use markup::*;
+mod published_bid_ask;
+pub use published_bid_ask::*;
+mod precision;
+pub use precision::*;
+mod instrument_pricing;
+pub use instrument_pricing::*;
+mod price_transform;
+pub use price_transform::*;
+mod synthetic;
+pub use synthetic::*;
+mod conflation;
+pub use conflation::*;
+mod client_subscription;
+pub use client_subscription::*;
+mod price_subscriber;
+pub use price_subscriber::*;
+mod candles;
+pub use candles::*;
+mod price_feed_source;
+pub use price_feed_source::*;
+mod fix_session_stats;
+pub use fix_session_stats::*;
+mod log_throttle;
+pub use log_throttle::*;
@@ -0,0 +1,117 @@
+use serde::{Deserialize, Serialize};
+
+use crate::settings::PrecisionSettingsModel;
+
+pub const MAX_DECIMALS: u32 = 10;
+// Share of a tick. A price this close to a tick is the tick plus LP or f64 noise: 1.0834999999
+const ALIGN_TOLERANCE: f64 = 1e-4;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum RoundingMode {
+    #[default]
+    Nearest,
+    Down,
+    Up,
+}
+
+impl PrecisionSettingsModel {
+    pub fn validate(&self) -> Result<(), String> {
+        if self.digits > MAX_DECIMALS {
+            return Err(format!(
+                "Invalid digits: {}. Expected 0..{}",
+                self.digits, MAX_DECIMALS
+            ));
+        }
+
+        if let Some(tick_size) = self.tick_size {
+            if !(tick_size > 0.0) {
+                return Err(format!("Invalid tick_size: {}", tick_size));
+            }
+        }
+
+        Ok(())
+    }
+
+    pub fn get_step(&self) -> f64 {
+        match self.tick_size {
+            Some(tick_size) => tick_size,
+            None => 10_f64.powi(-(self.digits as i32)),
+        }
+    }
+
+    pub fn is_aligned(&self, price: f64) -> bool {
+        let ticks = price / self.get_step();
+        (ticks - ticks.round()).abs() < ALIGN_TOLERANCE
+    }
+
+    pub fn round(&self, price: f64, mode: RoundingMode) -> f64 {
+        let step = self.get_step();
+        let ticks = price / step;
+
+        // Noise around a tick is snapped to it before the directional round
+        let ticks = if (ticks - ticks.round()).abs() < ALIGN_TOLERANCE {
+            ticks.round()
+        } else {
+            match mode {
+                RoundingMode::Nearest => ticks.round(),
+                RoundingMode::Down => ticks.floor(),
+                RoundingMode::Up => ticks.ceil(),
+            }
+        };
+
+        let decimals = self.digits.min(MAX_DECIMALS) as i32;
+        let multiplier = 10_f64.powi(decimals);
+
+        (ticks * step * multiplier).round() / multiplier
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::RoundingMode;
+    use crate::settings::PrecisionSettingsModel;
+
+    fn precision(digits: u32, tick_size: Option<f64>) -> PrecisionSettingsModel {
+        PrecisionSettingsModel {
+            digits,
+            tick_size,
+            bid_rounding: RoundingMode::Down,
+            ask_rounding: RoundingMode::Up,
+            reject_misaligned: false,
+        }
+    }
+
+    #[test]
+    fn test_round_by_digits() {
+        let precision = precision(5, None);
+
+        assert_eq!(
+            precision.round(1.0834999999, RoundingMode::Nearest),
+            1.08350
+        );
+        assert_eq!(precision.round(1.0834999999, RoundingMode::Down), 1.08350);
+        assert_eq!(precision.round(1.0834999999, RoundingMode::Up), 1.08350);
+        assert!(precision.is_aligned(1.0834999999));
+        assert_eq!(precision.round(1.083499, RoundingMode::Down), 1.08349);
+        assert_eq!(precision.round(1.083401, RoundingMode::Up), 1.08341);
+        assert_eq!(precision.round(1.08341, RoundingMode::Up), 1.08341);
+    }
+
+    #[test]
+    fn test_round_by_tick_size() {
+        let precision = precision(2, Some(0.25));
+
+        assert_eq!(precision.round(4501.13, RoundingMode::Nearest), 4501.25);
+        assert_eq!(precision.round(4501.13, RoundingMode::Down), 4501.0);
+        assert!(precision.is_aligned(4501.75));
+        assert!(!precision.is_aligned(4501.7));
+    }
+
+    #[test]
+    fn test_invalid_precision() {
+        assert!(precision(5, None).validate().is_ok());
+        assert!(precision(11, None).validate().is_err());
+        assert!(precision(2, Some(0.0)).validate().is_err());
+        assert!(precision(2, Some(-0.25)).validate().is_err());
+    }
+}
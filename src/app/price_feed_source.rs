@@ -0,0 +1,59 @@
+use std::{collections::HashMap, sync::Arc};
+
+use my_nosql_contracts::{InstrumentMappingEntity, ProductSettings, YbPriceFeedSettings};
+use service_sdk::my_no_sql_sdk::reader::MyNoSqlDataReaderTcp;
+
+// Where the bridge gets its instrument map and the feed connection settings from
+#[async_trait::async_trait]
+pub trait PriceFeedSource: Send + Sync {
+    // Our symbol -> external symbol. None - there is no map for the liquidity provider
+    async fn get_instrument_map(&self, lp_id: &str) -> Option<HashMap<String, String>>;
+
+    async fn get_yb_settings(&self) -> Option<YbPriceFeedSettings>;
+}
+
+pub struct NoSqlPriceFeedSource {
+    pub product_settings: Arc<MyNoSqlDataReaderTcp<ProductSettings>>,
+    pub instrument_mapping: Arc<MyNoSqlDataReaderTcp<InstrumentMappingEntity>>,
+}
+
+#[async_trait::async_trait]
+impl PriceFeedSource for NoSqlPriceFeedSource {
+    async fn get_instrument_map(&self, lp_id: &str) -> Option<HashMap<String, String>> {
+        let map_entity = self
+            .instrument_mapping
+            .get_entity(InstrumentMappingEntity::PARTITION_KEY, lp_id)
+            .await?;
+
+        let result = map_entity
+            .map
+            .iter()
+            .map(|(our_symbol, external_symbol)| {
+                (our_symbol.to_string(), external_symbol.to_string())
+            })
+            .collect();
+
+        Some(result)
+    }
+
+    async fn get_yb_settings(&self) -> Option<YbPriceFeedSettings> {
+        self.product_settings.get_enum_case_model().await
+    }
+}
+
+// Fixed map and feed settings. Runs the bridge without nosql - replays and tests
+pub struct StaticPriceFeedSource {
+    pub instrument_map: HashMap<String, String>,
+    pub yb_settings: Option<YbPriceFeedSettings>,
+}
+
+#[async_trait::async_trait]
+impl PriceFeedSource for StaticPriceFeedSource {
+    async fn get_instrument_map(&self, _lp_id: &str) -> Option<HashMap<String, String>> {
+        Some(self.instrument_map.clone())
+    }
+
+    async fn get_yb_settings(&self) -> Option<YbPriceFeedSettings> {
+        self.yb_settings.clone()
+    }
+}
@@ -0,0 +1,490 @@
+use std::{
+    collections::{HashMap, VecDeque},
+    sync::{
+        atomic::{AtomicBool, AtomicU64, Ordering},
+        Arc, Mutex,
+    },
+};
+
+use rust_extensions::date_time::DateTimeAsMicroseconds;
+use serde::Serialize;
+use tokio::sync::Notify;
+
+use super::OutboundTick;
+
+// Transport-neutral message to a price consumer
+#[derive(Debug, Clone)]
+pub enum OutboundMessage {
+    Tick(Arc<OutboundTick>),
+    SnapshotBegin,
+    SnapshotEnd,
+    // Instrument has no updates for longer than its stale threshold
+    Stale { instrument_id: String, age_ms: i64 },
+    // First tick after Stale follows
+    Active { instrument_id: String },
+}
+
+impl OutboundMessage {
+    // Anything but a price. Legacy consumers do not understand these
+    pub fn is_control(&self) -> bool {
+        match self {
+            Self::Tick(_) => false,
+            Self::SnapshotBegin | Self::SnapshotEnd | Self::Stale { .. } | Self::Active { .. } => {
+                true
+            }
+        }
+    }
+}
+
+struct QueuedMessage {
+    // None - the tick was moved to the back of the queue by conflation
+    message: Option<OutboundMessage>,
+    enqueued: i64,
+}
+
+struct SubscriberQueue {
+    items: VecDeque<QueuedMessage>,
+    // Sequence number of the front item. Grows as items are drained
+    head_seq: u64,
+    // Moved ticks still in items
+    removed: usize,
+    // Instrument -> sequence number of its last queued tick. Entries behind head_seq are drained
+    last_ticks: HashMap<String, u64>,
+    // Instrument -> sequence number of its last queued STALE/ACTIVE
+    last_statuses: HashMap<String, u64>,
+    // Sequence number of the last queued SNAPSHOT_BEGIN/SNAPSHOT_END. Applies to every instrument
+    last_snapshot_mark: Option<u64>,
+    // Some - subscriber is closed. Reason is empty for a regular disconnect
+    close_reason: Option<String>,
+}
+
+impl SubscriberQueue {
+    fn len(&self) -> usize {
+        self.items.len() - self.removed
+    }
+
+    fn push(&mut self, message: OutboundMessage) {
+        let seq = self.head_seq + self.items.len() as u64;
+
+        // Instrument set is small and stable. Allocates only for a new instrument
+        match &message {
+            OutboundMessage::Tick(tick) => {
+                set_seq(&mut self.last_ticks, tick.instrument_id.as_str(), seq)
+            }
+            OutboundMessage::Stale { instrument_id, .. }
+            | OutboundMessage::Active { instrument_id } => {
+                set_seq(&mut self.last_statuses, instrument_id.as_str(), seq)
+            }
+            OutboundMessage::SnapshotBegin | OutboundMessage::SnapshotEnd => {
+                self.last_snapshot_mark = Some(seq)
+            }
+        }
+
+        self.items.push_back(QueuedMessage {
+            message: Some(message),
+            enqueued: DateTimeAsMicroseconds::now().unix_microseconds,
+        });
+    }
+
+    // Sequence number of the queued tick of the instrument
+    fn get_last_tick_seq(&self, instrument_id: &str) -> Option<u64> {
+        let seq = *self.last_ticks.get(instrument_id)?;
+
+        if seq < self.head_seq {
+            return None;
+        }
+
+        Some(seq)
+    }
+
+    // Control message of the instrument queued after the tick. Replacing the tick would move
+    // the new price ahead of it
+    fn has_control_after(&self, instrument_id: &str, tick_seq: u64) -> bool {
+        if self.last_snapshot_mark.map_or(false, |seq| seq > tick_seq) {
+            return true;
+        }
+
+        self.last_statuses
+            .get(instrument_id)
+            .map_or(false, |seq| *seq > tick_seq)
+    }
+
+    fn get_mut(&mut self, seq: u64) -> Option<&mut QueuedMessage> {
+        self.items.get_mut((seq - self.head_seq) as usize)
+    }
+
+    fn remove(&mut self, seq: u64) {
+        if let Some(queued) = self.get_mut(seq) {
+            if queued.message.take().is_some() {
+                self.removed += 1;
+            }
+        }
+    }
+
+    fn drain(&mut self, max_batch_size: usize) -> Vec<OutboundMessage> {
+        let mut result = Vec::with_capacity(self.len().min(max_batch_size));
+
+        while result.len() < max_batch_size {
+            let queued = match self.items.pop_front() {
+                Some(queued) => queued,
+                None => break,
+            };
+
+            self.head_seq += 1;
+
+            match queued.message {
+                Some(message) => result.push(message),
+                None => self.removed -= 1,
+            }
+        }
+
+        result
+    }
+
+    fn get_oldest_enqueued(&self) -> Option<i64> {
+        self.items
+            .iter()
+            .find(|queued| queued.message.is_some())
+            .map(|queued| queued.enqueued)
+    }
+
+    fn close(&mut self, reason: String) {
+        self.items.clear();
+        self.removed = 0;
+        self.last_ticks.clear();
+        self.last_statuses.clear();
+        self.last_snapshot_mark = None;
+        self.close_reason = Some(reason);
+    }
+}
+
+fn set_seq(items: &mut HashMap<String, u64>, instrument_id: &str, seq: u64) {
+    match items.get_mut(instrument_id) {
+        Some(last_seq) => *last_seq = seq,
+        None => {
+            items.insert(instrument_id.to_string(), seq);
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct PriceSubscriberStatus {
+    pub id: i32,
+    pub addr: String,
+    pub queue_len: usize,
+    pub lag_ms: i64,
+    pub conflated: u64,
+}
+
+// Bounded queue between the publishing path and one consumer. Publishing never waits.
+// Over the soft limit ticks replace queued ticks of the same instrument,
+// over the hard limit the subscriber is closed and has to be disconnected
+pub struct PriceSubscriber {
+    pub id: i32,
+    pub addr: String,
+    queue: Mutex<SubscriberQueue>,
+    soft_queue_limit: usize,
+    hard_queue_limit: usize,
+    notify: Notify,
+    conflated: AtomicU64,
+    // Off - control messages are dropped. Turned on once the consumer proves it knows them
+    control_messages: AtomicBool,
+}
+
+impl PriceSubscriber {
+    pub fn new(id: i32, addr: String, soft_queue_limit: usize, hard_queue_limit: usize) -> Self {
+        Self {
+            id,
+            addr,
+            queue: Mutex::new(SubscriberQueue {
+                items: VecDeque::new(),
+                head_seq: 0,
+                removed: 0,
+                last_ticks: HashMap::new(),
+                last_statuses: HashMap::new(),
+                last_snapshot_mark: None,
+                close_reason: None,
+            }),
+            soft_queue_limit,
+            hard_queue_limit: hard_queue_limit.max(1),
+            notify: Notify::new(),
+            conflated: AtomicU64::new(0),
+            control_messages: AtomicBool::new(false),
+        }
+    }
+
+    // true - control messages were off until now
+    pub fn enable_control_messages(&self) -> bool {
+        !self.control_messages.swap(true, Ordering::Relaxed)
+    }
+
+    pub fn push(&self, message: OutboundMessage) {
+        if message.is_control() && !self.control_messages.load(Ordering::Relaxed) {
+            return;
+        }
+
+        {
+            let mut queue = self.queue.lock().unwrap();
+
+            if queue.close_reason.is_some() {
+                return;
+            }
+
+            if queue.len() >= self.soft_queue_limit && self.conflate(&mut queue, &message) {
+                return;
+            }
+
+            if queue.len() >= self.hard_queue_limit {
+                let reason = format!(
+                    "Queue depth {} reached hard limit {}",
+                    queue.len(),
+                    self.hard_queue_limit
+                );
+                queue.close(reason);
+            } else {
+                queue.push(message);
+            }
+        }
+
+        self.notify.notify_one();
+    }
+
+    // Replaces the queued tick of the same instrument and keeps its place in the queue.
+    // If a control message of the instrument follows it, the tick moves to the back instead
+    fn conflate(&self, queue: &mut SubscriberQueue, message: &OutboundMessage) -> bool {
+        let tick = match message {
+            OutboundMessage::Tick(tick) => tick,
+            _ => return false,
+        };
+
+        let seq = match queue.get_last_tick_seq(tick.instrument_id.as_str()) {
+            Some(seq) => seq,
+            None => return false,
+        };
+
+        if queue.has_control_after(tick.instrument_id.as_str(), seq) {
+            queue.remove(seq);
+            queue.push(message.clone());
+        } else if let Some(queued) = queue.get_mut(seq) {
+            queued.message = Some(message.clone());
+        }
+
+        self.conflated.fetch_add(1, Ordering::Relaxed);
+        true
+    }
+
+    // Waits for messages. None - subscriber is closed
+    pub async fn next_batch(&self, max_batch_size: usize) -> Option<Vec<OutboundMessage>> {
+        loop {
+            {
+                let mut queue = self.queue.lock().unwrap();
+
+                if queue.close_reason.is_some() {
+                    return None;
+                }
+
+                if queue.len() > 0 {
+                    return Some(queue.drain(max_batch_size));
+                }
+            }
+
+            self.notify.notified().await;
+        }
+    }
+
+    pub fn close(&self) {
+        self.close_with_reason(String::new());
+    }
+
+    pub fn close_with_reason(&self, reason: String) {
+        {
+            let mut queue = self.queue.lock().unwrap();
+
+            if queue.close_reason.is_some() {
+                return;
+            }
+
+            queue.close(reason);
+        }
+
+        self.notify.notify_one();
+    }
+
+    // Slow consumer policy. Closes the subscriber if its oldest message waits over max_lag_ms
+    pub fn close_if_lagging(&self, now: DateTimeAsMicroseconds, max_lag_ms: i64) -> bool {
+        let status = self.get_status(now);
+
+        if status.lag_ms <= max_lag_ms {
+            return false;
+        }
+
+        // Writer task logs the reason and drops the connection
+        self.close_with_reason(format!(
+            "Lag {} ms is over {} ms with {} queued messages",
+            status.lag_ms, max_lag_ms, status.queue_len
+        ));
+
+        true
+    }
+
+    // Not empty if the subscriber was closed by the slow consumer policy
+    pub fn get_close_reason(&self) -> Option<String> {
+        let queue = self.queue.lock().unwrap();
+        queue
+            .close_reason
+            .clone()
+            .filter(|reason| !reason.is_empty())
+    }
+
+    pub fn get_status(&self, now: DateTimeAsMicroseconds) -> PriceSubscriberStatus {
+        let queue = self.queue.lock().unwrap();
+
+        let lag_ms = match queue.get_oldest_enqueued() {
+            Some(enqueued) => (now.unix_microseconds - enqueued) / 1000,
+            None => 0,
+        };
+
+        PriceSubscriberStatus {
+            id: self.id,
+            addr: self.addr.clone(),
+            queue_len: queue.len(),
+            lag_ms,
+            conflated: self.conflated.load(Ordering::Relaxed),
+        }
+    }
+}
+
+// Queue fixture shared by the subscriber and slow consumer tests
+#[cfg(test)]
+pub fn test_tick(instrument_id: &str, bid: f64) -> OutboundMessage {
+    OutboundMessage::Tick(Arc::new(OutboundTick {
+        instrument_id: instrument_id.to_string(),
+        bid,
+        ask: bid + 0.0001,
+        date: DateTimeAsMicroseconds::new(0),
+        date_source: crate::your_bourse::QuoteTimeSource::ReceiveTime,
+        receive_time: DateTimeAsMicroseconds::new(0),
+        bid_size: None,
+        ask_size: None,
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use rust_extensions::date_time::DateTimeAsMicroseconds;
+
+    use super::{test_tick as tick, OutboundMessage, PriceSubscriber};
+
+    #[tokio::test]
+    async fn test_conflates_over_soft_limit() {
+        let subscriber = PriceSubscriber::new(1, "test".to_string(), 2, 10);
+
+        subscriber.push(tick("EURUSD", 1.1));
+        subscriber.push(tick("GBPUSD", 1.2));
+        subscriber.push(tick("EURUSD", 1.3));
+
+        let status = subscriber.get_status(DateTimeAsMicroseconds::now());
+        assert_eq!(status.queue_len, 2);
+        assert_eq!(status.conflated, 1);
+
+        let batch = subscriber.next_batch(10).await.unwrap();
+        assert!(matches!(&batch[0], OutboundMessage::Tick(tick) if tick.bid == 1.3));
+    }
+
+    #[tokio::test]
+    async fn test_does_not_conflate_into_drained_ticks() {
+        let subscriber = PriceSubscriber::new(1, "test".to_string(), 2, 10);
+
+        subscriber.push(tick("EURUSD", 1.1));
+        subscriber.push(tick("GBPUSD", 1.2));
+        assert_eq!(subscriber.next_batch(10).await.unwrap().len(), 2);
+
+        subscriber.push(tick("USDJPY", 150.0));
+        subscriber.push(tick("USDCHF", 0.9));
+        subscriber.push(tick("EURUSD", 1.3));
+        subscriber.push(tick("USDJPY", 150.1));
+
+        let status = subscriber.get_status(DateTimeAsMicroseconds::now());
+        assert_eq!(status.queue_len, 3);
+        assert_eq!(status.conflated, 1);
+
+        let batch = subscriber.next_batch(10).await.unwrap();
+        assert!(matches!(&batch[0], OutboundMessage::Tick(tick) if tick.bid == 150.1));
+        assert!(matches!(&batch[2], OutboundMessage::Tick(tick) if tick.bid == 1.3));
+    }
+
+    #[tokio::test]
+    async fn test_conflated_tick_stays_behind_control_message() {
+        let subscriber = PriceSubscriber::new(1, "test".to_string(), 2, 10);
+        subscriber.enable_control_messages();
+
+        subscriber.push(tick("EURUSD", 1.1));
+        subscriber.push(OutboundMessage::Stale {
+            instrument_id: "EURUSD".to_string(),
+            age_ms: 5_000,
+        });
+        subscriber.push(tick("GBPUSD", 1.2));
+        subscriber.push(tick("EURUSD", 1.3));
+        subscriber.push(tick("GBPUSD", 1.4));
+        subscriber.push(tick("EURUSD", 1.5));
+
+        let status = subscriber.get_status(DateTimeAsMicroseconds::now());
+        assert_eq!(status.queue_len, 3);
+        assert_eq!(status.conflated, 3);
+
+        let batch = subscriber.next_batch(10).await.unwrap();
+        assert_eq!(batch.len(), 3);
+        assert!(matches!(&batch[0], OutboundMessage::Stale { .. }));
+        assert!(matches!(&batch[1], OutboundMessage::Tick(tick) if tick.bid == 1.4));
+        assert!(matches!(&batch[2], OutboundMessage::Tick(tick) if tick.bid == 1.5));
+    }
+
+    #[tokio::test]
+    async fn test_closed_over_max_lag() {
+        let subscriber = PriceSubscriber::new(1, "test".to_string(), 10, 20);
+        subscriber.push(tick("EURUSD", 1.1));
+
+        let now = DateTimeAsMicroseconds::now();
+        assert!(!subscriber.close_if_lagging(now, 5_000));
+        assert!(subscriber.get_close_reason().is_none());
+
+        let later = DateTimeAsMicroseconds::new(now.unix_microseconds + 6_000_000);
+        assert!(subscriber.close_if_lagging(later, 5_000));
+        assert!(subscriber.get_close_reason().is_some());
+        assert!(subscriber.next_batch(10).await.is_none());
+    }
+
+    #[tokio::test]
+    async fn test_closed_over_hard_limit() {
+        let subscriber = PriceSubscriber::new(1, "test".to_string(), 10, 2);
+
+        subscriber.push(tick("EURUSD", 1.1));
+        subscriber.push(tick("GBPUSD", 1.2));
+        assert!(subscriber.get_close_reason().is_none());
+
+        subscriber.push(tick("USDJPY", 150.0));
+        assert!(subscriber.get_close_reason().is_some());
+        assert!(subscriber.next_batch(10).await.is_none());
+    }
+
+    #[tokio::test]
+    async fn test_control_messages_need_opt_in() {
+        let subscriber = PriceSubscriber::new(1, "test".to_string(), 10, 20);
+
+        subscriber.push(OutboundMessage::SnapshotBegin);
+        subscriber.push(tick("EURUSD", 1.1));
+        subscriber.push(OutboundMessage::SnapshotEnd);
+
+        let batch = subscriber.next_batch(10).await.unwrap();
+        assert_eq!(batch.len(), 1);
+        assert!(matches!(&batch[0], OutboundMessage::Tick(_)));
+
+        subscriber.enable_control_messages();
+        subscriber.push(OutboundMessage::Active {
+            instrument_id: "EURUSD".to_string(),
+        });
+
+        let batch = subscriber.next_batch(10).await.unwrap();
+        assert!(matches!(&batch[0], OutboundMessage::Active { .. }));
+    }
+}
@@ -0,0 +1,71 @@
+use crate::settings::PriceTransformSettingsModel;
+
+// Order: invert, multiply, add offset. Inversion swaps sides: bid = 1/ask, ask = 1/bid
+pub fn apply_price_transform(
+    transform: &PriceTransformSettingsModel,
+    bid: f64,
+    ask: f64,
+) -> Result<(f64, f64), String> {
+    let (mut bid, mut ask) = (bid, ask);
+
+    if transform.invert {
+        if bid <= 0.0 || ask <= 0.0 {
+            return Err(format!("Can not invert bid:{} ask:{}", bid, ask));
+        }
+
+        (bid, ask) = (1.0 / ask, 1.0 / bid);
+    }
+
+    if let Some(multiplier) = transform.multiplier {
+        bid *= multiplier;
+        ask *= multiplier;
+
+        // Negative multiplier flips the book
+        if multiplier < 0.0 {
+            (bid, ask) = (ask, bid);
+        }
+    }
+
+    if let Some(offset) = transform.offset {
+        bid += offset;
+        ask += offset;
+    }
+
+    Ok((bid, ask))
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::settings::PriceTransformSettingsModel;
+
+    #[test]
+    fn test_invert_swaps_sides() {
+        let transform = PriceTransformSettingsModel {
+            invert: true,
+            multiplier: None,
+            offset: None,
+        };
+
+        let (bid, ask) = super::apply_price_transform(&transform, 1.25, 1.28).unwrap();
+
+        assert_eq!(bid, 1.0 / 1.28);
+        assert_eq!(ask, 1.0 / 1.25);
+        assert!(bid < ask);
+
+        assert!(super::apply_price_transform(&transform, 0.0, 1.28).is_err());
+    }
+
+    #[test]
+    fn test_multiplier_then_offset() {
+        let transform = PriceTransformSettingsModel {
+            invert: false,
+            multiplier: Some(100.0),
+            offset: Some(-0.5),
+        };
+
+        let (bid, ask) = super::apply_price_transform(&transform, 1.5, 1.75).unwrap();
+
+        assert_eq!(bid, 149.5);
+        assert_eq!(ask, 174.5);
+    }
+}
@@ -0,0 +1,14 @@
+use rust_extensions::date_time::DateTimeAsMicroseconds;
+
+// Price of one of our instruments as it left the bridge
+#[derive(Debug, Clone)]
+pub struct PublishedBidAsk {
+    pub instrument_id: String,
+    pub src_id: String,
+    // LP price before markup
+    pub raw_bid: f64,
+    pub raw_ask: f64,
+    pub bid: f64,
+    pub ask: f64,
+    pub date: DateTimeAsMicroseconds,
+}
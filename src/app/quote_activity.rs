@@ -0,0 +1,234 @@
+use std::collections::HashMap;
+
+use rust_extensions::date_time::DateTimeAsMicroseconds;
+use tokio::sync::Mutex;
+
+#[derive(Debug, Clone)]
+pub struct SymbolQuoteActivity {
+    pub subscribed: DateTimeAsMicroseconds,
+    pub last_quote: Option<DateTimeAsMicroseconds>,
+    pub last_resubscribe: Option<DateTimeAsMicroseconds>,
+    pub resubscribe_attempts: u32,
+}
+
+impl SymbolQuoteActivity {
+    // Silence is counted from the latest of subscribe, quote or resubscribe moments
+    fn get_silence_started(&self) -> DateTimeAsMicroseconds {
+        let mut result = self.subscribed;
+
+        for moment in [self.last_quote, self.last_resubscribe]
+            .into_iter()
+            .flatten()
+        {
+            if moment.unix_microseconds > result.unix_microseconds {
+                result = moment;
+            }
+        }
+
+        result
+    }
+
+    // The first attempt waits for the threshold. Every next one waits twice as long
+    fn get_required_silence_sec(&self, threshold_sec: u64, max_interval_sec: u64) -> u64 {
+        if self.resubscribe_attempts == 0 {
+            return threshold_sec;
+        }
+
+        let backoff_sec = threshold_sec.saturating_mul(1 << self.resubscribe_attempts.min(16));
+        backoff_sec.min(max_interval_sec.max(threshold_sec))
+    }
+}
+
+pub struct SilentSymbol {
+    pub external_symbol: String,
+    pub silence_sec: i64,
+    pub resubscribe_attempts: u32,
+}
+
+pub struct QuoteActivityTracker {
+    items: Mutex<HashMap<String, SymbolQuoteActivity>>,
+}
+
+impl QuoteActivityTracker {
+    pub fn new() -> Self {
+        Self {
+            items: Mutex::new(HashMap::new()),
+        }
+    }
+
+    pub async fn subscribed(&self, external_symbol: &str) {
+        let mut write_access = self.items.lock().await;
+        write_access.insert(
+            external_symbol.to_string(),
+            SymbolQuoteActivity {
+                subscribed: DateTimeAsMicroseconds::now(),
+                last_quote: None,
+                last_resubscribe: None,
+                resubscribe_attempts: 0,
+            },
+        );
+    }
+
+    pub async fn quote_received(&self, external_symbol: &str) {
+        let mut write_access = self.items.lock().await;
+        if let Some(activity) = write_access.get_mut(external_symbol) {
+            activity.last_quote = Some(DateTimeAsMicroseconds::now());
+            activity.resubscribe_attempts = 0;
+        }
+    }
+
+    // Latest subscribe or quote moment across all symbols. None - nothing is subscribed
+    pub async fn get_last_session_activity(&self) -> Option<DateTimeAsMicroseconds> {
+        let read_access = self.items.lock().await;
+
+        read_access
+            .values()
+            .map(|activity| match activity.last_quote {
+                Some(last_quote)
+                    if last_quote.unix_microseconds > activity.subscribed.unix_microseconds =>
+                {
+                    last_quote
+                }
+                _ => activity.subscribed,
+            })
+            .max_by_key(|moment| moment.unix_microseconds)
+    }
+
+    pub async fn get_all(&self) -> HashMap<String, SymbolQuoteActivity> {
+        let read_access = self.items.lock().await;
+        read_access.clone()
+    }
+
+    pub async fn clear(&self) {
+        let mut write_access = self.items.lock().await;
+        write_access.clear();
+    }
+
+    // Returns symbols silent longer than their threshold, backed off by the attempts made,
+    // and counts them as resubscribed
+    pub async fn resubscribe_silent(
+        &self,
+        now: DateTimeAsMicroseconds,
+        get_threshold_sec: impl Fn(&str) -> u64,
+        max_interval_sec: u64,
+    ) -> Vec<SilentSymbol> {
+        let mut write_access = self.items.lock().await;
+
+        let mut result = Vec::new();
+
+        for (external_symbol, activity) in write_access.iter_mut() {
+            let silence_sec = (now.unix_microseconds
+                - activity.get_silence_started().unix_microseconds)
+                / 1_000_000;
+
+            let required_silence_sec = activity
+                .get_required_silence_sec(get_threshold_sec(external_symbol), max_interval_sec);
+
+            if silence_sec < required_silence_sec as i64 {
+                continue;
+            }
+
+            activity.last_resubscribe = Some(now);
+            activity.resubscribe_attempts += 1;
+
+            result.push(SilentSymbol {
+                external_symbol: external_symbol.to_string(),
+                silence_sec,
+                resubscribe_attempts: activity.resubscribe_attempts,
+            });
+        }
+
+        result
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rust_extensions::date_time::DateTimeAsMicroseconds;
+
+    use super::{QuoteActivityTracker, SymbolQuoteActivity};
+
+    fn create_tracker(subscribed: DateTimeAsMicroseconds) -> QuoteActivityTracker {
+        let tracker = QuoteActivityTracker::new();
+
+        tracker.items.try_lock().unwrap().insert(
+            "EUR/USD".to_string(),
+            SymbolQuoteActivity {
+                subscribed,
+                last_quote: None,
+                last_resubscribe: None,
+                resubscribe_attempts: 0,
+            },
+        );
+
+        tracker
+    }
+
+    fn at_sec(sec: i64) -> DateTimeAsMicroseconds {
+        DateTimeAsMicroseconds::new(sec * 1_000_000)
+    }
+
+    #[tokio::test]
+    async fn test_resubscribe_backs_off() {
+        let tracker = create_tracker(at_sec(0));
+
+        assert!(tracker
+            .resubscribe_silent(at_sec(59), |_| 60, 600)
+            .await
+            .is_empty());
+
+        let silent = tracker.resubscribe_silent(at_sec(60), |_| 60, 600).await;
+        assert_eq!(silent.len(), 1);
+        assert_eq!(silent[0].resubscribe_attempts, 1);
+
+        // Second attempt waits 120 sec after the first one
+        assert!(tracker
+            .resubscribe_silent(at_sec(179), |_| 60, 600)
+            .await
+            .is_empty());
+        let silent = tracker.resubscribe_silent(at_sec(180), |_| 60, 600).await;
+        assert_eq!(silent[0].resubscribe_attempts, 2);
+
+        // Third one 240 sec, then it is capped by the max interval
+        assert!(tracker
+            .resubscribe_silent(at_sec(419), |_| 60, 300)
+            .await
+            .is_empty());
+        assert_eq!(
+            tracker
+                .resubscribe_silent(at_sec(420), |_| 60, 300)
+                .await
+                .len(),
+            1
+        );
+        assert!(tracker
+            .resubscribe_silent(at_sec(719), |_| 60, 300)
+            .await
+            .is_empty());
+        assert_eq!(
+            tracker
+                .resubscribe_silent(at_sec(720), |_| 60, 300)
+                .await
+                .len(),
+            1
+        );
+    }
+
+    #[tokio::test]
+    async fn test_quote_resets_attempts() {
+        let tracker = create_tracker(at_sec(0));
+
+        assert_eq!(
+            tracker
+                .resubscribe_silent(at_sec(60), |_| 60, 600)
+                .await
+                .len(),
+            1
+        );
+
+        tracker.quote_received("EUR/USD").await;
+
+        let activity = tracker.get_all().await.remove("EUR/USD").unwrap();
+        assert_eq!(activity.resubscribe_attempts, 0);
+    }
+}
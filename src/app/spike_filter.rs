@@ -0,0 +1,251 @@
+use std::collections::HashMap;
+
+use rust_extensions::date_time::DateTimeAsMicroseconds;
+use tokio::sync::Mutex;
+
+use crate::settings::SpikeFilterRuleModel;
+
+use super::LogThrottle;
+
+// A spiking symbol suppresses quote after quote. One warning per symbol per period is enough
+const SUPPRESSED_LOG_INTERVAL_SEC: i64 = 10;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum SpikeCheckResult {
+    Accepted,
+    // Quote moved too far but the following quotes agreed with it
+    Confirmed,
+    Suppressed { move_percent: f64, move_pips: f64 },
+}
+
+struct AcceptedQuote {
+    mid: f64,
+    time: DateTimeAsMicroseconds,
+}
+
+pub struct SymbolSpikeState {
+    last_accepted: Option<AcceptedQuote>,
+    // Suppressed quotes waiting for confirmation. All of them agree with the first one
+    candidates: Vec<f64>,
+    pub suppressed_count: u64,
+}
+
+impl SymbolSpikeState {
+    pub fn new() -> Self {
+        Self {
+            last_accepted: None,
+            candidates: Vec::new(),
+            suppressed_count: 0,
+        }
+    }
+
+    pub fn check(
+        &mut self,
+        rule: &SpikeFilterRuleModel,
+        bid: f64,
+        ask: f64,
+        now: DateTimeAsMicroseconds,
+    ) -> SpikeCheckResult {
+        let mid = (bid + ask) / 2.0;
+
+        let (last_mid, last_time) = match self.last_accepted.as_ref() {
+            Some(last_accepted) => (last_accepted.mid, last_accepted.time),
+            None => return self.accept(mid, now, SpikeCheckResult::Accepted),
+        };
+
+        // Too long since the last accepted quote - market is allowed to move anywhere
+        let window_micros = rule.window_sec as i64 * 1_000_000;
+        if now.unix_microseconds - last_time.unix_microseconds > window_micros {
+            return self.accept(mid, now, SpikeCheckResult::Accepted);
+        }
+
+        let (move_percent, move_pips) = get_move(rule, last_mid, mid);
+
+        if !rule.is_spike(move_percent, move_pips) {
+            return self.accept(mid, now, SpikeCheckResult::Accepted);
+        }
+
+        let agrees_with_candidates = match self.candidates.first() {
+            Some(first_candidate) => {
+                let (move_percent, move_pips) = get_move(rule, *first_candidate, mid);
+                !rule.is_spike(move_percent, move_pips)
+            }
+            None => false,
+        };
+
+        if !agrees_with_candidates {
+            self.candidates.clear();
+        }
+
+        self.candidates.push(mid);
+
+        if let Some(confirm_after_quotes) = rule.confirm_after_quotes {
+            if self.candidates.len() > confirm_after_quotes as usize {
+                return self.accept(mid, now, SpikeCheckResult::Confirmed);
+            }
+        }
+
+        self.suppressed_count += 1;
+
+        SpikeCheckResult::Suppressed {
+            move_percent,
+            move_pips,
+        }
+    }
+
+    fn accept(
+        &mut self,
+        mid: f64,
+        now: DateTimeAsMicroseconds,
+        result: SpikeCheckResult,
+    ) -> SpikeCheckResult {
+        self.last_accepted = Some(AcceptedQuote { mid, time: now });
+        self.candidates.clear();
+        result
+    }
+}
+
+fn get_move(rule: &SpikeFilterRuleModel, from_mid: f64, to_mid: f64) -> (f64, f64) {
+    let diff = (to_mid - from_mid).abs();
+    let move_percent = diff / from_mid * 100.0;
+    let move_pips = match rule.pip_size {
+        Some(pip_size) => diff / pip_size,
+        None => 0.0,
+    };
+
+    (move_percent, move_pips)
+}
+
+pub struct SpikeFilter {
+    items: Mutex<HashMap<String, SymbolSpikeState>>,
+    log_throttle: Mutex<LogThrottle>,
+}
+
+impl SpikeFilter {
+    pub fn new() -> Self {
+        Self {
+            items: Mutex::new(HashMap::new()),
+            log_throttle: Mutex::new(LogThrottle::new(SUPPRESSED_LOG_INTERVAL_SEC)),
+        }
+    }
+
+    pub async fn check(
+        &self,
+        external_symbol: &str,
+        rule: &SpikeFilterRuleModel,
+        bid: f64,
+        ask: f64,
+        now: DateTimeAsMicroseconds,
+    ) -> SpikeCheckResult {
+        let mut write_access = self.items.lock().await;
+
+        if !write_access.contains_key(external_symbol) {
+            write_access.insert(external_symbol.to_string(), SymbolSpikeState::new());
+        }
+
+        write_access
+            .get_mut(external_symbol)
+            .unwrap()
+            .check(rule, bid, ask, now)
+    }
+
+    pub async fn get_suppressed_counters(&self) -> HashMap<String, u64> {
+        let read_access = self.items.lock().await;
+        read_access
+            .iter()
+            .map(|(external_symbol, state)| (external_symbol.to_string(), state.suppressed_count))
+            .collect()
+    }
+
+    // Some(suppressed quotes not logged since the last warning) - time to log this one
+    pub async fn try_log_suppressed(
+        &self,
+        external_symbol: &str,
+        now: DateTimeAsMicroseconds,
+    ) -> Option<u64> {
+        let mut write_access = self.log_throttle.lock().await;
+        write_access.try_log(external_symbol, now)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rust_extensions::date_time::DateTimeAsMicroseconds;
+
+    use super::{SpikeCheckResult, SymbolSpikeState};
+    use crate::settings::SpikeFilterRuleModel;
+
+    fn rule(confirm_after_quotes: Option<u32>) -> SpikeFilterRuleModel {
+        SpikeFilterRuleModel {
+            max_move_percent: Some(1.0),
+            max_move_pips: None,
+            pip_size: None,
+            window_sec: 60,
+            confirm_after_quotes,
+        }
+    }
+
+    fn at(sec: i64) -> DateTimeAsMicroseconds {
+        DateTimeAsMicroseconds::new(1_700_000_000_000_000 + sec * 1_000_000)
+    }
+
+    #[test]
+    fn test_spike_is_suppressed_and_normal_quote_passes() {
+        let rule = rule(None);
+        let mut state = SymbolSpikeState::new();
+
+        assert_eq!(
+            state.check(&rule, 1.0, 1.0, at(0)),
+            SpikeCheckResult::Accepted
+        );
+        assert!(matches!(
+            state.check(&rule, 1.1, 1.1, at(1)),
+            SpikeCheckResult::Suppressed { .. }
+        ));
+        assert_eq!(
+            state.check(&rule, 1.005, 1.005, at(2)),
+            SpikeCheckResult::Accepted
+        );
+        assert_eq!(state.suppressed_count, 1);
+    }
+
+    #[test]
+    fn test_spike_is_confirmed_by_following_quotes() {
+        let rule = rule(Some(2));
+        let mut state = SymbolSpikeState::new();
+
+        state.check(&rule, 1.0, 1.0, at(0));
+
+        assert!(matches!(
+            state.check(&rule, 1.1, 1.1, at(1)),
+            SpikeCheckResult::Suppressed { .. }
+        ));
+        assert!(matches!(
+            state.check(&rule, 1.101, 1.101, at(2)),
+            SpikeCheckResult::Suppressed { .. }
+        ));
+        assert_eq!(
+            state.check(&rule, 1.099, 1.099, at(3)),
+            SpikeCheckResult::Confirmed
+        );
+
+        // New level is the reference now
+        assert_eq!(
+            state.check(&rule, 1.1, 1.1, at(4)),
+            SpikeCheckResult::Accepted
+        );
+    }
+
+    #[test]
+    fn test_move_after_window_is_accepted() {
+        let rule = rule(None);
+        let mut state = SymbolSpikeState::new();
+
+        state.check(&rule, 1.0, 1.0, at(0));
+
+        assert_eq!(
+            state.check(&rule, 1.1, 1.1, at(61)),
+            SpikeCheckResult::Accepted
+        );
+    }
+}
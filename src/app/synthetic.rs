@@ -0,0 +1,193 @@
+use std::collections::HashMap;
+
+use rust_extensions::date_time::DateTimeAsMicroseconds;
+use serde::{Deserialize, Serialize};
+
+use crate::settings::SyntheticInstrumentSettingsModel;
+
+use super::ExternalLastPrice;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum SyntheticFormula {
+    // Cross rates: bid = bid1 * bid2 * ...; ask = ask1 * ask2 * ...
+    Product,
+    // Baskets: bid = bid1 + bid2 + ...; ask = ask1 + ask2 + ... Weights go to leg multipliers
+    Sum,
+}
+
+#[derive(Debug, Clone)]
+pub struct SyntheticPrice {
+    pub bid: f64,
+    pub ask: f64,
+    // Latest date among the legs
+    pub date: DateTimeAsMicroseconds,
+    // Latest receive time among the legs
+    pub receive_time: DateTimeAsMicroseconds,
+}
+
+#[derive(Debug)]
+pub enum SyntheticSkipReason {
+    NoQuote(String),
+    StaleLeg {
+        external_symbol: String,
+        age_ms: i64,
+    },
+    Invalid(String),
+}
+
+pub fn calculate_synthetic_price(
+    synthetic: &SyntheticInstrumentSettingsModel,
+    legs: &HashMap<String, ExternalLastPrice>,
+    now: DateTimeAsMicroseconds,
+) -> Result<SyntheticPrice, SyntheticSkipReason> {
+    if synthetic.legs.is_empty() {
+        return Err(SyntheticSkipReason::Invalid(
+            "No legs configured".to_string(),
+        ));
+    }
+
+    let (mut bid, mut ask) = match synthetic.formula {
+        SyntheticFormula::Product => (1.0, 1.0),
+        SyntheticFormula::Sum => (0.0, 0.0),
+    };
+
+    let mut date = DateTimeAsMicroseconds::new(0);
+    let mut receive_time = DateTimeAsMicroseconds::new(0);
+
+    for leg in &synthetic.legs {
+        let quote = legs
+            .get(&leg.external_symbol)
+            .ok_or_else(|| SyntheticSkipReason::NoQuote(leg.external_symbol.to_string()))?;
+
+        let age_ms = (now.unix_microseconds - quote.receive_time.unix_microseconds) / 1000;
+
+        if age_ms > synthetic.max_leg_age_ms as i64 {
+            return Err(SyntheticSkipReason::StaleLeg {
+                external_symbol: leg.external_symbol.to_string(),
+                age_ms,
+            });
+        }
+
+        let (leg_bid, leg_ask) = match leg.transform.as_ref() {
+            Some(transform) => super::apply_price_transform(transform, quote.bid, quote.ask)
+                .map_err(|err| {
+                    SyntheticSkipReason::Invalid(format!("{}: {}", leg.external_symbol, err))
+                })?,
+            None => (quote.bid, quote.ask),
+        };
+
+        match synthetic.formula {
+            SyntheticFormula::Product => {
+                bid *= leg_bid;
+                ask *= leg_ask;
+            }
+            SyntheticFormula::Sum => {
+                bid += leg_bid;
+                ask += leg_ask;
+            }
+        }
+
+        if quote.source_time.unix_microseconds > date.unix_microseconds {
+            date = quote.source_time;
+        }
+
+        if quote.receive_time.unix_microseconds > receive_time.unix_microseconds {
+            receive_time = quote.receive_time;
+        }
+    }
+
+    Ok(SyntheticPrice {
+        bid,
+        ask,
+        date,
+        receive_time,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use rust_extensions::date_time::DateTimeAsMicroseconds;
+
+    use super::{SyntheticFormula, SyntheticSkipReason};
+    use crate::{
+        app::ExternalLastPrice,
+        settings::{
+            PriceTransformSettingsModel, SyntheticInstrumentSettingsModel,
+            SyntheticLegSettingsModel,
+        },
+    };
+
+    fn leg_quote(bid: f64, ask: f64, received: i64) -> ExternalLastPrice {
+        ExternalLastPrice {
+            external_symbol: String::new(),
+            bid,
+            ask,
+            source_time: DateTimeAsMicroseconds::new(received),
+            receive_time: DateTimeAsMicroseconds::new(received),
+            update_count: 1,
+        }
+    }
+
+    fn eur_gbp() -> SyntheticInstrumentSettingsModel {
+        SyntheticInstrumentSettingsModel {
+            formula: SyntheticFormula::Product,
+            legs: vec![
+                SyntheticLegSettingsModel {
+                    external_symbol: "EURUSD".to_string(),
+                    transform: None,
+                },
+                SyntheticLegSettingsModel {
+                    external_symbol: "GBPUSD".to_string(),
+                    transform: Some(PriceTransformSettingsModel {
+                        invert: true,
+                        multiplier: None,
+                        offset: None,
+                    }),
+                },
+            ],
+            max_leg_age_ms: 1000,
+        }
+    }
+
+    #[test]
+    fn test_cross_rate_from_two_legs() {
+        let mut legs = HashMap::new();
+        legs.insert("EURUSD".to_string(), leg_quote(1.08, 1.0802, 1_000_000));
+        legs.insert("GBPUSD".to_string(), leg_quote(1.25, 1.2503, 1_500_000));
+
+        let price = super::calculate_synthetic_price(
+            &eur_gbp(),
+            &legs,
+            DateTimeAsMicroseconds::new(1_600_000),
+        )
+        .unwrap();
+
+        assert!((price.bid - 1.08 / 1.2503).abs() < 1e-12);
+        assert!((price.ask - 1.0802 / 1.25).abs() < 1e-12);
+        assert_eq!(price.date.unix_microseconds, 1_500_000);
+    }
+
+    #[test]
+    fn test_stale_or_missing_leg_is_skipped() {
+        let mut legs = HashMap::new();
+        legs.insert("EURUSD".to_string(), leg_quote(1.08, 1.0802, 1_000_000));
+
+        let result = super::calculate_synthetic_price(
+            &eur_gbp(),
+            &legs,
+            DateTimeAsMicroseconds::new(1_600_000),
+        );
+        assert!(matches!(result, Err(SyntheticSkipReason::NoQuote(_))));
+
+        legs.insert("GBPUSD".to_string(), leg_quote(1.25, 1.2503, 1_500_000));
+
+        let result = super::calculate_synthetic_price(
+            &eur_gbp(),
+            &legs,
+            DateTimeAsMicroseconds::new(2_500_000),
+        );
+        assert!(matches!(result, Err(SyntheticSkipReason::StaleLeg { .. })));
+    }
+}
@@ -0,0 +1,121 @@
+use std::{sync::Arc, time::Duration};
+
+use your_bourse_bridge::{
+    app::AppContext,
+    fix_journal::{read_fix_log, replay_fix_log, FixLogEntry, FixReplaySettings},
+    settings::SettingsReader,
+    timers::ConflationFlushTimer,
+};
+
+const USAGE: &'static str =
+    "Usage: fix-replay <journal-or-log-file> [--speed <factor>] [--no-delay] [--identity-map]";
+
+struct ReplayArgs {
+    file: String,
+    speed: f64,
+    no_delay: bool,
+    identity_map: bool,
+}
+
+impl ReplayArgs {
+    fn parse() -> Self {
+        let mut args = std::env::args().skip(1);
+
+        let mut file = None;
+        let mut speed = 1.0;
+        let mut no_delay = false;
+        let mut identity_map = false;
+
+        while let Some(arg) = args.next() {
+            match arg.as_str() {
+                "--speed" => {
+                    let value = args.next().expect(USAGE);
+                    speed = value.parse().expect("--speed must be a number");
+
+                    if speed <= 0.0 {
+                        panic!("--speed must be greater than 0");
+                    }
+                }
+                "--no-delay" => no_delay = true,
+                "--identity-map" => identity_map = true,
+                _ => file = Some(arg),
+            }
+        }
+
+        Self {
+            file: file.expect(USAGE),
+            speed,
+            no_delay,
+            identity_map,
+        }
+    }
+}
+
+#[tokio::main]
+async fn main() {
+    let args = ReplayArgs::parse();
+
+    let entries = match read_fix_log(args.file.as_str()) {
+        Ok(entries) => entries,
+        Err(err) => {
+            eprintln!("Can not read FIX log {}. Err: {}", args.file, err);
+            std::process::exit(1);
+        }
+    };
+    println!("Loaded {} FIX messages from {}", entries.len(), args.file);
+
+    let settings_reader = SettingsReader::new(".my-cfd-platform").await;
+    let settings_reader = Arc::new(settings_reader);
+
+    let mut service_context = service_sdk::ServiceContext::new(settings_reader.clone()).await;
+
+    let app_context = Arc::new(AppContext::new(settings_reader, &service_context).await);
+
+    service_context.register_timer(Duration::from_millis(50), |timer| {
+        timer.register_timer(
+            "Conflation Flush",
+            Arc::new(ConflationFlushTimer::new(app_context.clone())),
+        );
+    });
+
+    // PriceSrc Uploader is not registered on purpose:
+    // replayed prices must never reach the shared nosql table.
+
+    let tcp_server = your_bourse_bridge::tcp::setup_price_tcp_server(
+        &app_context,
+        service_context.app_states.clone(),
+    );
+
+    tcp_server.start().await;
+
+    let app_states = service_context.app_states.clone();
+
+    tokio::spawn(async move {
+        while !app_states.is_initialized() {
+            tokio::time::sleep(Duration::from_millis(100)).await;
+        }
+
+        if !args.identity_map {
+            app_context.get_map().await;
+        }
+
+        replay(&app_context, entries, &args).await;
+    });
+
+    service_context.start_application().await;
+}
+
+async fn replay(app: &Arc<AppContext>, entries: Vec<FixLogEntry>, args: &ReplayArgs) {
+    let settings = FixReplaySettings {
+        speed: args.speed,
+        no_delay: args.no_delay,
+        identity_map: args.identity_map,
+    };
+
+    let market_data_count = replay_fix_log(app, entries, &settings).await;
+
+    println!(
+        "Replay finished. Published {} market data messages",
+        market_data_count
+    );
+}
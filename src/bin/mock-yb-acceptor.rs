@@ -0,0 +1,103 @@
+use std::time::Duration;
+
+use tokio::io::{AsyncBufReadExt, BufReader};
+use your_bourse_bridge::mock_yb::{
+    MockInjection, MockQuote, MockQuoteSource, MockYbAcceptor, MockYbSettings,
+};
+
+const USAGE: &'static str = "Usage: mock-yb-acceptor [--bind <address>] [--port <port>] [--interval-ms <ms>] [--reject <SYMBOL,SYMBOL>] [--script <file with 'SYMBOL BID ASK' lines>]";
+
+const COMMANDS: &'static str =
+    "Commands: reject [text] | logout [text] | md-reject <SYMBOL> | gap <n> | garbage | stats";
+
+#[tokio::main]
+async fn main() {
+    let mut args = std::env::args().skip(1);
+
+    // Loopback unless exposed explicitly with --bind 0.0.0.0
+    let mut bind_address = "127.0.0.1".to_string();
+    let mut port = 9898;
+    let mut settings = MockYbSettings::default();
+
+    while let Some(arg) = args.next() {
+        let value = args.next().expect(USAGE);
+
+        match arg.as_str() {
+            "--bind" => bind_address = value,
+            "--port" => port = value.parse().expect(USAGE),
+            "--interval-ms" => {
+                settings.quote_interval = Duration::from_millis(value.parse().expect(USAGE))
+            }
+            "--reject" => {
+                settings.reject_symbols = value.split(',').map(|s| s.to_string()).collect()
+            }
+            "--script" => settings.quote_source = read_script(value.as_str()),
+            _ => panic!("{}", USAGE),
+        }
+    }
+
+    let acceptor = MockYbAcceptor::start(format!("{}:{}", bind_address, port).as_str(), settings)
+        .await
+        .unwrap();
+
+    eprintln!(
+        "Mock YourBourse acceptor is listening on {}",
+        acceptor.addr()
+    );
+    eprintln!("{}", COMMANDS);
+
+    let mut lines = BufReader::new(tokio::io::stdin()).lines();
+
+    while let Ok(Some(line)) = lines.next_line().await {
+        let line = line.trim();
+        let (command, argument) = match line.split_once(' ') {
+            Some((command, argument)) => (command, argument.trim()),
+            None => (line, ""),
+        };
+
+        match command {
+            "reject" => acceptor.inject(MockInjection::Reject(argument.to_string())),
+            "logout" => acceptor.inject(MockInjection::Logout(argument.to_string())),
+            "md-reject" => acceptor.inject(MockInjection::MarketDataReject(argument.to_string())),
+            "gap" => match argument.parse() {
+                Ok(gap) => acceptor.inject(MockInjection::SequenceGap(gap)),
+                Err(_) => eprintln!("gap requires a number"),
+            },
+            "garbage" => acceptor.inject(MockInjection::garbage()),
+            "stats" => eprintln!(
+                "Connections: {}. Accept errors: {}. Skipped frames: {}. Logons: {}. MarketDataRequests: {}. Quotes sent: {}",
+                acceptor.stats.get_connections(),
+                acceptor.stats.get_accept_errors(),
+                acceptor.stats.get_skipped_frames(),
+                acceptor.stats.get_logons(),
+                acceptor.stats.get_market_data_requests(),
+                acceptor.stats.get_quotes_sent()
+            ),
+            "" => {}
+            _ => eprintln!("{}", COMMANDS),
+        }
+    }
+
+    // stdin is closed (e.g. started in background) - keep serving
+    loop {
+        tokio::time::sleep(Duration::from_secs(60)).await;
+    }
+}
+
+fn read_script(path: &str) -> MockQuoteSource {
+    let content = std::fs::read_to_string(path).unwrap();
+
+    let quotes = content
+        .lines()
+        .filter(|line| !line.trim().is_empty())
+        .map(|line| {
+            let mut parts = line.split_whitespace();
+            let symbol = parts.next().unwrap().to_string();
+            let bid = parts.next().unwrap().parse().unwrap();
+            let ask = parts.next().unwrap().parse().unwrap();
+            MockQuote { symbol, bid, ask }
+        })
+        .collect();
+
+    MockQuoteSource::Scripted(quotes)
+}
@@ -19,6 +19,59 @@ pub fn parse_fix_date(date: &str) -> DateTimeAsMicroseconds {
     DateTimeAsMicroseconds::create(year, month, day, hour, min, sec, micros * 1000)
 }
 
+// Tolerant version for optional fields: fraction of seconds may be absent or up to 6 digits
+pub fn try_parse_fix_date(date: &str) -> Option<DateTimeAsMicroseconds> {
+    if date.len() < 17 {
+        return None;
+    }
+
+    let year = date.get(0..4)?.parse().ok()?;
+    let month = date.get(4..6)?.parse().ok()?;
+    let day = date.get(6..8)?.parse().ok()?;
+    let hour = date.get(9..11)?.parse().ok()?;
+    let min = date.get(12..14)?.parse().ok()?;
+    let sec = date.get(15..17)?.parse().ok()?;
+
+    let micros = match date.get(18..) {
+        Some(fraction) if !fraction.is_empty() => {
+            let fraction = &fraction[..fraction.len().min(6)];
+            let value: i64 = fraction.parse().ok()?;
+            value * 10_i64.pow(6 - fraction.len() as u32)
+        }
+        _ => 0,
+    };
+
+    Some(DateTimeAsMicroseconds::create(
+        year, month, day, hour, min, sec, micros,
+    ))
+}
+
+const DAY_MICROS: i64 = 24 * 60 * 60 * 1_000_000;
+
+// MDEntryDate (272) is YYYYMMDD and MDEntryTime (273) is HH:MM:SS[.sss].
+// If MDEntryDate is absent the time is put on the previous, same or next day of the fallback
+// (usually SendingTime), whichever is closest. 23:59:59 sent at 00:00:01 is yesterday
+pub fn parse_md_entry_date_time(
+    entry_date: Option<&str>,
+    entry_time: &str,
+    fallback: DateTimeAsMicroseconds,
+) -> Option<DateTimeAsMicroseconds> {
+    if let Some(entry_date) = entry_date {
+        return try_parse_fix_date(format!("{}-{}", entry_date, entry_time).as_str());
+    }
+
+    let fallback_date = fallback.to_chrono_utc().format("%Y%m%d").to_string();
+    let same_day = try_parse_fix_date(format!("{}-{}", fallback_date, entry_time).as_str())?;
+
+    let result = [-DAY_MICROS, 0, DAY_MICROS]
+        .iter()
+        .map(|shift| same_day.unix_microseconds + shift)
+        .min_by_key(|candidate| (candidate - fallback.unix_microseconds).abs())
+        .unwrap();
+
+    Some(DateTimeAsMicroseconds::new(result))
+}
+
 fn parse_number<TResult: FromStr + Debug>(date: &str, src: &str) -> TResult {
     match src.parse() {
         Ok(result) => result,
@@ -30,6 +83,7 @@ fn parse_number<TResult: FromStr + Debug>(date: &str, src: &str) -> TResult {
 
 #[cfg(test)]
 mod tests {
+    use rust_extensions::date_time::DateTimeAsMicroseconds;
 
     #[test]
     fn test_parse_date() {
@@ -37,4 +91,39 @@ mod tests {
         let date: rust_extensions::date_time::DateTimeAsMicroseconds = super::parse_fix_date(date);
         assert_eq!(&date.to_rfc3339()[..23], "2024-04-25T17:28:02.629");
     }
+
+    #[test]
+    fn test_parse_md_entry_date_time() {
+        let fallback = super::parse_fix_date("20240425-17:28:02.629");
+
+        let date = super::parse_md_entry_date_time(None, "17:28:01.123456", fallback).unwrap();
+        let expected = DateTimeAsMicroseconds::create(2024, 4, 25, 17, 28, 1, 123456);
+        assert_eq!(date.unix_microseconds, expected.unix_microseconds);
+
+        let date =
+            super::parse_md_entry_date_time(Some("20240424"), "23:59:59.5", fallback).unwrap();
+        let expected = DateTimeAsMicroseconds::create(2024, 4, 24, 23, 59, 59, 500000);
+        assert_eq!(date.unix_microseconds, expected.unix_microseconds);
+
+        assert!(super::parse_md_entry_date_time(None, "garbage", fallback).is_none());
+    }
+
+    #[test]
+    fn test_md_entry_time_before_midnight_received_after_it() {
+        let fallback = super::parse_fix_date("20240425-00:00:00.050");
+
+        let date = super::parse_md_entry_date_time(None, "23:59:59.9", fallback).unwrap();
+        let expected = DateTimeAsMicroseconds::create(2024, 4, 24, 23, 59, 59, 900000);
+        assert_eq!(date.unix_microseconds, expected.unix_microseconds);
+    }
+
+    #[test]
+    fn test_md_entry_time_after_midnight_received_before_it() {
+        // Venue clock is slightly ahead of the fallback
+        let fallback = super::parse_fix_date("20240424-23:59:59.950");
+
+        let date = super::parse_md_entry_date_time(None, "00:00:00.1", fallback).unwrap();
+        let expected = DateTimeAsMicroseconds::create(2024, 4, 25, 0, 0, 0, 100000);
+        assert_eq!(date.unix_microseconds, expected.unix_microseconds);
+    }
 }
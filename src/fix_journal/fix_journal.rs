@@ -0,0 +1,61 @@
+use std::sync::{
+    atomic::{AtomicU64, Ordering},
+    mpsc::{SyncSender, TrySendError},
+    Arc,
+};
+
+use rust_extensions::date_time::DateTimeAsMicroseconds;
+
+use crate::settings::FixJournalSettingsModel;
+
+use super::{FixDirection, FixJournalRecord, FixJournalWriter};
+
+// Records waiting for the disk. Over it records are dropped and counted, the FIX path never waits
+const QUEUE_CAPACITY: usize = 100_000;
+
+pub struct FixJournal {
+    sender: SyncSender<FixJournalRecord>,
+    dropped: Arc<AtomicU64>,
+}
+
+impl FixJournal {
+    pub fn new(settings: FixJournalSettingsModel) -> Self {
+        let (sender, receiver) = std::sync::mpsc::sync_channel(QUEUE_CAPACITY);
+        let dropped = Arc::new(AtomicU64::new(0));
+
+        let writer_dropped = dropped.clone();
+        std::thread::Builder::new()
+            .name("fix-journal".to_string())
+            .spawn(move || {
+                let mut writer = FixJournalWriter::new(settings, writer_dropped);
+                writer.run(receiver);
+            })
+            .unwrap();
+
+        Self { sender, dropped }
+    }
+
+    // timestamp - the moment the rest of the pipeline uses for the message. Receive time for In
+    pub fn write(
+        &self,
+        direction: FixDirection,
+        payload: &[u8],
+        timestamp: DateTimeAsMicroseconds,
+    ) {
+        let record = FixJournalRecord {
+            timestamp,
+            direction,
+            payload: super::mask_secret_tags(payload),
+        };
+
+        match self.sender.try_send(record) {
+            Ok(_) => {}
+            // Reported by the writer with the next flush
+            Err(TrySendError::Full(_)) => {
+                self.dropped.fetch_add(1, Ordering::Relaxed);
+            }
+            // Writer thread is gone on shutdown
+            Err(TrySendError::Disconnected(_)) => {}
+        }
+    }
+}
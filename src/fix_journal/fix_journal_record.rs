@@ -0,0 +1,190 @@
+use rust_extensions::date_time::DateTimeAsMicroseconds;
+use rust_fix::utils::FIX_DELIMITER;
+
+pub const JOURNAL_FIX_DELIMITER: u8 = b'|';
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FixDirection {
+    In,
+    Out,
+}
+
+impl FixDirection {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::In => "IN",
+            Self::Out => "OUT",
+        }
+    }
+
+    pub fn parse(src: &str) -> Option<Self> {
+        match src {
+            "IN" => Some(Self::In),
+            "OUT" => Some(Self::Out),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct FixJournalRecord {
+    pub timestamp: DateTimeAsMicroseconds,
+    pub direction: FixDirection,
+    pub payload: Vec<u8>,
+}
+
+impl FixJournalRecord {
+    // Line format: <rfc3339 receive time> <IN|OUT> <fix message with SOH replaced by '|'>.
+    // Bytes, not a string: values such as Text (58) may be in any encoding and are kept as is
+    pub fn to_line(&self) -> Vec<u8> {
+        let mut result = Vec::with_capacity(self.payload.len() + 40);
+        let timestamp = self.timestamp.to_chrono_utc();
+        result.extend_from_slice(
+            timestamp
+                .format("%Y-%m-%dT%H:%M:%S%.6fZ")
+                .to_string()
+                .as_bytes(),
+        );
+        result.push(b' ');
+        result.extend_from_slice(self.direction.as_str().as_bytes());
+        result.push(b' ');
+
+        for b in self.payload.iter() {
+            if *b == FIX_DELIMITER {
+                result.push(JOURNAL_FIX_DELIMITER);
+            } else {
+                result.push(*b);
+            }
+        }
+
+        result.push(b'\n');
+        result
+    }
+
+    pub fn parse_line(line: &[u8]) -> Option<Self> {
+        let line = line.strip_suffix(b"\n").unwrap_or(line);
+        let line = line.strip_suffix(b"\r").unwrap_or(line);
+        let mut parts = line.splitn(3, |b| *b == b' ');
+
+        let timestamp = std::str::from_utf8(parts.next()?).ok()?;
+        let direction = FixDirection::parse(std::str::from_utf8(parts.next()?).ok()?)?;
+        let payload = parts.next()?;
+
+        let timestamp = chrono::DateTime::parse_from_rfc3339(timestamp).ok()?;
+
+        Some(Self {
+            timestamp: DateTimeAsMicroseconds::new(timestamp.timestamp_micros()),
+            direction,
+            payload: restore_fix_delimiters(payload),
+        })
+    }
+}
+
+// Values of these tags never get to disk: Username, Password, NewPassword, RawData
+const SECRET_TAGS: [&'static [u8]; 4] = [b"553", b"554", b"925", b"96"];
+const MASKED_VALUE: &'static [u8] = b"***";
+
+pub fn mask_secret_tags(payload: &[u8]) -> Vec<u8> {
+    let mut result = Vec::with_capacity(payload.len());
+
+    for field in payload.split_inclusive(|b| *b == FIX_DELIMITER) {
+        match field.iter().position(|b| *b == b'=') {
+            Some(key_end) if SECRET_TAGS.contains(&&field[..key_end]) => {
+                result.extend_from_slice(&field[..=key_end]);
+                result.extend_from_slice(MASKED_VALUE);
+
+                if field.last() == Some(&FIX_DELIMITER) {
+                    result.push(FIX_DELIMITER);
+                }
+            }
+            _ => result.extend_from_slice(field),
+        }
+    }
+
+    result
+}
+
+pub fn restore_fix_delimiters(src: &[u8]) -> Vec<u8> {
+    src.iter()
+        .map(|b| {
+            if *b == JOURNAL_FIX_DELIMITER {
+                FIX_DELIMITER
+            } else {
+                *b
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use rust_extensions::date_time::DateTimeAsMicroseconds;
+    use rust_fix::utils::FIX_DELIMITER;
+
+    use super::{mask_secret_tags, FixDirection, FixJournalRecord};
+
+    #[test]
+    fn test_line_round_trip() {
+        let payload = format!(
+            "8=FIX.4.4{}35=0{}10=123{}",
+            FIX_DELIMITER as char, FIX_DELIMITER as char, FIX_DELIMITER as char
+        );
+
+        let record = FixJournalRecord {
+            timestamp: DateTimeAsMicroseconds::create(2024, 4, 25, 14, 3, 0, 123456),
+            direction: FixDirection::In,
+            payload: payload.as_bytes().to_vec(),
+        };
+
+        let line = record.to_line();
+        assert_eq!(
+            line,
+            b"2024-04-25T14:03:00.123456Z IN 8=FIX.4.4|35=0|10=123|\n"
+        );
+
+        let parsed = FixJournalRecord::parse_line(line.as_slice()).unwrap();
+
+        assert_eq!(parsed.direction, FixDirection::In);
+        assert_eq!(parsed.payload, payload.as_bytes());
+        assert_eq!(
+            parsed.timestamp.unix_microseconds,
+            record.timestamp.unix_microseconds
+        );
+    }
+
+    #[test]
+    fn test_non_ascii_bytes_are_kept() {
+        // Text (58) in UTF-8 and a byte which is not UTF-8 at all
+        let mut payload = b"8=FIX.4.4\x0158=Z\xc3\xbcrich \xff\x01".to_vec();
+        payload.extend_from_slice(b"10=123\x01");
+
+        let record = FixJournalRecord {
+            timestamp: DateTimeAsMicroseconds::create(2024, 4, 25, 14, 3, 0, 0),
+            direction: FixDirection::In,
+            payload: payload.clone(),
+        };
+
+        let line = record.to_line();
+        assert!(line
+            .windows(b"58=Z\xc3\xbcrich \xff|".len())
+            .any(|window| window == b"58=Z\xc3\xbcrich \xff|"));
+
+        let parsed = FixJournalRecord::parse_line(line.as_slice()).unwrap();
+        assert_eq!(parsed.payload, payload);
+    }
+
+    #[test]
+    fn test_secret_tags_are_masked() {
+        let payload = "8=FIX.4.4|35=A|553=user|554=secret|98=0|10=123|"
+            .replace('|', &(FIX_DELIMITER as char).to_string());
+
+        let masked = mask_secret_tags(payload.as_bytes());
+
+        assert_eq!(
+            String::from_utf8(masked)
+                .unwrap()
+                .replace(FIX_DELIMITER as char, "|"),
+            "8=FIX.4.4|35=A|553=***|554=***|98=0|10=123|"
+        );
+    }
+}
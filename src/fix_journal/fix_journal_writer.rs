@@ -0,0 +1,346 @@
+use std::{
+    fs::File,
+    io::{BufWriter, Write},
+    path::PathBuf,
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        mpsc::{Receiver, RecvTimeoutError},
+        Arc,
+    },
+    time::{Duration, Instant},
+};
+
+use rust_extensions::date_time::DateTimeAsMicroseconds;
+use service_sdk::my_logger::LogEventCtx;
+
+use crate::{app::LogThrottle, settings::FixJournalSettingsModel};
+
+use super::FixJournalRecord;
+
+const FILE_PREFIX: &'static str = "fix-journal-";
+const FILE_EXTENSION: &'static str = ".log";
+const FLUSH_INTERVAL: Duration = Duration::from_secs(1);
+// Wait before the next attempt to open a file after a failed open or write.
+// Records are not journaled meanwhile
+const RETRY_INTERVAL_SEC: i64 = 10;
+const ERROR_LOG_INTERVAL_SEC: i64 = 60;
+
+pub struct FixJournalWriter {
+    settings: FixJournalSettingsModel,
+    file: Option<BufWriter<File>>,
+    file_size: u64,
+    file_opened: DateTimeAsMicroseconds,
+    file_no: u64,
+    // Some - opening or writing a file failed. No new attempt before this time
+    retry_after: Option<DateTimeAsMicroseconds>,
+    // Records lost while there was no file
+    not_journaled: u64,
+    // A broken or full disk fails every attempt. Keeps the log readable
+    error_log: LogThrottle,
+    // Records the FIX path could not queue
+    dropped: Arc<AtomicU64>,
+}
+
+impl FixJournalWriter {
+    pub fn new(settings: FixJournalSettingsModel, dropped: Arc<AtomicU64>) -> Self {
+        Self {
+            settings,
+            file: None,
+            file_size: 0,
+            file_opened: DateTimeAsMicroseconds::now(),
+            file_no: 0,
+            retry_after: None,
+            not_journaled: 0,
+            error_log: LogThrottle::new(ERROR_LOG_INTERVAL_SEC),
+            dropped,
+        }
+    }
+
+    pub fn run(&mut self, receiver: Receiver<FixJournalRecord>) {
+        let mut last_flush = Instant::now();
+
+        loop {
+            match receiver.recv_timeout(FLUSH_INTERVAL) {
+                Ok(record) => self.write_record(&record),
+                Err(RecvTimeoutError::Timeout) => {}
+                Err(RecvTimeoutError::Disconnected) => {
+                    self.flush();
+                    return;
+                }
+            }
+
+            // Busy sessions never time out, so the interval is checked after every record
+            if last_flush.elapsed() >= FLUSH_INTERVAL {
+                self.flush();
+                last_flush = Instant::now();
+            }
+        }
+    }
+
+    fn write_record(&mut self, record: &FixJournalRecord) {
+        if self.rotation_required(record.timestamp) {
+            self.rotate(record.timestamp);
+        }
+
+        let line = record.to_line();
+
+        let file = match self.file.as_mut() {
+            Some(file) => file,
+            None => {
+                self.not_journaled += 1;
+                return;
+            }
+        };
+
+        if let Err(err) = file.write_all(line.as_slice()) {
+            self.file = None;
+            self.not_journaled += 1;
+            self.file_failed(
+                record.timestamp,
+                format!("Can not write to FIX journal. Err: {:?}", err),
+            );
+            return;
+        }
+
+        self.file_size += line.len() as u64;
+    }
+
+    fn flush(&mut self) {
+        let dropped = self.dropped.swap(0, Ordering::Relaxed);
+
+        if dropped > 0 {
+            write_error(format!(
+                "FIX journal queue is full. {} records are not journaled",
+                dropped
+            ));
+        }
+
+        if let Some(file) = self.file.as_mut() {
+            if let Err(err) = file.flush() {
+                write_error(format!("Can not flush FIX journal. Err: {:?}", err));
+            }
+        }
+    }
+
+    fn rotation_required(&self, now: DateTimeAsMicroseconds) -> bool {
+        if self.file.is_none() {
+            return match self.retry_after {
+                Some(retry_after) => now.unix_microseconds >= retry_after.unix_microseconds,
+                None => true,
+            };
+        }
+
+        if self.file_size >= self.settings.max_file_size_mb * 1024 * 1024 {
+            return true;
+        }
+
+        let file_age_sec = (now.unix_microseconds - self.file_opened.unix_microseconds) / 1_000_000;
+        file_age_sec >= (self.settings.rotate_interval_min * 60) as i64
+    }
+
+    fn rotate(&mut self, now: DateTimeAsMicroseconds) {
+        self.flush();
+        self.file = None;
+
+        if let Err(err) = std::fs::create_dir_all(&self.settings.path) {
+            self.file_failed(
+                now,
+                format!(
+                    "Can not create FIX journal directory {}. Err: {:?}",
+                    self.settings.path, err
+                ),
+            );
+            return;
+        }
+
+        self.file_no += 1;
+
+        let file_name = format!(
+            "{}{}-{:04}{}",
+            FILE_PREFIX,
+            now.to_chrono_utc().format("%Y%m%d-%H%M%S"),
+            self.file_no,
+            FILE_EXTENSION
+        );
+
+        let mut file_path = PathBuf::from(&self.settings.path);
+        file_path.push(file_name);
+
+        match File::create(&file_path) {
+            Ok(file) => {
+                self.file = Some(BufWriter::new(file));
+                self.file_size = 0;
+                self.file_opened = now;
+            }
+            Err(err) => {
+                self.file_failed(
+                    now,
+                    format!(
+                        "Can not create FIX journal file {:?}. Err: {:?}",
+                        file_path, err
+                    ),
+                );
+                return;
+            }
+        }
+
+        if self.retry_after.take().is_some() {
+            write_error(format!(
+                "FIX journal file is open again. {} records are not journaled",
+                self.not_journaled
+            ));
+        }
+
+        self.not_journaled = 0;
+        self.remove_expired_files();
+    }
+
+    fn file_failed(&mut self, now: DateTimeAsMicroseconds, err: String) {
+        self.retry_after = Some(DateTimeAsMicroseconds::new(
+            now.unix_microseconds + RETRY_INTERVAL_SEC * 1_000_000,
+        ));
+
+        if let Some(not_logged) = self.error_log.try_log("file", now) {
+            write_error(format!(
+                "{}. Retry in {} sec. {} more failed since the last error",
+                err, RETRY_INTERVAL_SEC, not_logged
+            ));
+        }
+    }
+
+    fn remove_expired_files(&self) {
+        let dir = match std::fs::read_dir(&self.settings.path) {
+            Ok(dir) => dir,
+            Err(err) => {
+                write_error(format!(
+                    "Can not read FIX journal directory {}. Err: {:?}",
+                    self.settings.path, err
+                ));
+                return;
+            }
+        };
+
+        let mut files: Vec<PathBuf> = dir
+            .filter_map(|entry| entry.ok())
+            .map(|entry| entry.path())
+            .filter(|path| is_journal_file(path))
+            .collect();
+
+        if files.len() <= self.settings.retention_files {
+            return;
+        }
+
+        // File names start with the creation time, so the oldest ones go first
+        files.sort();
+
+        let to_remove = files.len() - self.settings.retention_files;
+
+        for path in files.iter().take(to_remove) {
+            if let Err(err) = std::fs::remove_file(path) {
+                write_error(format!(
+                    "Can not remove FIX journal file {:?}. Err: {:?}",
+                    path, err
+                ));
+            }
+        }
+    }
+}
+
+fn is_journal_file(path: &PathBuf) -> bool {
+    match path.file_name().and_then(|name| name.to_str()) {
+        Some(name) => name.starts_with(FILE_PREFIX) && name.ends_with(FILE_EXTENSION),
+        None => false,
+    }
+}
+
+fn write_error(message: String) {
+    service_sdk::my_logger::LOGGER.write_error(
+        String::from("FixJournalWriter"),
+        message,
+        LogEventCtx::new(),
+    );
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        fs::OpenOptions,
+        io::BufWriter,
+        sync::{atomic::AtomicU64, Arc},
+    };
+
+    use rust_extensions::date_time::DateTimeAsMicroseconds;
+
+    use super::FixJournalWriter;
+    use crate::{
+        fix_journal::{FixDirection, FixJournalRecord},
+        settings::FixJournalSettingsModel,
+    };
+
+    fn record(timestamp: DateTimeAsMicroseconds) -> FixJournalRecord {
+        FixJournalRecord {
+            timestamp,
+            direction: FixDirection::In,
+            payload: b"8=FIX.4.4\x0135=0\x01".to_vec(),
+        }
+    }
+
+    #[test]
+    fn test_failed_open_is_retried_after_interval() {
+        // A file where the directory should be. Every attempt to open the journal fails
+        let mut path = std::env::temp_dir();
+        path.push(format!("yb-bridge-journal-{}", uuid::Uuid::new_v4()));
+        std::fs::write(&path, b"").unwrap();
+
+        let mut writer = FixJournalWriter::new(
+            FixJournalSettingsModel {
+                path: path.to_str().unwrap().to_string(),
+                ..Default::default()
+            },
+            Arc::new(AtomicU64::new(0)),
+        );
+
+        let at_sec = |sec: i64| DateTimeAsMicroseconds::new(sec * 1_000_000);
+
+        writer.write_record(&record(at_sec(0)));
+        assert!(writer.file.is_none());
+        assert_eq!(writer.file_no, 0);
+
+        // No attempt on every record
+        for sec in 1..10 {
+            writer.write_record(&record(at_sec(sec)));
+        }
+        assert!(!writer.rotation_required(at_sec(9)));
+        assert_eq!(writer.not_journaled, 10);
+
+        assert!(writer.rotation_required(at_sec(10)));
+
+        std::fs::remove_file(path).unwrap();
+    }
+
+    #[test]
+    fn test_failed_write_is_retried_after_interval() {
+        let mut writer = FixJournalWriter::new(
+            FixJournalSettingsModel::default(),
+            Arc::new(AtomicU64::new(0)),
+        );
+
+        // Full disk. Unbuffered, so the write itself fails
+        let full_disk = OpenOptions::new().write(true).open("/dev/full").unwrap();
+        writer.file = Some(BufWriter::with_capacity(0, full_disk));
+
+        let at_sec = |sec: i64| DateTimeAsMicroseconds::new(sec * 1_000_000);
+
+        writer.write_record(&record(at_sec(0)));
+        assert!(writer.file.is_none());
+
+        for sec in 1..10 {
+            writer.write_record(&record(at_sec(sec)));
+        }
+        assert!(writer.file.is_none());
+        assert_eq!(writer.file_no, 0);
+        assert_eq!(writer.not_journaled, 10);
+
+        assert!(writer.rotation_required(at_sec(10)));
+    }
+}
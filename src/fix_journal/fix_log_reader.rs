@@ -0,0 +1,63 @@
+use std::io::{BufRead, BufReader};
+
+use rust_extensions::date_time::DateTimeAsMicroseconds;
+use rust_fix::utils::FIX_DELIMITER;
+
+use super::{restore_fix_delimiters, FixDirection, FixJournalRecord};
+
+const FIX_MESSAGE_START: &'static str = "8=FIX";
+
+pub struct FixLogEntry {
+    pub timestamp: Option<DateTimeAsMicroseconds>,
+    pub payload: Vec<u8>,
+}
+
+// Reads inbound messages either from a FIX journal or from a plain log
+// where each line contains one SOH or pipe delimited FIX message.
+// Plain log lines have no receive time, so their timestamp is None.
+pub fn read_fix_log(path: &str) -> std::io::Result<Vec<FixLogEntry>> {
+    let file = std::fs::File::open(path)?;
+    let reader = BufReader::new(file);
+
+    let mut result = Vec::new();
+
+    // Journal lines are bytes. Values are not guaranteed to be UTF-8
+    for line in reader.split(b'\n') {
+        let line = line?;
+
+        if let Some(record) = FixJournalRecord::parse_line(line.as_slice()) {
+            if record.direction == FixDirection::In {
+                result.push(FixLogEntry {
+                    timestamp: Some(record.timestamp),
+                    payload: record.payload,
+                });
+            }
+
+            continue;
+        }
+
+        if let Some(payload) = extract_fix_message(&String::from_utf8_lossy(&line)) {
+            result.push(FixLogEntry {
+                timestamp: None,
+                payload,
+            });
+        }
+    }
+
+    Ok(result)
+}
+
+fn extract_fix_message(line: &str) -> Option<Vec<u8>> {
+    let start = line.find(FIX_MESSAGE_START)?;
+
+    // DEBUG_FIX output prints messages as debug strings - strip the closing quote
+    let message = line[start..].trim_end().trim_end_matches('"');
+
+    let mut payload = restore_fix_delimiters(message.as_bytes());
+
+    if payload.last() != Some(&FIX_DELIMITER) {
+        payload.push(FIX_DELIMITER);
+    }
+
+    Some(payload)
+}
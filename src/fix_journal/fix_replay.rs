@@ -0,0 +1,74 @@
+use std::time::Duration;
+
+use rust_extensions::date_time::DateTimeAsMicroseconds;
+
+use crate::{app::AppContext, your_bourse::YbFixContract};
+
+use super::FixLogEntry;
+
+pub struct FixReplaySettings {
+    // Delays between messages are divided by it
+    pub speed: f64,
+    pub no_delay: bool,
+    // External symbols are published under their own names
+    pub identity_map: bool,
+}
+
+// Publishes inbound market data of a FIX log. Returns the number of market data messages.
+// Time checks run on the recorded receive time. Plain log lines have none and run on now
+pub async fn replay_fix_log(
+    app: &AppContext,
+    entries: Vec<FixLogEntry>,
+    settings: &FixReplaySettings,
+) -> usize {
+    let quote_time_source = app.settings_reader.get_quote_time_source().await;
+    let mut prev_timestamp = None;
+    let mut market_data_count = 0;
+
+    for entry in entries {
+        if !settings.no_delay {
+            if let (Some(prev), Some(current)) = (prev_timestamp, entry.timestamp) {
+                let delay_micros = (current.unix_microseconds - prev.unix_microseconds) as f64;
+
+                if delay_micros > 0.0 {
+                    let delay = Duration::from_micros((delay_micros / settings.speed) as u64);
+                    tokio::time::sleep(delay).await;
+                }
+            }
+        }
+
+        if entry.timestamp.is_some() {
+            prev_timestamp = entry.timestamp;
+        }
+
+        let receive_time = entry.timestamp.unwrap_or_else(DateTimeAsMicroseconds::now);
+
+        let deserialized =
+            YbFixContract::deserialize(entry.payload, receive_time, quote_time_source);
+
+        match deserialized {
+            YbFixContract::MarketData(market_data) => {
+                if settings.identity_map {
+                    let mut broadcast_data = app.broadcast_data.lock().await;
+                    if !broadcast_data.maps.contains_key(&market_data.instrument_id) {
+                        broadcast_data.maps.insert(
+                            market_data.instrument_id.clone(),
+                            vec![market_data.instrument_id.clone()],
+                        );
+                    }
+                }
+
+                app.broad_cast_bid_ask_at(market_data, receive_time).await;
+                market_data_count += 1;
+            }
+            YbFixContract::Skip(reason) => {
+                println!("Skipping Fix message: {}", reason);
+            }
+            other => {
+                println!("Replayed {:?}", other);
+            }
+        }
+    }
+
+    market_data_count
+}
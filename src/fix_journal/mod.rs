@@ -0,0 +1,10 @@
+mod fix_journal;
+pub use fix_journal::*;
+mod fix_journal_record;
+pub use fix_journal_record::*;
+mod fix_journal_writer;
+pub use fix_journal_writer::*;
+mod fix_log_reader;
+pub use fix_log_reader::*;
+mod fix_replay;
+pub use fix_replay::*;
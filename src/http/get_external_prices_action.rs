@@ -0,0 +1,71 @@
+use std::{collections::BTreeSet, sync::Arc};
+
+use rust_extensions::date_time::DateTimeAsMicroseconds;
+service_sdk::macros::use_my_http_server!();
+
+use crate::app::AppContext;
+
+use super::{parse_ids_filter, ExternalPriceHttpModel, GetExternalPricesHttpInput};
+
+#[http_route(
+    method: "GET",
+    route: "/api/prices/external",
+    summary: "Current venue quotes",
+    description: "Last accepted venue quote and FIX subscription state per symbol",
+    controller: "Prices",
+    input_data: "GetExternalPricesHttpInput",
+    result:[
+        {status_code: 200, description: "Quotes", model: "Vec<ExternalPriceHttpModel>"},
+    ]
+)]
+pub struct GetExternalPricesAction {
+    app: Arc<AppContext>,
+}
+
+impl GetExternalPricesAction {
+    pub fn new(app: Arc<AppContext>) -> Self {
+        Self { app }
+    }
+}
+
+async fn handle_request(
+    action: &GetExternalPricesAction,
+    input_data: GetExternalPricesHttpInput,
+    _ctx: &mut HttpContext,
+) -> Result<HttpOkResult, HttpFailResult> {
+    let maps = action.app.broadcast_data.lock().await.maps.clone();
+    let activity = action.app.quote_activity.get_all().await;
+
+    // Subscribed, mapped or quoted before
+    let symbols: BTreeSet<String> = match parse_ids_filter(input_data.symbols.as_ref()) {
+        Some(symbols) => symbols.into_iter().collect(),
+        None => {
+            let mut result: BTreeSet<String> = maps.keys().cloned().collect();
+            result.extend(activity.keys().cloned());
+
+            for price in action.app.last_prices.get_externals().await {
+                result.insert(price.external_symbol);
+            }
+
+            result
+        }
+    };
+
+    let now = DateTimeAsMicroseconds::now();
+    let mut result = Vec::with_capacity(symbols.len());
+
+    for symbol in symbols {
+        let price = action.app.last_prices.get_external(&symbol).await;
+        let instruments = maps.get(&symbol).cloned().unwrap_or_default();
+
+        result.push(ExternalPriceHttpModel::new(
+            symbol.to_string(),
+            instruments,
+            price,
+            activity.get(&symbol),
+            now,
+        ));
+    }
+
+    HttpOutput::as_json(result).into_ok_result(true).into()
+}
@@ -0,0 +1,43 @@
+use std::sync::Arc;
+
+service_sdk::macros::use_my_http_server!();
+
+use crate::app::AppContext;
+
+use super::ForcedReconnectHttpModel;
+
+#[http_route(
+    method: "GET",
+    route: "/api/fix/forced-reconnects",
+    summary: "FIX reconnects forced by the session watchdog",
+    description: "Last forced reconnects with their reasons. Newest last",
+    controller: "Fix",
+    result:[
+        {status_code: 200, description: "Forced reconnects", model: "Vec<ForcedReconnectHttpModel>"},
+    ]
+)]
+pub struct GetForcedReconnectsAction {
+    app: Arc<AppContext>,
+}
+
+impl GetForcedReconnectsAction {
+    pub fn new(app: Arc<AppContext>) -> Self {
+        Self { app }
+    }
+}
+
+async fn handle_request(
+    action: &GetForcedReconnectsAction,
+    _ctx: &mut HttpContext,
+) -> Result<HttpOkResult, HttpFailResult> {
+    let result: Vec<ForcedReconnectHttpModel> = action
+        .app
+        .forced_reconnects
+        .get_all()
+        .await
+        .into_iter()
+        .map(ForcedReconnectHttpModel::new)
+        .collect();
+
+    HttpOutput::as_json(result).into_ok_result(true).into()
+}
@@ -0,0 +1,45 @@
+use std::sync::Arc;
+
+service_sdk::macros::use_my_http_server!();
+
+use crate::app::AppContext;
+
+use super::{LatencyReportHttpModel, SymbolLatencyHttpModel};
+
+#[http_route(
+    method: "GET",
+    route: "/api/latency",
+    summary: "Quote latency per venue symbol",
+    description: "Percentiles of the window in progress and of the last finished one",
+    controller: "Fix",
+    result:[
+        {status_code: 200, description: "Latency", model: "LatencyReportHttpModel"},
+    ]
+)]
+pub struct GetLatencyAction {
+    app: Arc<AppContext>,
+}
+
+impl GetLatencyAction {
+    pub fn new(app: Arc<AppContext>) -> Self {
+        Self { app }
+    }
+}
+
+async fn handle_request(
+    action: &GetLatencyAction,
+    _ctx: &mut HttpContext,
+) -> Result<HttpOkResult, HttpFailResult> {
+    let current = action.app.latency_stats.get_summary().await;
+    let last = action.app.last_latency_report.lock().await.clone();
+
+    let result = LatencyReportHttpModel {
+        current: current
+            .into_iter()
+            .map(SymbolLatencyHttpModel::new)
+            .collect(),
+        last: last.into_iter().map(SymbolLatencyHttpModel::new).collect(),
+    };
+
+    HttpOutput::as_json(result).into_ok_result(true).into()
+}
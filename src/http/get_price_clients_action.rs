@@ -0,0 +1,49 @@
+use std::sync::Arc;
+
+use rust_extensions::date_time::DateTimeAsMicroseconds;
+service_sdk::macros::use_my_http_server!();
+
+use crate::app::AppContext;
+
+use super::PriceClientHttpModel;
+
+#[http_route(
+    method: "GET",
+    route: "/api/price-clients",
+    summary: "Connected price clients",
+    description: "Queue state and instrument subscriptions of every price client",
+    controller: "Prices",
+    result:[
+        {status_code: 200, description: "Clients", model: "Vec<PriceClientHttpModel>"},
+    ]
+)]
+pub struct GetPriceClientsAction {
+    app: Arc<AppContext>,
+}
+
+impl GetPriceClientsAction {
+    pub fn new(app: Arc<AppContext>) -> Self {
+        Self { app }
+    }
+}
+
+async fn handle_request(
+    action: &GetPriceClientsAction,
+    _ctx: &mut HttpContext,
+) -> Result<HttpOkResult, HttpFailResult> {
+    let now = DateTimeAsMicroseconds::now();
+    let broadcast_data = action.app.broadcast_data.lock().await;
+
+    let mut result: Vec<PriceClientHttpModel> = broadcast_data
+        .get_subscribers_status(now)
+        .into_iter()
+        .map(|status| {
+            let subscription = broadcast_data.subscriptions.get(&status.id);
+            PriceClientHttpModel::new(status, subscription)
+        })
+        .collect();
+
+    result.sort_by_key(|client| client.id);
+
+    HttpOutput::as_json(result).into_ok_result(true).into()
+}
@@ -0,0 +1,61 @@
+use std::sync::Arc;
+
+use rust_extensions::date_time::DateTimeAsMicroseconds;
+service_sdk::macros::use_my_http_server!();
+
+use crate::app::AppContext;
+
+use super::{parse_ids_filter, GetPricesHttpInput, InstrumentPriceHttpModel};
+
+#[http_route(
+    method: "GET",
+    route: "/api/prices",
+    summary: "Current prices of our instruments",
+    description: "Last published bid/ask with update time and staleness",
+    controller: "Prices",
+    input_data: "GetPricesHttpInput",
+    result:[
+        {status_code: 200, description: "Prices", model: "Vec<InstrumentPriceHttpModel>"},
+    ]
+)]
+pub struct GetPricesAction {
+    app: Arc<AppContext>,
+}
+
+impl GetPricesAction {
+    pub fn new(app: Arc<AppContext>) -> Self {
+        Self { app }
+    }
+}
+
+async fn handle_request(
+    action: &GetPricesAction,
+    input_data: GetPricesHttpInput,
+    _ctx: &mut HttpContext,
+) -> Result<HttpOkResult, HttpFailResult> {
+    let prices = match parse_ids_filter(input_data.instruments.as_ref()) {
+        Some(instruments) => {
+            let mut result = Vec::with_capacity(instruments.len());
+
+            for instrument_id in instruments {
+                if let Some(price) = action.app.last_prices.get_instrument(&instrument_id).await {
+                    result.push(price);
+                }
+            }
+
+            result
+        }
+        None => action.app.last_prices.get_instruments().await,
+    };
+
+    let now = DateTimeAsMicroseconds::now();
+
+    let mut result: Vec<InstrumentPriceHttpModel> = prices
+        .into_iter()
+        .map(|price| InstrumentPriceHttpModel::new(price, now))
+        .collect();
+
+    result.sort_by(|a, b| a.instrument_id.cmp(&b.instrument_id));
+
+    HttpOutput::as_json(result).into_ok_result(true).into()
+}
@@ -0,0 +1,43 @@
+use std::sync::Arc;
+
+service_sdk::macros::use_my_http_server!();
+
+use crate::app::AppContext;
+
+use super::QuoteCorrectionHttpModel;
+
+#[http_route(
+    method: "GET",
+    route: "/api/quote-corrections",
+    summary: "Crossed and zero spread quotes",
+    description: "Last quotes changed or dropped by the crossed quote policy. Newest last",
+    controller: "Prices",
+    result:[
+        {status_code: 200, description: "Corrections", model: "Vec<QuoteCorrectionHttpModel>"},
+    ]
+)]
+pub struct GetQuoteCorrectionsAction {
+    app: Arc<AppContext>,
+}
+
+impl GetQuoteCorrectionsAction {
+    pub fn new(app: Arc<AppContext>) -> Self {
+        Self { app }
+    }
+}
+
+async fn handle_request(
+    action: &GetQuoteCorrectionsAction,
+    _ctx: &mut HttpContext,
+) -> Result<HttpOkResult, HttpFailResult> {
+    let result: Vec<QuoteCorrectionHttpModel> = action
+        .app
+        .quote_corrections
+        .get_all()
+        .await
+        .into_iter()
+        .map(QuoteCorrectionHttpModel::new)
+        .collect();
+
+    HttpOutput::as_json(result).into_ok_result(true).into()
+}
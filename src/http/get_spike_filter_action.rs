@@ -0,0 +1,48 @@
+use std::sync::Arc;
+
+service_sdk::macros::use_my_http_server!();
+
+use crate::app::AppContext;
+
+use super::SpikeFilterHttpModel;
+
+#[http_route(
+    method: "GET",
+    route: "/api/spike-filter",
+    summary: "Spike filter counters",
+    description: "Suppressed quotes per venue symbol",
+    controller: "Prices",
+    result:[
+        {status_code: 200, description: "Counters", model: "Vec<SpikeFilterHttpModel>"},
+    ]
+)]
+pub struct GetSpikeFilterAction {
+    app: Arc<AppContext>,
+}
+
+impl GetSpikeFilterAction {
+    pub fn new(app: Arc<AppContext>) -> Self {
+        Self { app }
+    }
+}
+
+async fn handle_request(
+    action: &GetSpikeFilterAction,
+    _ctx: &mut HttpContext,
+) -> Result<HttpOkResult, HttpFailResult> {
+    let mut result: Vec<SpikeFilterHttpModel> = action
+        .app
+        .spike_filter
+        .get_suppressed_counters()
+        .await
+        .into_iter()
+        .map(|(external_symbol, suppressed_count)| SpikeFilterHttpModel {
+            external_symbol,
+            suppressed_count,
+        })
+        .collect();
+
+    result.sort_by(|a, b| a.external_symbol.cmp(&b.external_symbol));
+
+    HttpOutput::as_json(result).into_ok_result(true).into()
+}
@@ -0,0 +1,16 @@
+mod models;
+pub use models::*;
+mod get_prices_action;
+pub use get_prices_action::*;
+mod get_external_prices_action;
+pub use get_external_prices_action::*;
+mod get_price_clients_action;
+pub use get_price_clients_action::*;
+mod get_forced_reconnects_action;
+pub use get_forced_reconnects_action::*;
+mod get_latency_action;
+pub use get_latency_action::*;
+mod get_spike_filter_action;
+pub use get_spike_filter_action::*;
+mod get_quote_corrections_action;
+pub use get_quote_corrections_action::*;
@@ -0,0 +1,249 @@
+use rust_extensions::date_time::DateTimeAsMicroseconds;
+use serde::{Deserialize, Serialize};
+service_sdk::macros::use_my_http_server!();
+
+use crate::app::{
+    ClientSubscription, ExternalLastPrice, ForcedReconnect, InstrumentLastPrice, LatencySummary,
+    PriceSubscriberStatus, QuoteCorrection, SymbolLatencySummary, SymbolQuoteActivity,
+    SUBSCRIBE_ALL,
+};
+
+#[derive(MyHttpInput)]
+pub struct GetPricesHttpInput {
+    #[http_query(name = "instruments"; description = "Comma separated ids. Empty - all")]
+    pub instruments: Option<String>,
+}
+
+#[derive(MyHttpInput)]
+pub struct GetExternalPricesHttpInput {
+    #[http_query(name = "symbols"; description = "Comma separated venue symbols. Empty - all")]
+    pub symbols: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Debug, MyHttpObjectStructure)]
+pub struct InstrumentPriceHttpModel {
+    pub instrument_id: String,
+    pub src_id: String,
+    pub bid: f64,
+    pub ask: f64,
+    pub raw_bid: f64,
+    pub raw_ask: f64,
+    pub source_time: String,
+    pub receive_time: String,
+    pub age_ms: i64,
+    pub is_stale: bool,
+    pub update_count: u64,
+}
+
+impl InstrumentPriceHttpModel {
+    pub fn new(price: InstrumentLastPrice, now: DateTimeAsMicroseconds) -> Self {
+        Self {
+            age_ms: price.get_age_ms(now),
+            is_stale: price.stale_since.is_some(),
+            source_time: price.source_time.to_rfc3339(),
+            receive_time: price.receive_time.to_rfc3339(),
+            instrument_id: price.instrument_id,
+            src_id: price.src_id,
+            bid: price.bid,
+            ask: price.ask,
+            raw_bid: price.raw_bid,
+            raw_ask: price.raw_ask,
+            update_count: price.update_count,
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, MyHttpObjectStructure)]
+pub struct ExternalPriceHttpModel {
+    pub external_symbol: String,
+    // Our instruments priced from the symbol
+    pub instruments: Vec<String>,
+    pub bid: Option<f64>,
+    pub ask: Option<f64>,
+    pub source_time: Option<String>,
+    pub receive_time: Option<String>,
+    pub age_ms: Option<i64>,
+    pub update_count: u64,
+    // Market data is requested from the venue in the current FIX session
+    pub is_subscribed: bool,
+    pub subscribed_at: Option<String>,
+    pub resubscribe_attempts: u32,
+}
+
+impl ExternalPriceHttpModel {
+    pub fn new(
+        external_symbol: String,
+        instruments: Vec<String>,
+        price: Option<ExternalLastPrice>,
+        activity: Option<&SymbolQuoteActivity>,
+        now: DateTimeAsMicroseconds,
+    ) -> Self {
+        Self {
+            external_symbol,
+            instruments,
+            bid: price.as_ref().map(|price| price.bid),
+            ask: price.as_ref().map(|price| price.ask),
+            source_time: price.as_ref().map(|price| price.source_time.to_rfc3339()),
+            receive_time: price.as_ref().map(|price| price.receive_time.to_rfc3339()),
+            age_ms: price
+                .as_ref()
+                .map(|price| (now.unix_microseconds - price.receive_time.unix_microseconds) / 1000),
+            update_count: price.as_ref().map_or(0, |price| price.update_count),
+            is_subscribed: activity.is_some(),
+            subscribed_at: activity.map(|activity| activity.subscribed.to_rfc3339()),
+            resubscribe_attempts: activity.map_or(0, |activity| activity.resubscribe_attempts),
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, MyHttpObjectStructure)]
+pub struct PriceClientHttpModel {
+    pub id: i32,
+    pub addr: String,
+    pub queue_len: usize,
+    pub lag_ms: i64,
+    pub conflated: u64,
+    // ["*"] - every instrument except the ones in "except"
+    pub subscribed: Vec<String>,
+    pub except: Vec<String>,
+}
+
+impl PriceClientHttpModel {
+    pub fn new(status: PriceSubscriberStatus, subscription: Option<&ClientSubscription>) -> Self {
+        let (subscribed, except) = match subscription {
+            Some(ClientSubscription::Instruments(instruments)) => {
+                (sorted(instruments.iter()), Vec::new())
+            }
+            Some(ClientSubscription::All { except, .. }) => {
+                (vec![SUBSCRIBE_ALL.to_string()], sorted(except.iter()))
+            }
+            None => (vec![SUBSCRIBE_ALL.to_string()], Vec::new()),
+        };
+
+        Self {
+            id: status.id,
+            addr: status.addr,
+            queue_len: status.queue_len,
+            lag_ms: status.lag_ms,
+            conflated: status.conflated,
+            subscribed,
+            except,
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, MyHttpObjectStructure)]
+pub struct ForcedReconnectHttpModel {
+    pub date: String,
+    pub reason: String,
+}
+
+impl ForcedReconnectHttpModel {
+    pub fn new(src: ForcedReconnect) -> Self {
+        Self {
+            date: src.date.to_rfc3339(),
+            reason: src.reason,
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, MyHttpObjectStructure)]
+pub struct LatencyReportHttpModel {
+    // Window in progress. Resets with every latency report
+    pub current: Vec<SymbolLatencyHttpModel>,
+    // Last finished window - the one written to the log
+    pub last: Vec<SymbolLatencyHttpModel>,
+}
+
+#[derive(Serialize, Deserialize, Debug, MyHttpObjectStructure)]
+pub struct SymbolLatencyHttpModel {
+    pub external_symbol: String,
+    pub sending_to_receive: LatencyHttpModel,
+    pub entry_to_receive: LatencyHttpModel,
+    pub receive_to_publish: LatencyHttpModel,
+    pub sending_to_publish: LatencyHttpModel,
+}
+
+impl SymbolLatencyHttpModel {
+    pub fn new(src: SymbolLatencySummary) -> Self {
+        Self {
+            external_symbol: src.external_symbol,
+            sending_to_receive: LatencyHttpModel::new(src.sending_to_receive),
+            entry_to_receive: LatencyHttpModel::new(src.entry_to_receive),
+            receive_to_publish: LatencyHttpModel::new(src.receive_to_publish),
+            sending_to_publish: LatencyHttpModel::new(src.sending_to_publish),
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, MyHttpObjectStructure)]
+pub struct LatencyHttpModel {
+    pub count: u64,
+    pub p50_micros: i64,
+    pub p99_micros: i64,
+    pub max_micros: i64,
+}
+
+impl LatencyHttpModel {
+    pub fn new(src: LatencySummary) -> Self {
+        Self {
+            count: src.count,
+            p50_micros: src.p50_micros,
+            p99_micros: src.p99_micros,
+            max_micros: src.max_micros,
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, MyHttpObjectStructure)]
+pub struct SpikeFilterHttpModel {
+    pub external_symbol: String,
+    // Quotes suppressed since the service start
+    pub suppressed_count: u64,
+}
+
+#[derive(Serialize, Deserialize, Debug, MyHttpObjectStructure)]
+pub struct QuoteCorrectionHttpModel {
+    pub date: String,
+    pub external_symbol: String,
+    // Crossed or ZeroSpread
+    pub kind: String,
+    pub action: String,
+    pub original_bid: f64,
+    pub original_ask: f64,
+}
+
+impl QuoteCorrectionHttpModel {
+    pub fn new(src: QuoteCorrection) -> Self {
+        Self {
+            date: src.date.to_rfc3339(),
+            kind: format!("{:?}", src.kind),
+            action: format!("{:?}", src.action),
+            external_symbol: src.external_symbol,
+            original_bid: src.original_bid,
+            original_ask: src.original_ask,
+        }
+    }
+}
+
+// None - no filter
+pub fn parse_ids_filter(src: Option<&String>) -> Option<Vec<String>> {
+    let ids: Vec<String> = src?
+        .split(',')
+        .map(|id| id.trim())
+        .filter(|id| !id.is_empty())
+        .map(|id| id.to_string())
+        .collect();
+
+    if ids.is_empty() {
+        return None;
+    }
+
+    Some(ids)
+}
+
+fn sorted<'s>(src: impl Iterator<Item = &'s String>) -> Vec<String> {
+    let mut result: Vec<String> = src.cloned().collect();
+    result.sort();
+    result
+}
@@ -0,0 +1,22 @@
+pub mod app;
+pub mod date_utils;
+pub mod fix_journal;
+pub mod http;
+pub mod mock_yb;
+pub mod nosql;
+pub mod settings;
+pub mod tcp;
+pub mod tick_recorder;
+pub mod timers;
+pub mod websocket;
+pub mod your_bourse;
+
+use my_tcp_sockets::tcp_connection::TcpSocketConnection;
+
+use tcp::{PriceTcpContract, PriceTcpSerializer};
+use your_bourse::{FixMessageSerializer, YbFixContract, YbTcpSate};
+
+pub use crate::app::AppContext;
+
+pub type FixSocketConnection = TcpSocketConnection<YbFixContract, FixMessageSerializer, YbTcpSate>;
+pub type PriceTcpSocketConnection = TcpSocketConnection<PriceTcpContract, PriceTcpSerializer, ()>;
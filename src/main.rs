@@ -1,45 +1,125 @@
-mod app;
-mod date_utils;
-mod settings;
-mod tcp;
-mod your_bourse;
 use std::{sync::Arc, time::Duration};
-mod timers;
 
-use my_tcp_sockets::{tcp_connection::TcpSocketConnection, TcpClient};
+use my_tcp_sockets::TcpClient;
+use service_sdk::my_logger::LogEventCtx;
 
-use prices_tcp_contracts::{BidAskTcpMessage, BidAskTcpSerializer};
-use timers::UploadSrcPricesTimer;
-use your_bourse::{
-    FixMessageHandler, FixMessageSerializer, YbFixContract, YbSerializerFactory, YbTcpSate,
+use your_bourse_bridge::{
+    app::AppContext,
+    http::{
+        GetExternalPricesAction, GetForcedReconnectsAction, GetLatencyAction,
+        GetPriceClientsAction, GetPricesAction, GetQuoteCorrectionsAction, GetSpikeFilterAction,
+    },
+    settings::SettingsReader,
+    timers::{
+        ClockSkewMonitorTimer, ConflationFlushTimer, LatencyReportTimer, SessionWatchdogTimer,
+        SlowConsumerTimer, StalePricesTimer, SubscriptionWatchdogTimer, UploadCandlesTimer,
+        UploadSrcPricesTimer,
+    },
+    your_bourse::{FixMessageHandler, YbSerializerFactory},
 };
 
-use crate::app::AppContext;
-
-type FixSocketConnection = TcpSocketConnection<YbFixContract, FixMessageSerializer, YbTcpSate>;
-type BidAskTcpSocketConnection = TcpSocketConnection<BidAskTcpMessage, BidAskTcpSerializer, ()>;
-
 #[tokio::main]
 async fn main() {
-    let settings_reader = crate::settings::SettingsReader::new(".my-cfd-platform").await;
+    let settings_reader = SettingsReader::new(".my-cfd-platform").await;
     let settings_reader = Arc::new(settings_reader);
 
     let mut service_context = service_sdk::ServiceContext::new(settings_reader.clone()).await;
 
+    // Invalid trading hours would silently turn the watchdogs off
+    if let Err(err) = settings_reader.validate().await {
+        panic!("Invalid settings. {}", err);
+    }
+
     let app_context = Arc::new(AppContext::new(settings_reader, &service_context).await);
 
+    // Conflated ticks wait up to this period after their interval ends
+    service_context.register_timer(Duration::from_millis(50), |timer| {
+        timer.register_timer(
+            "Conflation Flush",
+            Arc::new(ConflationFlushTimer::new(app_context.clone())),
+        );
+    });
+
     service_context.register_timer(Duration::from_secs(1), |timer| {
         timer.register_timer(
             "PriceSrc Uploader",
             Arc::new(UploadSrcPricesTimer::new(app_context.clone())),
         );
+        timer.register_timer(
+            "Candles Uploader",
+            Arc::new(UploadCandlesTimer::new(app_context.clone())),
+        );
+        timer.register_timer(
+            "Subscription Watchdog",
+            Arc::new(SubscriptionWatchdogTimer::new(app_context.clone())),
+        );
+        timer.register_timer(
+            "Session Watchdog",
+            Arc::new(SessionWatchdogTimer::new(app_context.clone())),
+        );
+        timer.register_timer(
+            "Slow Consumer",
+            Arc::new(SlowConsumerTimer::new(app_context.clone())),
+        );
+        timer.register_timer(
+            "Stale Prices",
+            Arc::new(StalePricesTimer::new(app_context.clone())),
+        );
+    });
+
+    service_context.register_timer(Duration::from_secs(10), |timer| {
+        timer.register_timer(
+            "Clock Skew Monitor",
+            Arc::new(ClockSkewMonitorTimer::new(app_context.clone())),
+        );
     });
 
-    let tcp_server =
-        crate::tcp::setup_price_tcp_server(&app_context, service_context.app_states.clone());
+    service_context.register_timer(Duration::from_secs(60), |timer| {
+        timer.register_timer(
+            "Latency Report",
+            Arc::new(LatencyReportTimer::new(app_context.clone())),
+        );
+    });
+
+    service_context.configure_http_server(|config| {
+        config.register_http_routes(|server| {
+            server.register_get(GetPricesAction::new(app_context.clone()));
+            server.register_get(GetExternalPricesAction::new(app_context.clone()));
+            server.register_get(GetPriceClientsAction::new(app_context.clone()));
+            server.register_get(GetForcedReconnectsAction::new(app_context.clone()));
+            server.register_get(GetLatencyAction::new(app_context.clone()));
+            server.register_get(GetSpikeFilterAction::new(app_context.clone()));
+            server.register_get(GetQuoteCorrectionsAction::new(app_context.clone()));
+        });
+    });
+
+    let tcp_server = your_bourse_bridge::tcp::setup_price_tcp_server(
+        &app_context,
+        service_context.app_states.clone(),
+    );
 
     tcp_server.start().await;
 
+    if let Some(settings) = app_context
+        .settings_reader
+        .get_price_websocket_settings()
+        .await
+    {
+        match settings.get_addr() {
+            Ok(addr) => {
+                your_bourse_bridge::websocket::start_price_ws_server(app_context.clone(), addr)
+                    .await;
+            }
+            Err(err) => {
+                service_sdk::my_logger::LOGGER.write_error(
+                    String::from("PriceWsServer"),
+                    format!("WebSocket price stream is off. {}", err),
+                    LogEventCtx::new(),
+                );
+            }
+        }
+    }
+
     let tcp_client = TcpClient::new("Yb-fix-client".to_string(), app_context.clone());
 
     tcp_client
@@ -0,0 +1,73 @@
+use std::{
+    net::SocketAddr,
+    sync::{atomic::Ordering, Arc},
+};
+
+use rust_extensions::date_time::DateTimeAsMicroseconds;
+use tokio::{net::TcpListener, sync::broadcast};
+
+use super::{mock_yb_session::MockYbSession, MockInjection, MockYbSettings, MockYbStats};
+
+// Plays the YourBourse side of the FIX session. Used by the mock-yb-acceptor
+// binary and by integration tests.
+pub struct MockYbAcceptor {
+    addr: SocketAddr,
+    injections: broadcast::Sender<MockInjection>,
+    pub stats: Arc<MockYbStats>,
+}
+
+impl MockYbAcceptor {
+    pub async fn start(listen_addr: &str, settings: MockYbSettings) -> std::io::Result<Self> {
+        let listener = TcpListener::bind(listen_addr).await?;
+        let addr = listener.local_addr()?;
+
+        let (injections, _) = broadcast::channel(1024);
+        let stats = Arc::new(MockYbStats::default());
+        let settings = Arc::new(settings);
+
+        let injections_to_sessions = injections.clone();
+        let stats_to_sessions = stats.clone();
+
+        tokio::spawn(async move {
+            loop {
+                // Reported through stats only. Printing is up to the binary
+                let stream = match listener.accept().await {
+                    Ok((stream, _)) => stream,
+                    Err(_) => {
+                        stats_to_sessions
+                            .accept_errors
+                            .fetch_add(1, Ordering::Relaxed);
+                        continue;
+                    }
+                };
+
+                stats_to_sessions
+                    .connections
+                    .fetch_add(1, Ordering::Relaxed);
+
+                MockYbSession::start(
+                    stream,
+                    settings.clone(),
+                    stats_to_sessions.clone(),
+                    injections_to_sessions.subscribe(),
+                    DateTimeAsMicroseconds::now().unix_microseconds as u64,
+                );
+            }
+        });
+
+        Ok(Self {
+            addr,
+            injections,
+            stats,
+        })
+    }
+
+    pub fn addr(&self) -> SocketAddr {
+        self.addr
+    }
+
+    pub fn inject(&self, injection: MockInjection) {
+        // No sessions - nobody to inject into
+        let _ = self.injections.send(injection);
+    }
+}
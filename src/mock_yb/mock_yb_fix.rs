@@ -0,0 +1,113 @@
+use rust_extensions::date_time::DateTimeAsMicroseconds;
+use rust_fix::{utils::FIX_DELIMITER, FixMessageWriter};
+
+const MOCK_FIX_VERSION: &'static str = "FIX.4.4";
+
+pub struct MockFixHeader<'s> {
+    pub sender_company_id: &'s str,
+    pub target_company_id: &'s str,
+    pub seq_no: u64,
+}
+
+fn create_message(header: &MockFixHeader, message_type: &str) -> FixMessageWriter {
+    let now = DateTimeAsMicroseconds::now();
+    let date_string = crate::date_utils::to_fix_date_string(now);
+
+    let mut fix_builder = FixMessageWriter::new(MOCK_FIX_VERSION, message_type);
+    fix_builder.with_value("49", header.sender_company_id);
+    fix_builder.with_value("56", header.target_company_id);
+    fix_builder.with_value("34", header.seq_no.to_string().as_str());
+    fix_builder.with_value("52", date_string.as_str());
+    fix_builder
+}
+
+pub fn serialize_logon(header: &MockFixHeader) -> Vec<u8> {
+    let mut fix_builder = create_message(header, "A");
+    fix_builder.with_value("98", "0");
+    fix_builder.with_value("108", "30");
+    fix_builder.compile_message()
+}
+
+pub fn serialize_heartbeat(header: &MockFixHeader, test_req_id: Option<&str>) -> Vec<u8> {
+    let mut fix_builder = create_message(header, "0");
+    if let Some(test_req_id) = test_req_id {
+        fix_builder.with_value("112", test_req_id);
+    }
+    fix_builder.compile_message()
+}
+
+pub fn serialize_logout(header: &MockFixHeader, text: &str) -> Vec<u8> {
+    let mut fix_builder = create_message(header, "5");
+    fix_builder.with_value("58", text);
+    fix_builder.compile_message()
+}
+
+pub fn serialize_reject(header: &MockFixHeader, ref_seq_no: u64, text: &str) -> Vec<u8> {
+    let mut fix_builder = create_message(header, "3");
+    fix_builder.with_value("45", ref_seq_no.to_string().as_str());
+    fix_builder.with_value("58", text);
+    fix_builder.compile_message()
+}
+
+pub fn serialize_market_data_reject(header: &MockFixHeader, req_id: &str, text: &str) -> Vec<u8> {
+    let mut fix_builder = create_message(header, "Y");
+    fix_builder.with_value("262", req_id);
+    fix_builder.with_value("58", text);
+    fix_builder.compile_message()
+}
+
+pub fn serialize_snapshot(
+    header: &MockFixHeader,
+    req_id: &str,
+    symbol: &str,
+    bid: f64,
+    ask: f64,
+) -> Vec<u8> {
+    let mut fix_builder = create_message(header, "W");
+    fix_builder.with_value("262", req_id);
+    fix_builder.with_value("55", symbol);
+    fix_builder.with_value("268", "2");
+    fix_builder.with_value("269", "0");
+    fix_builder.with_value("270", bid.to_string().as_str());
+    fix_builder.with_value("271", "1000000");
+    fix_builder.with_value("269", "1");
+    fix_builder.with_value("270", ask.to_string().as_str());
+    fix_builder.with_value("271", "1000000");
+    fix_builder.compile_message()
+}
+
+// Takes one complete message (up to and including the CheckSum field) out of the buffer
+pub fn take_fix_frame(buffer: &mut Vec<u8>) -> Option<Vec<u8>> {
+    let mut field_start = 0;
+
+    while let Some(pos) = buffer[field_start..]
+        .iter()
+        .position(|b| *b == FIX_DELIMITER)
+    {
+        let field_end = field_start + pos;
+
+        if buffer[field_start..field_end].starts_with(b"10=") {
+            return Some(buffer.drain(..=field_end).collect());
+        }
+
+        field_start = field_end + 1;
+    }
+
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::take_fix_frame;
+
+    #[test]
+    fn test_take_fix_frame() {
+        let mut buffer = b"8=FIX.4.4\x0135=0\x0110=123\x018=FIX.4.4\x0135=A".to_vec();
+
+        let frame = take_fix_frame(&mut buffer).unwrap();
+
+        assert_eq!(frame, b"8=FIX.4.4\x0135=0\x0110=123\x01".to_vec());
+        assert_eq!(buffer, b"8=FIX.4.4\x0135=A".to_vec());
+        assert!(take_fix_frame(&mut buffer).is_none());
+    }
+}
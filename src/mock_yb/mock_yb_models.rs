@@ -0,0 +1,168 @@
+use std::{
+    collections::HashMap,
+    sync::atomic::{AtomicUsize, Ordering},
+    time::Duration,
+};
+
+#[derive(Debug, Clone)]
+pub struct MockQuote {
+    pub symbol: String,
+    pub bid: f64,
+    pub ask: f64,
+}
+
+#[derive(Debug, Clone)]
+pub enum MockQuoteSource {
+    // Quotes are played per symbol in the given order and then started over
+    Scripted(Vec<MockQuote>),
+    RandomWalk {
+        start_mid: f64,
+        spread: f64,
+        max_step: f64,
+    },
+}
+
+#[derive(Debug, Clone)]
+pub struct MockYbSettings {
+    pub quote_source: MockQuoteSource,
+    pub quote_interval: Duration,
+    pub reject_symbols: Vec<String>,
+}
+
+impl Default for MockYbSettings {
+    fn default() -> Self {
+        Self {
+            quote_source: MockQuoteSource::RandomWalk {
+                start_mid: 1.1,
+                spread: 0.0002,
+                max_step: 0.0005,
+            },
+            quote_interval: Duration::from_millis(500),
+            reject_symbols: Vec::new(),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub enum MockInjection {
+    Reject(String),
+    Logout(String),
+    MarketDataReject(String),
+    SequenceGap(u64),
+    Garbage(Vec<u8>),
+}
+
+impl MockInjection {
+    pub fn garbage() -> Self {
+        Self::Garbage(b"8=FIX.4.4\x019=9\x0135=W\x01garbage\x0110=000\x01".to_vec())
+    }
+}
+
+#[derive(Default)]
+pub struct MockYbStats {
+    pub connections: AtomicUsize,
+    pub accept_errors: AtomicUsize,
+    // Frames without a readable message type
+    pub skipped_frames: AtomicUsize,
+    pub logons: AtomicUsize,
+    pub market_data_requests: AtomicUsize,
+    pub market_data_unsubscribes: AtomicUsize,
+    pub quotes_sent: AtomicUsize,
+}
+
+impl MockYbStats {
+    pub fn get_connections(&self) -> usize {
+        self.connections.load(Ordering::Relaxed)
+    }
+
+    pub fn get_accept_errors(&self) -> usize {
+        self.accept_errors.load(Ordering::Relaxed)
+    }
+
+    pub fn get_skipped_frames(&self) -> usize {
+        self.skipped_frames.load(Ordering::Relaxed)
+    }
+
+    pub fn get_logons(&self) -> usize {
+        self.logons.load(Ordering::Relaxed)
+    }
+
+    pub fn get_market_data_requests(&self) -> usize {
+        self.market_data_requests.load(Ordering::Relaxed)
+    }
+
+    pub fn get_market_data_unsubscribes(&self) -> usize {
+        self.market_data_unsubscribes.load(Ordering::Relaxed)
+    }
+
+    pub fn get_quotes_sent(&self) -> usize {
+        self.quotes_sent.load(Ordering::Relaxed)
+    }
+}
+
+pub struct MockQuoteGenerator {
+    source: MockQuoteSource,
+    scripted_positions: HashMap<String, usize>,
+    random_walk_mids: HashMap<String, f64>,
+    seed: u64,
+}
+
+impl MockQuoteGenerator {
+    pub fn new(source: MockQuoteSource, seed: u64) -> Self {
+        Self {
+            source,
+            scripted_positions: HashMap::new(),
+            random_walk_mids: HashMap::new(),
+            seed: seed | 1,
+        }
+    }
+
+    pub fn next_quote(&mut self, symbol: &str) -> Option<(f64, f64)> {
+        match &self.source {
+            MockQuoteSource::Scripted(quotes) => {
+                let symbol_quotes: Vec<&MockQuote> =
+                    quotes.iter().filter(|q| q.symbol == symbol).collect();
+
+                if symbol_quotes.is_empty() {
+                    return None;
+                }
+
+                let position = self
+                    .scripted_positions
+                    .entry(symbol.to_string())
+                    .or_insert(0);
+
+                let quote = symbol_quotes[*position % symbol_quotes.len()];
+                *position += 1;
+
+                Some((quote.bid, quote.ask))
+            }
+            MockQuoteSource::RandomWalk {
+                start_mid,
+                spread,
+                max_step,
+            } => {
+                let (start_mid, spread, max_step) = (*start_mid, *spread, *max_step);
+
+                let step = (self.next_random() * 2.0 - 1.0) * max_step;
+
+                let mid = self
+                    .random_walk_mids
+                    .entry(symbol.to_string())
+                    .or_insert(start_mid);
+
+                *mid = (*mid + step).max(spread);
+
+                Some((*mid - spread / 2.0, *mid + spread / 2.0))
+            }
+        }
+    }
+
+    // xorshift64 - good enough to move prices around
+    fn next_random(&mut self) -> f64 {
+        self.seed ^= self.seed << 13;
+        self.seed ^= self.seed >> 7;
+        self.seed ^= self.seed << 17;
+        (self.seed % 1_000_000) as f64 / 1_000_000.0
+    }
+}
@@ -0,0 +1,279 @@
+use std::sync::{
+    atomic::{AtomicBool, AtomicU64, Ordering},
+    Arc,
+};
+
+use rust_fix::FixMessageReader;
+use tokio::{
+    io::{AsyncReadExt, AsyncWriteExt},
+    net::{
+        tcp::{OwnedReadHalf, OwnedWriteHalf},
+        TcpStream,
+    },
+    sync::{broadcast, Mutex},
+};
+
+use super::{mock_yb_fix::*, MockInjection, MockQuoteGenerator, MockYbSettings, MockYbStats};
+
+struct MockSubscription {
+    symbol: String,
+    req_id: String,
+}
+
+pub struct MockYbSession {
+    settings: Arc<MockYbSettings>,
+    stats: Arc<MockYbStats>,
+    writer: Mutex<OwnedWriteHalf>,
+    seq_no: AtomicU64,
+    sender_company_id: Mutex<String>,
+    target_company_id: Mutex<String>,
+    subscriptions: Mutex<Vec<MockSubscription>>,
+    quote_generator: Mutex<MockQuoteGenerator>,
+    closed: AtomicBool,
+}
+
+impl MockYbSession {
+    pub fn start(
+        stream: TcpStream,
+        settings: Arc<MockYbSettings>,
+        stats: Arc<MockYbStats>,
+        injections: broadcast::Receiver<MockInjection>,
+        seed: u64,
+    ) {
+        let (reader, writer) = stream.into_split();
+
+        let session = Arc::new(Self {
+            quote_generator: Mutex::new(MockQuoteGenerator::new(
+                settings.quote_source.clone(),
+                seed,
+            )),
+            settings,
+            stats,
+            writer: Mutex::new(writer),
+            seq_no: AtomicU64::new(1),
+            sender_company_id: Mutex::new(String::new()),
+            target_company_id: Mutex::new(String::new()),
+            subscriptions: Mutex::new(Vec::new()),
+            closed: AtomicBool::new(false),
+        });
+
+        tokio::spawn(session.clone().read_loop(reader));
+        tokio::spawn(session.publish_loop(injections));
+    }
+
+    async fn read_loop(self: Arc<Self>, mut reader: OwnedReadHalf) {
+        let mut buffer = Vec::new();
+        let mut read_buffer = [0u8; 4096];
+
+        while !self.closed.load(Ordering::Relaxed) {
+            let read = match reader.read(&mut read_buffer).await {
+                Ok(0) | Err(_) => break,
+                Ok(read) => read,
+            };
+
+            buffer.extend_from_slice(&read_buffer[..read]);
+
+            while let Some(frame) = take_fix_frame(&mut buffer) {
+                self.handle_message(frame).await;
+            }
+        }
+
+        self.closed.store(true, Ordering::Relaxed);
+    }
+
+    async fn publish_loop(self: Arc<Self>, mut injections: broadcast::Receiver<MockInjection>) {
+        let mut interval = tokio::time::interval(self.settings.quote_interval);
+
+        while !self.closed.load(Ordering::Relaxed) {
+            tokio::select! {
+                _ = interval.tick() => {
+                    self.publish_quotes().await;
+                }
+                injection = injections.recv() => {
+                    match injection {
+                        Ok(injection) => self.apply_injection(injection).await,
+                        Err(broadcast::error::RecvError::Lagged(_)) => {}
+                        Err(broadcast::error::RecvError::Closed) => {
+                            self.close().await;
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    async fn handle_message(&self, frame: Vec<u8>) {
+        let message = FixMessageReader::from_bytes(&frame);
+
+        let message_type = match message.get_message_type() {
+            Ok(message_type) => message_type.to_string(),
+            Err(_) => {
+                self.stats.skipped_frames.fetch_add(1, Ordering::Relaxed);
+                return;
+            }
+        };
+
+        match message_type.as_str() {
+            "A" => {
+                // We answer as the venue, so company ids are swapped
+                if let Ok(Some(sender)) = message.get_value("49") {
+                    *self.target_company_id.lock().await = sender.to_string();
+                }
+                if let Ok(Some(target)) = message.get_value("56") {
+                    *self.sender_company_id.lock().await = target.to_string();
+                }
+
+                self.stats.logons.fetch_add(1, Ordering::Relaxed);
+                self.send_with_header(|header| serialize_logon(header))
+                    .await;
+            }
+            "V" => {
+                let symbol = get_string_value(&message, "55");
+                let req_id = get_string_value(&message, "262");
+
+                // 2 = Disable previous Snapshot + Updates
+                if get_string_value(&message, "263") == "2" {
+                    self.stats
+                        .market_data_unsubscribes
+                        .fetch_add(1, Ordering::Relaxed);
+
+                    let mut subscriptions = self.subscriptions.lock().await;
+                    subscriptions.retain(|itm| itm.req_id != req_id);
+                    return;
+                }
+
+                self.stats
+                    .market_data_requests
+                    .fetch_add(1, Ordering::Relaxed);
+
+                if self.settings.reject_symbols.contains(&symbol) {
+                    self.send_with_header(|header| {
+                        serialize_market_data_reject(header, req_id.as_str(), "Unknown symbol")
+                    })
+                    .await;
+                    return;
+                }
+
+                let mut subscriptions = self.subscriptions.lock().await;
+                subscriptions.retain(|itm| itm.symbol != symbol);
+                subscriptions.push(MockSubscription {
+                    symbol: symbol.clone(),
+                    req_id: req_id.clone(),
+                });
+                drop(subscriptions);
+
+                self.publish_quote(symbol.as_str(), req_id.as_str()).await;
+            }
+            "1" => {
+                let test_req_id = get_string_value(&message, "112");
+                self.send_with_header(|header| {
+                    serialize_heartbeat(header, Some(test_req_id.as_str()))
+                })
+                .await;
+            }
+            "5" => {
+                self.send_with_header(|header| serialize_logout(header, "Bye"))
+                    .await;
+                self.close().await;
+            }
+            _ => {}
+        }
+    }
+
+    async fn apply_injection(&self, injection: MockInjection) {
+        match injection {
+            MockInjection::Reject(text) => {
+                let ref_seq_no = self.seq_no.load(Ordering::Relaxed);
+                self.send_with_header(|header| serialize_reject(header, ref_seq_no, text.as_str()))
+                    .await;
+            }
+            MockInjection::Logout(text) => {
+                self.send_with_header(|header| serialize_logout(header, text.as_str()))
+                    .await;
+                self.close().await;
+            }
+            MockInjection::MarketDataReject(symbol) => {
+                let mut subscriptions = self.subscriptions.lock().await;
+                let index = subscriptions.iter().position(|itm| itm.symbol == symbol);
+
+                if let Some(index) = index {
+                    let subscription = subscriptions.remove(index);
+                    drop(subscriptions);
+
+                    self.send_with_header(|header| {
+                        serialize_market_data_reject(
+                            header,
+                            subscription.req_id.as_str(),
+                            "Injected reject",
+                        )
+                    })
+                    .await;
+                }
+            }
+            MockInjection::SequenceGap(gap) => {
+                self.seq_no.fetch_add(gap, Ordering::Relaxed);
+            }
+            MockInjection::Garbage(payload) => {
+                self.write(payload.as_slice()).await;
+            }
+        }
+    }
+
+    async fn publish_quotes(&self) {
+        let subscriptions: Vec<(String, String)> = self
+            .subscriptions
+            .lock()
+            .await
+            .iter()
+            .map(|itm| (itm.symbol.clone(), itm.req_id.clone()))
+            .collect();
+
+        for (symbol, req_id) in subscriptions {
+            self.publish_quote(symbol.as_str(), req_id.as_str()).await;
+        }
+    }
+
+    async fn publish_quote(&self, symbol: &str, req_id: &str) {
+        let quote = self.quote_generator.lock().await.next_quote(symbol);
+
+        if let Some((bid, ask)) = quote {
+            self.send_with_header(|header| serialize_snapshot(header, req_id, symbol, bid, ask))
+                .await;
+            self.stats.quotes_sent.fetch_add(1, Ordering::Relaxed);
+        }
+    }
+
+    async fn send_with_header(&self, serialize: impl FnOnce(&MockFixHeader) -> Vec<u8>) {
+        let sender_company_id = self.sender_company_id.lock().await.clone();
+        let target_company_id = self.target_company_id.lock().await.clone();
+
+        let header = MockFixHeader {
+            sender_company_id: sender_company_id.as_str(),
+            target_company_id: target_company_id.as_str(),
+            seq_no: self.seq_no.fetch_add(1, Ordering::Relaxed),
+        };
+
+        let payload = serialize(&header);
+        self.write(payload.as_slice()).await;
+    }
+
+    async fn write(&self, payload: &[u8]) {
+        let mut writer = self.writer.lock().await;
+        if writer.write_all(payload).await.is_err() {
+            self.closed.store(true, Ordering::Relaxed);
+        }
+    }
+
+    async fn close(&self) {
+        self.closed.store(true, Ordering::Relaxed);
+        let mut writer = self.writer.lock().await;
+        let _ = writer.shutdown().await;
+    }
+}
+
+fn get_string_value(message: &FixMessageReader, key: &str) -> String {
+    match message.get_value(key) {
+        Ok(Some(value)) => value.to_string(),
+        _ => String::new(),
+    }
+}
@@ -0,0 +1,6 @@
+mod mock_yb_acceptor;
+pub use mock_yb_acceptor::*;
+mod mock_yb_models;
+pub use mock_yb_models::*;
+mod mock_yb_fix;
+mod mock_yb_session;
@@ -0,0 +1,59 @@
+use serde::{Deserialize, Serialize};
+
+use crate::app::BidAskCandle;
+service_sdk::macros::use_my_no_sql_entity!();
+
+// Candle of published prices. PartitionKey: "{lp}:{instrument}:{type}", RowKey: candle start
+#[my_no_sql_entity("bidask-candles")]
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "PascalCase")]
+pub struct BidAskCandleNoSqlEntity {
+    pub instrument_id: String,
+    pub candle_type: String,
+    pub start: String,
+    // False - candle is in progress and will be updated
+    pub is_closed: bool,
+    pub bid_open: f64,
+    pub bid_high: f64,
+    pub bid_low: f64,
+    pub bid_close: f64,
+    pub ask_open: f64,
+    pub ask_high: f64,
+    pub ask_low: f64,
+    pub ask_close: f64,
+    pub ticks: u64,
+}
+
+impl BidAskCandleNoSqlEntity {
+    pub fn generate_partition_key(lp_id: &str, candle: &BidAskCandle) -> String {
+        format!(
+            "{}:{}:{}",
+            lp_id,
+            candle.instrument_id,
+            candle.candle_type.as_str()
+        )
+    }
+
+    pub fn from_candle(lp_id: &str, candle: &BidAskCandle) -> Self {
+        let start = candle.start.to_rfc3339();
+
+        Self {
+            partition_key: Self::generate_partition_key(lp_id, candle),
+            row_key: start.clone(),
+            time_stamp: "".to_string(),
+            instrument_id: candle.instrument_id.to_string(),
+            candle_type: candle.candle_type.as_str().to_string(),
+            start,
+            is_closed: candle.is_closed,
+            bid_open: candle.bid.open,
+            bid_high: candle.bid.high,
+            bid_low: candle.bid.low,
+            bid_close: candle.bid.close,
+            ask_open: candle.ask.open,
+            ask_high: candle.ask.high,
+            ask_low: candle.ask.low,
+            ask_close: candle.ask.close,
+            ticks: candle.ticks,
+        }
+    }
+}
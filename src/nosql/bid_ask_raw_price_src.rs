@@ -0,0 +1,27 @@
+use rust_extensions::sorted_vec::EntityWithStrKey;
+use serde::{Deserialize, Serialize};
+service_sdk::macros::use_my_no_sql_entity!();
+
+// LP price before markup next to the price we publish. Used by risk to reconcile
+#[my_no_sql_entity("bidask-raw-price-src")]
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "PascalCase")]
+pub struct BidAskRawPriceSrcNoSqlEntity {
+    pub src_id: String,
+    pub raw_bid: f64,
+    pub raw_ask: f64,
+    pub bid: f64,
+    pub ask: f64,
+    pub dt: String,
+    // No updates for longer than the instrument stale threshold. Dt is the last update
+    #[serde(default)]
+    pub is_stale: bool,
+    #[serde(default)]
+    pub stale_age_sec: u64,
+}
+
+impl EntityWithStrKey for BidAskRawPriceSrcNoSqlEntity {
+    fn get_key(&self) -> &str {
+        self.row_key.as_str()
+    }
+}
@@ -0,0 +1,4 @@
+mod bid_ask_raw_price_src;
+pub use bid_ask_raw_price_src::*;
+mod bid_ask_candle;
+pub use bid_ask_candle::*;
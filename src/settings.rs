@@ -1,4 +1,16 @@
+use std::{
+    collections::HashMap,
+    net::{IpAddr, SocketAddr},
+};
+
+use chrono::{Datelike, Timelike};
 use my_nosql_contracts::YbPriceFeedSettings;
+use rust_extensions::date_time::DateTimeAsMicroseconds;
+
+use crate::{
+    app::{CrossedQuoteAction, RoundingMode, SyntheticFormula},
+    your_bourse::QuoteTimeSource,
+};
 use serde::{Deserialize, Serialize};
 service_sdk::macros::use_settings!();
 
@@ -10,6 +22,7 @@ service_sdk::macros::use_settings!();
     Deserialize,
     Debug,
     Clone,
+    Default,
 )]
 pub struct SettingsModel {
     pub seq_conn_string: String,
@@ -18,9 +31,34 @@ pub struct SettingsModel {
     pub my_telemetry: String,
     pub my_no_sql_writer: String,
     pub feed_settings: Option<YbPriceFeedSettingsModel>,
+    pub fix_journal: Option<FixJournalSettingsModel>,
+    // None - silent symbols are not resubscribed
+    pub subscription_watchdog: Option<SubscriptionWatchdogSettingsModel>,
+    pub session_watchdog: Option<SessionWatchdogSettingsModel>,
+    pub quote_time_source: Option<QuoteTimeSource>,
+    pub clock_skew: Option<ClockSkewSettingsModel>,
+    pub spike_filter: Option<SpikeFilterSettingsModel>,
+    pub crossed_quotes: Option<CrossedQuotesSettingsModel>,
+    // Our instrument id -> pricing settings
+    pub instruments: Option<HashMap<String, InstrumentSettingsModel>>,
+    // Our instrument id -> formula over external symbols
+    pub synthetic_instruments: Option<HashMap<String, SyntheticInstrumentSettingsModel>>,
+    pub price_clients: Option<PriceClientsSettingsModel>,
+    pub stale_prices: Option<StalePricesSettingsModel>,
+    // None - ticks are not recorded
+    pub tick_recorder: Option<TickRecorderSettingsModel>,
+    // None - WebSocket price stream is off
+    pub price_websocket: Option<PriceWebSocketSettingsModel>,
 }
 
 impl SettingsReader {
+    // Settings kept in memory instead of the settings file. Tests and tools
+    pub fn from_model(model: SettingsModel) -> Self {
+        Self {
+            settings: std::sync::Arc::new(tokio::sync::RwLock::new(model)),
+        }
+    }
+
     pub async fn get_liquidity_provider_id(&self) -> String {
         let read = self.settings.read().await;
         read.liquidity_provider_id.to_string()
@@ -40,6 +78,183 @@ impl SettingsReader {
 
         Some(result)
     }
+
+    pub async fn get_fix_journal_settings(&self) -> FixJournalSettingsModel {
+        let read = self.settings.read().await;
+        read.fix_journal.clone().unwrap_or_default()
+    }
+
+    pub async fn get_subscription_watchdog_settings(
+        &self,
+    ) -> Option<SubscriptionWatchdogSettingsModel> {
+        let read = self.settings.read().await;
+        read.subscription_watchdog.clone()
+    }
+
+    pub async fn get_quote_time_source(&self) -> QuoteTimeSource {
+        let read = self.settings.read().await;
+        read.quote_time_source.unwrap_or_default()
+    }
+
+    pub async fn get_clock_skew_settings(&self) -> ClockSkewSettingsModel {
+        let read = self.settings.read().await;
+        read.clock_skew.clone().unwrap_or_default()
+    }
+
+    pub async fn get_spike_filter_rule(
+        &self,
+        external_symbol: &str,
+    ) -> Option<SpikeFilterRuleModel> {
+        let read = self.settings.read().await;
+        let settings = read.spike_filter.as_ref()?;
+
+        if let Some(instruments) = settings.instruments.as_ref() {
+            if let Some(rule) = instruments.get(external_symbol) {
+                return Some(rule.clone());
+            }
+        }
+
+        settings.default_rule.clone()
+    }
+
+    pub async fn get_crossed_quote_policy(&self, external_symbol: &str) -> CrossedQuotePolicyModel {
+        let read = self.settings.read().await;
+
+        let settings = match read.crossed_quotes.as_ref() {
+            Some(settings) => settings,
+            None => return CrossedQuotePolicyModel::default(),
+        };
+
+        if let Some(instruments) = settings.instruments.as_ref() {
+            if let Some(policy) = instruments.get(external_symbol) {
+                return policy.clone();
+            }
+        }
+
+        settings.default_policy.clone().unwrap_or_default()
+    }
+
+    pub async fn get_instruments_settings(
+        &self,
+        instruments: &[String],
+    ) -> HashMap<String, InstrumentSettingsModel> {
+        let read = self.settings.read().await;
+
+        let settings = match read.instruments.as_ref() {
+            Some(settings) => settings,
+            None => return HashMap::new(),
+        };
+
+        instruments
+            .iter()
+            .filter_map(|instrument_id| {
+                let instrument_settings = settings.get(instrument_id)?;
+                Some((instrument_id.to_string(), instrument_settings.clone()))
+            })
+            .collect()
+    }
+
+    // Synthetic instruments which have the external symbol as one of the legs
+    pub async fn get_synthetic_instruments_by_leg(
+        &self,
+        external_symbol: &str,
+    ) -> Vec<(String, SyntheticInstrumentSettingsModel)> {
+        let read = self.settings.read().await;
+
+        let settings = match read.synthetic_instruments.as_ref() {
+            Some(settings) => settings,
+            None => return Vec::new(),
+        };
+
+        settings
+            .iter()
+            .filter(|(_, synthetic)| synthetic.has_leg(external_symbol))
+            .map(|(instrument_id, synthetic)| (instrument_id.to_string(), synthetic.clone()))
+            .collect()
+    }
+
+    pub async fn get_synthetic_legs(&self) -> Vec<String> {
+        let read = self.settings.read().await;
+        let mut result: Vec<String> = Vec::new();
+
+        for synthetic in read.synthetic_instruments.iter().flat_map(|s| s.values()) {
+            for leg in &synthetic.legs {
+                if !result.contains(&leg.external_symbol) {
+                    result.push(leg.external_symbol.to_string());
+                }
+            }
+        }
+
+        result
+    }
+
+    pub async fn get_price_clients_settings(&self) -> PriceClientsSettingsModel {
+        let read = self.settings.read().await;
+        read.price_clients.clone().unwrap_or_default()
+    }
+
+    pub async fn get_stale_prices_settings(&self) -> StalePricesSettingsModel {
+        let read = self.settings.read().await;
+        read.stale_prices.clone().unwrap_or_default()
+    }
+
+    pub async fn get_tick_recorder_settings(&self) -> Option<TickRecorderSettingsModel> {
+        let read = self.settings.read().await;
+        read.tick_recorder.clone()
+    }
+
+    pub async fn get_price_websocket_settings(&self) -> Option<PriceWebSocketSettingsModel> {
+        let read = self.settings.read().await;
+        read.price_websocket.clone()
+    }
+
+    pub async fn get_session_watchdog_settings(&self) -> Option<SessionWatchdogSettingsModel> {
+        let read = self.settings.read().await;
+        read.session_watchdog.clone()
+    }
+
+    // Invalid trading hours never match, so watchdogs would stay silent without it
+    pub async fn validate(&self) -> Result<(), String> {
+        let read = self.settings.read().await;
+
+        if let Some(price_websocket) = read.price_websocket.as_ref() {
+            price_websocket.get_addr()?;
+        }
+
+        let session_trading_hours = read
+            .session_watchdog
+            .as_ref()
+            .and_then(|settings| settings.trading_hours.as_ref());
+
+        let subscription_trading_hours = read
+            .subscription_watchdog
+            .as_ref()
+            .and_then(|settings| settings.trading_hours.as_ref());
+
+        for trading_hours in session_trading_hours
+            .into_iter()
+            .chain(subscription_trading_hours)
+            .flatten()
+        {
+            trading_hours.validate()?;
+        }
+
+        if let Some(spike_filter) = read.spike_filter.as_ref() {
+            spike_filter.validate()?;
+        }
+
+        if let Some(price_clients) = read.price_clients.as_ref() {
+            price_clients.validate()?;
+        }
+
+        for (instrument_id, instrument_settings) in read.instruments.iter().flatten() {
+            instrument_settings
+                .validate()
+                .map_err(|err| format!("Instrument {}. {}", instrument_id, err))?;
+        }
+
+        Ok(())
+    }
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
@@ -49,3 +264,691 @@ pub struct YbPriceFeedSettingsModel {
     pub target_company_id: String,
     pub user_password: String,
 }
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct FixJournalSettingsModel {
+    pub path: String,
+    pub max_file_size_mb: u64,
+    pub rotate_interval_min: u64,
+    pub retention_files: usize,
+}
+
+impl Default for FixJournalSettingsModel {
+    fn default() -> Self {
+        Self {
+            path: "./fix-journal".to_string(),
+            max_file_size_mb: 100,
+            rotate_interval_min: 60,
+            retention_files: 72,
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct PriceWebSocketSettingsModel {
+    pub port: u16,
+    // Interface to listen on. None - 127.0.0.1, set 0.0.0.0 to expose the stream
+    pub bind_address: Option<String>,
+}
+
+impl PriceWebSocketSettingsModel {
+    pub fn get_addr(&self) -> Result<SocketAddr, String> {
+        let bind_address = self.bind_address.as_deref().unwrap_or("127.0.0.1");
+
+        let ip: IpAddr = bind_address.parse().map_err(|err| {
+            format!(
+                "Invalid WebSocket bind address '{}'. Err: {}",
+                bind_address, err
+            )
+        })?;
+
+        Ok(SocketAddr::new(ip, self.port))
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct TickRecorderSettingsModel {
+    pub path: String,
+    // Day directories to keep
+    pub retention_days: usize,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct SubscriptionWatchdogSettingsModel {
+    pub silence_threshold_sec: u64,
+    // External symbol -> silence threshold. Overrides silence_threshold_sec
+    pub instruments_silence_threshold_sec: Option<HashMap<String, u64>>,
+    pub alert_after_attempts: u32,
+    // Every attempt doubles the wait before the next one up to this. None - 600 sec
+    pub max_resubscribe_interval_sec: Option<u64>,
+    // No trading hours - watchdog is active all the time
+    pub trading_hours: Option<Vec<TradingHoursSettingsModel>>,
+}
+
+impl SubscriptionWatchdogSettingsModel {
+    pub fn get_silence_threshold_sec(&self, external_symbol: &str) -> u64 {
+        if let Some(thresholds) = self.instruments_silence_threshold_sec.as_ref() {
+            if let Some(threshold) = thresholds.get(external_symbol) {
+                return *threshold;
+            }
+        }
+
+        self.silence_threshold_sec
+    }
+
+    pub fn get_max_resubscribe_interval_sec(&self) -> u64 {
+        self.max_resubscribe_interval_sec.unwrap_or(600)
+    }
+
+    pub fn is_trading_time(&self, now: DateTimeAsMicroseconds) -> bool {
+        is_trading_time(self.trading_hours.as_ref(), now)
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct StalePricesSettingsModel {
+    // Instrument is stale when its last price is older than this
+    pub stale_after_sec: u64,
+    // Our instrument id -> threshold. E.g. for instruments which update rarely
+    pub instruments_stale_after_sec: Option<HashMap<String, u64>>,
+}
+
+impl StalePricesSettingsModel {
+    pub fn get_stale_after_sec(&self, instrument_id: &str) -> u64 {
+        if let Some(thresholds) = self.instruments_stale_after_sec.as_ref() {
+            if let Some(threshold) = thresholds.get(instrument_id) {
+                return *threshold;
+            }
+        }
+
+        self.stale_after_sec
+    }
+}
+
+impl Default for StalePricesSettingsModel {
+    fn default() -> Self {
+        Self {
+            stale_after_sec: 30,
+            instruments_stale_after_sec: None,
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct ClockSkewSettingsModel {
+    pub window_sec: u64,
+    // Also the skew below which quote times are not corrected
+    pub warn_threshold_ms: i64,
+    // Moves venue quote timestamps to the bridge clock by the estimated skew.
+    // The estimate includes network latency, so corrected times land at about receive time
+    pub correct_quote_time: bool,
+}
+
+impl Default for ClockSkewSettingsModel {
+    fn default() -> Self {
+        Self {
+            window_sec: 60,
+            warn_threshold_ms: 500,
+            correct_quote_time: false,
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct SpikeFilterSettingsModel {
+    pub default_rule: Option<SpikeFilterRuleModel>,
+    // External symbol -> rule. Overrides default_rule
+    pub instruments: Option<HashMap<String, SpikeFilterRuleModel>>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct SpikeFilterRuleModel {
+    pub max_move_percent: Option<f64>,
+    pub max_move_pips: Option<f64>,
+    pub pip_size: Option<f64>,
+    // Moves are checked against the last accepted quote only if it is younger than the window
+    pub window_sec: u64,
+    // None - spikes are dropped. Some(k) - spike is accepted if the next k quotes agree with it
+    pub confirm_after_quotes: Option<u32>,
+}
+
+impl SpikeFilterSettingsModel {
+    pub fn validate(&self) -> Result<(), String> {
+        if let Some(default_rule) = self.default_rule.as_ref() {
+            default_rule
+                .validate()
+                .map_err(|err| format!("Spike filter default rule. {}", err))?;
+        }
+
+        for (external_symbol, rule) in self.instruments.iter().flatten() {
+            rule.validate()
+                .map_err(|err| format!("Spike filter rule of {}. {}", external_symbol, err))?;
+        }
+
+        Ok(())
+    }
+}
+
+impl SpikeFilterRuleModel {
+    // Pips move can not be measured without pip_size. The rule would never fire on it
+    pub fn validate(&self) -> Result<(), String> {
+        if self.max_move_pips.is_some() && self.pip_size.is_none() {
+            return Err("max_move_pips is set without pip_size".to_string());
+        }
+
+        if let Some(pip_size) = self.pip_size {
+            if pip_size <= 0.0 {
+                return Err(format!("Invalid pip_size: {}", pip_size));
+            }
+        }
+
+        Ok(())
+    }
+
+    pub fn is_spike(&self, move_percent: f64, move_pips: f64) -> bool {
+        if let Some(max_move_percent) = self.max_move_percent {
+            if move_percent > max_move_percent {
+                return true;
+            }
+        }
+
+        if let Some(max_move_pips) = self.max_move_pips {
+            if self.pip_size.is_some() && move_pips > max_move_pips {
+                return true;
+            }
+        }
+
+        false
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct CrossedQuotesSettingsModel {
+    pub default_policy: Option<CrossedQuotePolicyModel>,
+    // External symbol -> policy. Overrides default_policy
+    pub instruments: Option<HashMap<String, CrossedQuotePolicyModel>>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+pub struct CrossedQuotePolicyModel {
+    // bid > ask
+    pub crossed: CrossedQuoteAction,
+    // bid == ask. Swap is treated as Pass
+    pub zero_spread: CrossedQuoteAction,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct InstrumentSettingsModel {
+    pub transform: Option<PriceTransformSettingsModel>,
+    pub markup: Option<MarkupSettingsModel>,
+    pub precision: Option<PrecisionSettingsModel>,
+    pub conflation: Option<ConflationSettingsModel>,
+}
+
+impl InstrumentSettingsModel {
+    // Zero steps turn prices into inf/NaN
+    pub fn validate(&self) -> Result<(), String> {
+        if let Some(precision) = self.precision.as_ref() {
+            precision.validate()?;
+        }
+
+        if let Some(markup) = self.markup.as_ref() {
+            if !(markup.pip_size > 0.0) {
+                return Err(format!("Invalid markup pip_size: {}", markup.pip_size));
+            }
+        }
+
+        Ok(())
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct ConflationSettingsModel {
+    // Not set - no limit
+    pub max_updates_per_sec: Option<u32>,
+    // Skip ticks where neither bid nor ask changed since the last sent one
+    pub drop_unchanged: bool,
+}
+
+// Converts the external symbol price into ours. E.g. USDJPY -> JPYUSD or cents -> dollars
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct PriceTransformSettingsModel {
+    #[serde(default)]
+    pub invert: bool,
+    pub multiplier: Option<f64>,
+    pub offset: Option<f64>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct MarkupSettingsModel {
+    pub pip_size: f64,
+    pub bid_pips: Option<f64>,
+    pub ask_pips: Option<f64>,
+    pub bid_percent: Option<f64>,
+    pub ask_percent: Option<f64>,
+    // Applied after pips and percents by widening around the mid
+    pub min_spread_pips: Option<f64>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct PrecisionSettingsModel {
+    pub digits: u32,
+    // If not set - 10^-digits
+    pub tick_size: Option<f64>,
+    pub bid_rounding: RoundingMode,
+    pub ask_rounding: RoundingMode,
+    // Drop LP prices which are not a multiple of the tick size
+    pub reject_misaligned: bool,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct SyntheticInstrumentSettingsModel {
+    pub formula: SyntheticFormula,
+    pub legs: Vec<SyntheticLegSettingsModel>,
+    // Price is not published while any leg is older than this
+    pub max_leg_age_ms: u64,
+}
+
+impl SyntheticInstrumentSettingsModel {
+    pub fn has_leg(&self, external_symbol: &str) -> bool {
+        self.legs
+            .iter()
+            .any(|leg| leg.external_symbol == external_symbol)
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct SyntheticLegSettingsModel {
+    pub external_symbol: String,
+    // Applied to the leg before combining. E.g. invert GBPUSD to get EURGBP from EURUSD
+    pub transform: Option<PriceTransformSettingsModel>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct PriceClientsSettingsModel {
+    // Queued messages of one consumer. At it ticks of the same instrument are conflated
+    // and the consumer is reported as behind
+    pub soft_queue_limit: usize,
+    // Over it the consumer is disconnected
+    pub hard_queue_limit: usize,
+    // Consumer is disconnected if its oldest queued message waits longer
+    pub max_lag_ms: i64,
+}
+
+impl Default for PriceClientsSettingsModel {
+    fn default() -> Self {
+        Self {
+            soft_queue_limit: 1_000,
+            hard_queue_limit: 10_000,
+            max_lag_ms: 5_000,
+        }
+    }
+}
+
+impl PriceClientsSettingsModel {
+    // A consumer would be dropped before it is ever reported as behind
+    pub fn validate(&self) -> Result<(), String> {
+        if self.soft_queue_limit == 0 || self.hard_queue_limit == 0 {
+            return Err("Price clients queue limits must be greater than 0".to_string());
+        }
+
+        if self.soft_queue_limit >= self.hard_queue_limit {
+            return Err(format!(
+                "Price clients soft_queue_limit {} must be less than hard_queue_limit {}",
+                self.soft_queue_limit, self.hard_queue_limit
+            ));
+        }
+
+        Ok(())
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct SessionWatchdogSettingsModel {
+    pub silence_threshold_sec: u64,
+    // No trading hours - watchdog is active all the time
+    pub trading_hours: Option<Vec<TradingHoursSettingsModel>>,
+}
+
+impl SessionWatchdogSettingsModel {
+    pub fn is_trading_time(&self, now: DateTimeAsMicroseconds) -> bool {
+        is_trading_time(self.trading_hours.as_ref(), now)
+    }
+
+    // Silence counts from the last activity, but never from before the trading window opened.
+    // None - outside trading hours
+    pub fn get_silence_started(
+        &self,
+        now: DateTimeAsMicroseconds,
+        last_activity: DateTimeAsMicroseconds,
+    ) -> Option<DateTimeAsMicroseconds> {
+        let trading_hours = match self.trading_hours.as_ref() {
+            Some(trading_hours) => trading_hours,
+            None => return Some(last_activity),
+        };
+
+        let window_start = trading_hours
+            .iter()
+            .filter_map(|itm| itm.get_window_start(now))
+            .min_by_key(|moment| moment.unix_microseconds)?;
+
+        if window_start.unix_microseconds > last_activity.unix_microseconds {
+            Some(window_start)
+        } else {
+            Some(last_activity)
+        }
+    }
+}
+
+fn is_trading_time(
+    trading_hours: Option<&Vec<TradingHoursSettingsModel>>,
+    now: DateTimeAsMicroseconds,
+) -> bool {
+    match trading_hours {
+        Some(trading_hours) => trading_hours.iter().any(|itm| itm.is_inside(now)),
+        None => true,
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct TradingHoursSettingsModel {
+    // ISO day numbers: 1 - Monday ... 7 - Sunday
+    pub days: Vec<u32>,
+    // UTC, HH:MM. If from > to the interval goes through midnight
+    pub from: String,
+    pub to: String,
+}
+
+impl TradingHoursSettingsModel {
+    pub fn is_inside(&self, now: DateTimeAsMicroseconds) -> bool {
+        self.get_window_start(now).is_some()
+    }
+
+    // Moment the window now is inside of opened. None - outside or invalid settings
+    pub fn get_window_start(&self, now: DateTimeAsMicroseconds) -> Option<DateTimeAsMicroseconds> {
+        let (from, to) = self.parse_window().ok()?;
+        let now_utc = now.to_chrono_utc();
+        let day = now_utc.weekday().number_from_monday();
+
+        if !self.days.contains(&day) {
+            return None;
+        }
+
+        let minute_of_day = now_utc.hour() * 60 + now_utc.minute();
+        let day_start = now.unix_microseconds - now.unix_microseconds.rem_euclid(MICROS_IN_DAY);
+
+        if minute_of_day >= from && (from > to || minute_of_day < to) {
+            return Some(DateTimeAsMicroseconds::new(
+                day_start + from as i64 * MICROS_IN_MINUTE,
+            ));
+        }
+
+        if from > to && minute_of_day < to {
+            // Opened yesterday evening if yesterday is a trading day, at midnight otherwise
+            let yesterday = if day == 1 { 7 } else { day - 1 };
+
+            if self.days.contains(&yesterday) {
+                return Some(DateTimeAsMicroseconds::new(
+                    day_start - MICROS_IN_DAY + from as i64 * MICROS_IN_MINUTE,
+                ));
+            }
+
+            return Some(DateTimeAsMicroseconds::new(day_start));
+        }
+
+        None
+    }
+
+    pub fn validate(&self) -> Result<(), String> {
+        if let Some(day) = self.days.iter().find(|day| **day < 1 || **day > 7) {
+            return Err(format!("Invalid trading day: {}. Expected 1..7", day));
+        }
+
+        self.parse_window()?;
+        Ok(())
+    }
+
+    fn parse_window(&self) -> Result<(u32, u32), String> {
+        let from = parse_minute_of_day(self.from.as_str())?;
+        let to = parse_minute_of_day(self.to.as_str())?;
+        Ok((from, to))
+    }
+}
+
+const MICROS_IN_MINUTE: i64 = 60_000_000;
+const MICROS_IN_DAY: i64 = 24 * 60 * MICROS_IN_MINUTE;
+
+fn parse_minute_of_day(src: &str) -> Result<u32, String> {
+    let err = || format!("Invalid trading hours time: '{}'. Expected HH:MM", src);
+
+    let (hour, minute) = src.split_once(':').ok_or_else(err)?;
+    let hour: u32 = hour.trim().parse().map_err(|_| err())?;
+    let minute: u32 = minute.trim().parse().map_err(|_| err())?;
+
+    // 24:00 - end of the day
+    if minute > 59 || hour > 24 || (hour == 24 && minute > 0) {
+        return Err(err());
+    }
+
+    Ok(hour * 60 + minute)
+}
+
+#[cfg(test)]
+mod tests {
+    use rust_extensions::date_time::DateTimeAsMicroseconds;
+
+    use std::collections::HashMap;
+
+    use super::{
+        InstrumentSettingsModel, MarkupSettingsModel, PrecisionSettingsModel,
+        PriceClientsSettingsModel, PriceWebSocketSettingsModel, SessionWatchdogSettingsModel,
+        SettingsModel, SettingsReader, SpikeFilterRuleModel, SpikeFilterSettingsModel,
+        TradingHoursSettingsModel,
+    };
+
+    #[test]
+    fn test_trading_hours_through_midnight() {
+        let trading_hours = TradingHoursSettingsModel {
+            days: vec![1, 2, 3, 4, 5],
+            from: "22:00".to_string(),
+            to: "21:00".to_string(),
+        };
+
+        // 2024-04-25 is Thursday
+        let inside = DateTimeAsMicroseconds::create(2024, 4, 25, 23, 30, 0, 0);
+        let break_time = DateTimeAsMicroseconds::create(2024, 4, 25, 21, 30, 0, 0);
+        let weekend = DateTimeAsMicroseconds::create(2024, 4, 27, 12, 0, 0, 0);
+
+        assert!(trading_hours.is_inside(inside));
+        assert!(!trading_hours.is_inside(break_time));
+        assert!(!trading_hours.is_inside(weekend));
+    }
+
+    #[test]
+    fn test_silence_counts_from_window_open() {
+        let settings = SessionWatchdogSettingsModel {
+            silence_threshold_sec: 60,
+            trading_hours: Some(vec![TradingHoursSettingsModel {
+                days: vec![1, 2, 3, 4, 5],
+                from: "22:00".to_string(),
+                to: "21:00".to_string(),
+            }]),
+        };
+
+        // Last quote on Friday 2024-04-26 before the close, now 30 sec after Monday open
+        let last_activity = DateTimeAsMicroseconds::create(2024, 4, 26, 20, 59, 0, 0);
+        let window_open = DateTimeAsMicroseconds::create(2024, 4, 29, 22, 0, 0, 0);
+        let now = DateTimeAsMicroseconds::create(2024, 4, 29, 22, 0, 30, 0);
+
+        let silence_started = settings.get_silence_started(now, last_activity).unwrap();
+        assert_eq!(
+            silence_started.unix_microseconds,
+            window_open.unix_microseconds
+        );
+
+        // Monday window opens at midnight: Sunday evening is not a trading day
+        let monday_morning = DateTimeAsMicroseconds::create(2024, 4, 29, 0, 0, 10, 0);
+        let midnight = DateTimeAsMicroseconds::create(2024, 4, 29, 0, 0, 0, 0);
+        let silence_started = settings
+            .get_silence_started(monday_morning, last_activity)
+            .unwrap();
+        assert_eq!(
+            silence_started.unix_microseconds,
+            midnight.unix_microseconds
+        );
+
+        // Tuesday morning window opened on Monday evening
+        let tuesday_morning = DateTimeAsMicroseconds::create(2024, 4, 30, 1, 0, 0, 0);
+        let monday_evening = DateTimeAsMicroseconds::create(2024, 4, 29, 22, 0, 0, 0);
+        let silence_started = settings
+            .get_silence_started(tuesday_morning, last_activity)
+            .unwrap();
+        assert_eq!(
+            silence_started.unix_microseconds,
+            monday_evening.unix_microseconds
+        );
+
+        let weekend = DateTimeAsMicroseconds::create(2024, 4, 27, 12, 0, 0, 0);
+        assert!(settings
+            .get_silence_started(weekend, last_activity)
+            .is_none());
+    }
+
+    #[test]
+    fn test_invalid_trading_hours() {
+        let mut trading_hours = TradingHoursSettingsModel {
+            days: vec![1],
+            from: "2200".to_string(),
+            to: "21:00".to_string(),
+        };
+
+        assert!(trading_hours.validate().is_err());
+        // Monday
+        let now = DateTimeAsMicroseconds::create(2024, 4, 29, 23, 0, 0, 0);
+        assert!(!trading_hours.is_inside(now));
+
+        trading_hours.from = "22:75".to_string();
+        assert!(trading_hours.validate().is_err());
+
+        trading_hours.from = "22:00".to_string();
+        trading_hours.days = vec![8];
+        assert!(trading_hours.validate().is_err());
+
+        trading_hours.days = vec![1];
+        assert!(trading_hours.validate().is_ok());
+        assert!(trading_hours.is_inside(now));
+    }
+
+    #[test]
+    fn test_websocket_bind_address() {
+        let mut settings = PriceWebSocketSettingsModel {
+            port: 8090,
+            bind_address: None,
+        };
+        assert_eq!(settings.get_addr().unwrap().to_string(), "127.0.0.1:8090");
+
+        settings.bind_address = Some("0.0.0.0".to_string());
+        assert_eq!(settings.get_addr().unwrap().to_string(), "0.0.0.0:8090");
+
+        settings.bind_address = Some("localhost".to_string());
+        assert!(settings.get_addr().is_err());
+    }
+
+    #[tokio::test]
+    async fn test_pips_rule_without_pip_size_is_rejected() {
+        let mut rule = SpikeFilterRuleModel {
+            max_move_percent: None,
+            max_move_pips: Some(50.0),
+            pip_size: None,
+            window_sec: 10,
+            confirm_after_quotes: None,
+        };
+
+        let mut instruments = HashMap::new();
+        instruments.insert("EUR/USD".to_string(), rule.clone());
+
+        let settings_reader = SettingsReader::from_model(SettingsModel {
+            spike_filter: Some(SpikeFilterSettingsModel {
+                default_rule: None,
+                instruments: Some(instruments),
+            }),
+            ..Default::default()
+        });
+
+        let err = settings_reader.validate().await.unwrap_err();
+        assert!(err.contains("EUR/USD"));
+
+        rule.pip_size = Some(0.0001);
+        assert!(rule.validate().is_ok());
+
+        rule.max_move_pips = None;
+        rule.pip_size = None;
+        rule.max_move_percent = Some(1.0);
+        assert!(rule.validate().is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_invalid_instrument_settings_are_rejected() {
+        let mut instrument_settings = InstrumentSettingsModel {
+            transform: None,
+            markup: Some(MarkupSettingsModel {
+                pip_size: 0.0,
+                bid_pips: Some(1.0),
+                ask_pips: Some(1.0),
+                bid_percent: None,
+                ask_percent: None,
+                min_spread_pips: None,
+            }),
+            precision: None,
+            conflation: None,
+        };
+
+        let mut instruments = HashMap::new();
+        instruments.insert("EURUSD".to_string(), instrument_settings.clone());
+
+        let settings_reader = SettingsReader::from_model(SettingsModel {
+            instruments: Some(instruments),
+            ..Default::default()
+        });
+
+        let err = settings_reader.validate().await.unwrap_err();
+        assert!(err.contains("EURUSD"));
+
+        instrument_settings.markup = None;
+        instrument_settings.precision = Some(PrecisionSettingsModel {
+            digits: 5,
+            tick_size: Some(0.0),
+            bid_rounding: Default::default(),
+            ask_rounding: Default::default(),
+            reject_misaligned: false,
+        });
+        assert!(instrument_settings.validate().is_err());
+
+        instrument_settings.precision.as_mut().unwrap().tick_size = None;
+        assert!(instrument_settings.validate().is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_invalid_price_clients_limits_are_rejected() {
+        let settings_reader = SettingsReader::from_model(SettingsModel {
+            price_clients: Some(PriceClientsSettingsModel {
+                soft_queue_limit: 10_000,
+                hard_queue_limit: 1_000,
+                max_lag_ms: 5_000,
+            }),
+            ..Default::default()
+        });
+
+        let err = settings_reader.validate().await.unwrap_err();
+        assert!(err.contains("soft_queue_limit"));
+
+        let mut price_clients = PriceClientsSettingsModel::default();
+        assert!(price_clients.validate().is_ok());
+
+        price_clients.soft_queue_limit = 0;
+        assert!(price_clients.validate().is_err());
+
+        price_clients.soft_queue_limit = price_clients.hard_queue_limit;
+        assert!(price_clients.validate().is_err());
+    }
+}
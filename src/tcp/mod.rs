@@ -1,4 +1,10 @@
+mod price_tcp_contract;
+mod price_tcp_serializer;
+mod price_tcp_writer;
 mod tcp_event_handler;
 mod tcp_server;
 
+pub use price_tcp_contract::*;
+pub use price_tcp_serializer::*;
+pub use price_tcp_writer::*;
 pub use tcp_server::*;
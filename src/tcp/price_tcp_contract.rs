@@ -0,0 +1,172 @@
+use prices_tcp_contracts::{BidAskDataTcpModel, BidAskDateTimeTcpModel, BidAskTcpMessage};
+
+use crate::app::OutboundMessage;
+
+pub const PRICE_TCP_LINE_END: &'static [u8] = b"\r\n";
+
+const SUBSCRIBE_PREFIX: &'static str = "SUBSCRIBE ";
+const UNSUBSCRIBE_PREFIX: &'static str = "UNSUBSCRIBE ";
+const SNAPSHOT_BEGIN: &'static str = "SNAPSHOT_BEGIN";
+const SNAPSHOT_END: &'static str = "SNAPSHOT_END";
+const STALE_PREFIX: &'static str = "STALE ";
+const ACTIVE_PREFIX: &'static str = "ACTIVE ";
+
+// Price feed protocol plus control lines of our server.
+// Control lines: "SUBSCRIBE EURUSD,GBPUSD", "UNSUBSCRIBE EURUSD", "*" - all instruments.
+// Prices between SNAPSHOT_BEGIN and SNAPSHOT_END are last known values, not live ticks.
+// "STALE EURUSD 31000" - no updates for 31000 ms, "ACTIVE EURUSD" - updates are back.
+// Server control lines only go to clients which sent one. Others get plain feed lines.
+// The snapshot sent on connect is unmarked: the feed line has no room for a flag.
+// The first control line is answered with a marked snapshot of the whole subscription,
+// so a client opts in by sending "SUBSCRIBE *" right after connecting
+pub enum PriceTcpContract {
+    Feed(BidAskTcpMessage),
+    Subscribe(Vec<String>),
+    Unsubscribe(Vec<String>),
+    SnapshotBegin,
+    SnapshotEnd,
+    Stale { instrument_id: String, age_ms: i64 },
+    Active(String),
+    Unknown(String),
+}
+
+impl PriceTcpContract {
+    pub fn parse(line: &str) -> Self {
+        let line = line.trim();
+
+        if line == "PING" {
+            return Self::Feed(BidAskTcpMessage::Ping);
+        }
+
+        if line == "PONG" {
+            return Self::Feed(BidAskTcpMessage::Pong);
+        }
+
+        if line == SNAPSHOT_BEGIN {
+            return Self::SnapshotBegin;
+        }
+
+        if line == SNAPSHOT_END {
+            return Self::SnapshotEnd;
+        }
+
+        if let Some(list) = line.strip_prefix(SUBSCRIBE_PREFIX) {
+            return Self::Subscribe(parse_instruments(list));
+        }
+
+        if let Some(list) = line.strip_prefix(UNSUBSCRIBE_PREFIX) {
+            return Self::Unsubscribe(parse_instruments(list));
+        }
+
+        if let Some(status) = line.strip_prefix(STALE_PREFIX) {
+            let mut parts = status.split_whitespace();
+
+            if let (Some(instrument_id), Some(Ok(age_ms))) =
+                (parts.next(), parts.next().map(|age_ms| age_ms.parse()))
+            {
+                return Self::Stale {
+                    instrument_id: instrument_id.to_string(),
+                    age_ms,
+                };
+            }
+        }
+
+        if let Some(instrument_id) = line.strip_prefix(ACTIVE_PREFIX) {
+            return Self::Active(instrument_id.trim().to_string());
+        }
+
+        Self::Unknown(line.to_string())
+    }
+
+    pub fn from_outbound(lp_id: &str, message: &OutboundMessage) -> Self {
+        let tick = match message {
+            OutboundMessage::Tick(tick) => tick,
+            OutboundMessage::SnapshotBegin => return Self::SnapshotBegin,
+            OutboundMessage::SnapshotEnd => return Self::SnapshotEnd,
+            OutboundMessage::Stale {
+                instrument_id,
+                age_ms,
+            } => {
+                return Self::Stale {
+                    instrument_id: instrument_id.to_string(),
+                    age_ms: *age_ms,
+                }
+            }
+            OutboundMessage::Active { instrument_id } => {
+                return Self::Active(instrument_id.to_string())
+            }
+        };
+
+        let date_time = if tick.date_source.is_venue_time() {
+            BidAskDateTimeTcpModel::Source(tick.date)
+        } else {
+            BidAskDateTimeTcpModel::Our(tick.date)
+        };
+
+        Self::Feed(BidAskTcpMessage::BidAsk(BidAskDataTcpModel {
+            exchange_id: lp_id.to_string(),
+            instrument_id: tick.instrument_id.to_string(),
+            bid: tick.bid,
+            ask: tick.ask,
+            volume: 0.0,
+            date_time,
+        }))
+    }
+
+    // Control lines only. Feed messages are written by the feed serializer
+    pub fn to_control_line(&self) -> Option<String> {
+        match self {
+            Self::Feed(_) => None,
+            Self::Subscribe(instruments) => {
+                Some(format!("{}{}", SUBSCRIBE_PREFIX, instruments.join(",")))
+            }
+            Self::Unsubscribe(instruments) => {
+                Some(format!("{}{}", UNSUBSCRIBE_PREFIX, instruments.join(",")))
+            }
+            Self::SnapshotBegin => Some(SNAPSHOT_BEGIN.to_string()),
+            Self::SnapshotEnd => Some(SNAPSHOT_END.to_string()),
+            Self::Stale {
+                instrument_id,
+                age_ms,
+            } => Some(format!("{}{} {}", STALE_PREFIX, instrument_id, age_ms)),
+            Self::Active(instrument_id) => Some(format!("{}{}", ACTIVE_PREFIX, instrument_id)),
+            Self::Unknown(line) => Some(line.to_string()),
+        }
+    }
+}
+
+fn parse_instruments(src: &str) -> Vec<String> {
+    src.split(',')
+        .map(|instrument_id| instrument_id.trim())
+        .filter(|instrument_id| !instrument_id.is_empty())
+        .map(|instrument_id| instrument_id.to_string())
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::PriceTcpContract;
+
+    #[test]
+    fn test_parse_control_lines() {
+        assert!(matches!(
+            PriceTcpContract::parse("SUBSCRIBE EURUSD, GBPUSD\r\n"),
+            PriceTcpContract::Subscribe(instruments) if instruments == vec!["EURUSD", "GBPUSD"]
+        ));
+
+        assert!(matches!(
+            PriceTcpContract::parse("UNSUBSCRIBE *"),
+            PriceTcpContract::Unsubscribe(instruments) if instruments == vec!["*"]
+        ));
+
+        assert!(matches!(
+            PriceTcpContract::parse("PING"),
+            PriceTcpContract::Feed(message) if message.is_ping()
+        ));
+
+        assert!(matches!(
+            PriceTcpContract::parse("STALE EURUSD 31000"),
+            PriceTcpContract::Stale { instrument_id, age_ms } if instrument_id == "EURUSD" && age_ms == 31000
+        ));
+    }
+}
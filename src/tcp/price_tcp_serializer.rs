@@ -0,0 +1,74 @@
+use my_tcp_sockets::{
+    socket_reader::{ReadBuffer, ReadingTcpContractFail, SocketReader},
+    TcpSerializerFactory, TcpSerializerState, TcpSocketSerializer, TcpWriteBuffer,
+};
+use prices_tcp_contracts::{BidAskTcpMessage, BidAskTcpSerializer, TcpFeedSerializerFactory};
+
+use super::{PriceTcpContract, PRICE_TCP_LINE_END};
+
+// Feed messages go through the standard feed serializer, control lines are written as is
+pub struct PriceTcpSerializer {
+    feed: BidAskTcpSerializer,
+    buffer: ReadBuffer,
+}
+
+impl PriceTcpSerializer {
+    pub fn new(feed: BidAskTcpSerializer) -> Self {
+        Self {
+            feed,
+            buffer: ReadBuffer::new(1024 * 24),
+        }
+    }
+}
+
+#[async_trait::async_trait]
+impl TcpSocketSerializer<PriceTcpContract, ()> for PriceTcpSerializer {
+    fn serialize(&self, out: &mut impl TcpWriteBuffer, contract: &PriceTcpContract, state: &()) {
+        if let PriceTcpContract::Feed(message) = contract {
+            self.feed.serialize(out, message, state);
+            return;
+        }
+
+        if let Some(line) = contract.to_control_line() {
+            out.write_slice(line.as_bytes());
+            out.write_slice(PRICE_TCP_LINE_END);
+        }
+    }
+
+    fn get_ping(&self) -> PriceTcpContract {
+        PriceTcpContract::Feed(BidAskTcpMessage::Ping)
+    }
+
+    async fn deserialize<TSocketReader: Send + Sync + 'static + SocketReader>(
+        &mut self,
+        socket_reader: &mut TSocketReader,
+        _state: &(),
+    ) -> Result<PriceTcpContract, ReadingTcpContractFail> {
+        let line = socket_reader
+            .read_until_end_marker(&mut self.buffer, PRICE_TCP_LINE_END)
+            .await?;
+
+        Ok(PriceTcpContract::parse(
+            String::from_utf8_lossy(line).as_ref(),
+        ))
+    }
+}
+
+impl TcpSerializerState<PriceTcpContract> for () {
+    fn is_tcp_contract_related_to_metadata(&self, _: &PriceTcpContract) -> bool {
+        false
+    }
+
+    fn apply_tcp_contract(&mut self, _: &PriceTcpContract) {}
+}
+
+pub struct PriceTcpSerializerFactory;
+
+#[async_trait::async_trait]
+impl TcpSerializerFactory<PriceTcpContract, PriceTcpSerializer, ()> for PriceTcpSerializerFactory {
+    async fn create_serializer(&self) -> PriceTcpSerializer {
+        PriceTcpSerializer::new(TcpFeedSerializerFactory.create_serializer().await)
+    }
+
+    async fn create_serializer_state(&self) {}
+}
@@ -0,0 +1,42 @@
+use std::sync::Arc;
+
+use service_sdk::my_logger::LogEventCtx;
+
+use crate::{app::PriceSubscriber, PriceTcpSocketConnection};
+
+use super::PriceTcpContract;
+
+const MAX_BATCH_SIZE: usize = 512;
+
+// Drains the subscriber queue into the socket. Ends when the subscriber is closed.
+// Closed by the slow consumer policy - the connection is dropped
+pub fn start_price_tcp_writer(
+    connection: Arc<PriceTcpSocketConnection>,
+    subscriber: Arc<PriceSubscriber>,
+    lp_id: String,
+) {
+    tokio::spawn(async move {
+        while let Some(batch) = subscriber.next_batch(MAX_BATCH_SIZE).await {
+            for message in batch.iter() {
+                connection
+                    .send(&PriceTcpContract::from_outbound(lp_id.as_str(), message))
+                    .await;
+            }
+        }
+
+        if let Some(reason) = subscriber.get_close_reason() {
+            service_sdk::my_logger::LOGGER.write_warning(
+                String::from("SlowConsumer"),
+                format!(
+                    "Disconnecting {} {}. {}",
+                    subscriber.id, subscriber.addr, reason
+                ),
+                LogEventCtx::new()
+                    .add("connectionId", subscriber.id.to_string())
+                    .add("addr", subscriber.addr.as_str()),
+            );
+
+            connection.disconnect().await;
+        }
+    });
+}
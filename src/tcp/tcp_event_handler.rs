@@ -1,10 +1,15 @@
 use std::sync::Arc;
 
-use my_tcp_sockets::{tcp_connection::TcpSocketConnection, SocketEventCallback};
+use my_tcp_sockets::SocketEventCallback;
 use prices_tcp_contracts::*;
 use service_sdk::my_logger::LogEventCtx;
 
-use crate::{app::AppContext, BidAskTcpSocketConnection};
+use crate::{
+    app::{AppContext, PriceSubscriber},
+    PriceTcpSocketConnection,
+};
+
+use super::{start_price_tcp_writer, PriceTcpContract, PriceTcpSerializer};
 
 pub struct PriceTcpServerCallback {
     pub app: Arc<AppContext>,
@@ -17,8 +22,8 @@ impl PriceTcpServerCallback {
 }
 
 #[async_trait::async_trait]
-impl SocketEventCallback<BidAskTcpMessage, BidAskTcpSerializer, ()> for PriceTcpServerCallback {
-    async fn connected(&self, connection: Arc<BidAskTcpSocketConnection>) {
+impl SocketEventCallback<PriceTcpContract, PriceTcpSerializer, ()> for PriceTcpServerCallback {
+    async fn connected(&self, connection: Arc<PriceTcpSocketConnection>) {
         service_sdk::my_logger::LOGGER.write_info(
             String::from("PriceTcpServerCallback"),
             format!(
@@ -27,14 +32,26 @@ impl SocketEventCallback<BidAskTcpMessage, BidAskTcpSerializer, ()> for PriceTcp
             ),
             LogEventCtx::new(),
         );
-        let mut write_access = self.app.broadcast_data.lock().await;
-        write_access.connections.insert(connection.id, connection);
+        let settings = self.app.settings_reader.get_price_clients_settings().await;
+
+        let subscriber = Arc::new(PriceSubscriber::new(
+            connection.id,
+            format!("{:?}", connection.addr),
+            settings.soft_queue_limit,
+            settings.hard_queue_limit,
+        ));
+
+        {
+            let mut write_access = self.app.broadcast_data.lock().await;
+            write_access.add_subscriber(subscriber.clone());
+            // Under the lock so no live tick gets ahead of the snapshot
+            write_access.send_snapshot(connection.id, None);
+        }
+
+        start_price_tcp_writer(connection, subscriber, self.app.lp_id.clone());
     }
 
-    async fn disconnected(
-        &self,
-        connection: Arc<TcpSocketConnection<BidAskTcpMessage, BidAskTcpSerializer, ()>>,
-    ) {
+    async fn disconnected(&self, connection: Arc<PriceTcpSocketConnection>) {
         service_sdk::my_logger::LOGGER.write_info(
             String::from("PriceTcpServerCallback"),
             format!(
@@ -44,17 +61,65 @@ impl SocketEventCallback<BidAskTcpMessage, BidAskTcpSerializer, ()> for PriceTcp
             LogEventCtx::new(),
         );
         let mut write_access = self.app.broadcast_data.lock().await;
-        write_access.connections.remove(&connection.id);
+        write_access.remove_subscriber(connection.id);
     }
 
-    async fn payload(
+    async fn payload(&self, connection: &Arc<PriceTcpSocketConnection>, payload: PriceTcpContract) {
+        match payload {
+            PriceTcpContract::Feed(message) => {
+                if message.is_ping() {
+                    println!("Received ping from {:?}", connection.addr);
+                    connection
+                        .send(&PriceTcpContract::Feed(BidAskTcpMessage::Pong))
+                        .await;
+                }
+            }
+            PriceTcpContract::Subscribe(instruments) => {
+                self.log_subscription(connection, "Subscribe", &instruments);
+                let mut write_access = self.app.broadcast_data.lock().await;
+                write_access.subscribe(connection.id, &instruments);
+            }
+            PriceTcpContract::Unsubscribe(instruments) => {
+                self.log_subscription(connection, "Unsubscribe", &instruments);
+                let mut write_access = self.app.broadcast_data.lock().await;
+                write_access.unsubscribe(connection.id, &instruments);
+            }
+            // Server to client messages
+            PriceTcpContract::SnapshotBegin
+            | PriceTcpContract::SnapshotEnd
+            | PriceTcpContract::Stale { .. }
+            | PriceTcpContract::Active(_) => {}
+            PriceTcpContract::Unknown(line) => {
+                service_sdk::my_logger::LOGGER.write_warning(
+                    String::from("PriceTcpServerCallback"),
+                    format!(
+                        "Unknown message from {} {:?}: {}",
+                        connection.id, connection.addr, line
+                    ),
+                    LogEventCtx::new(),
+                );
+            }
+        }
+    }
+}
+
+impl PriceTcpServerCallback {
+    fn log_subscription(
         &self,
-        connection: &Arc<TcpSocketConnection<BidAskTcpMessage, BidAskTcpSerializer, ()>>,
-        payload: BidAskTcpMessage,
+        connection: &Arc<PriceTcpSocketConnection>,
+        action: &str,
+        instruments: &[String],
     ) {
-        if payload.is_ping() {
-            println!("Received ping from {:?}", connection.addr);
-            connection.send(&BidAskTcpMessage::Pong).await;
-        }
+        service_sdk::my_logger::LOGGER.write_info(
+            String::from("PriceTcpServerCallback"),
+            format!(
+                "{} {} {:?}: {}",
+                action,
+                connection.id,
+                connection.addr,
+                instruments.join(",")
+            ),
+            LogEventCtx::new().add("connectionId", connection.id.to_string()),
+        );
     }
 }
@@ -1,12 +1,13 @@
 use std::{env, net::SocketAddr, sync::Arc};
 
 use my_tcp_sockets::TcpServer;
-use prices_tcp_contracts::TcpFeedSerializerFactory;
 use rust_extensions::AppStates;
 use service_sdk::my_logger::LogEventCtx;
 
 use crate::{tcp::tcp_event_handler::PriceTcpServerCallback, AppContext};
 
+use super::PriceTcpSerializerFactory;
+
 pub struct PriceRouterTcpServer {
     pub tcp_server: TcpServer,
     pub app: Arc<AppContext>,
@@ -17,7 +18,7 @@ impl PriceRouterTcpServer {
     pub async fn start(&self) {
         self.tcp_server
             .start(
-                Arc::new(TcpFeedSerializerFactory),
+                Arc::new(PriceTcpSerializerFactory),
                 Arc::new(PriceTcpServerCallback::new(self.app.clone())),
                 self.app_states.clone(),
                 service_sdk::my_logger::LOGGER.clone(),
@@ -0,0 +1,6 @@
+mod tick_recorder;
+pub use tick_recorder::*;
+mod tick_record;
+pub use tick_record::*;
+mod tick_recorder_writer;
+pub use tick_recorder_writer::*;
@@ -0,0 +1,96 @@
+use rust_extensions::date_time::DateTimeAsMicroseconds;
+
+use crate::app::OutboundTick;
+
+pub const TICK_RECORD_HEADER: &'static str =
+    "source_time_us,receive_time_us,bid,ask,bid_size,ask_size\n";
+
+// One line of a per-day, per-instrument file. Instrument is the file name
+#[derive(Debug, Clone)]
+pub struct TickRecord {
+    pub source_time: DateTimeAsMicroseconds,
+    pub receive_time: DateTimeAsMicroseconds,
+    pub bid: f64,
+    pub ask: f64,
+    pub bid_size: Option<f64>,
+    pub ask_size: Option<f64>,
+}
+
+impl TickRecord {
+    pub fn from_tick(tick: &OutboundTick) -> Self {
+        Self {
+            source_time: tick.date,
+            receive_time: tick.receive_time,
+            bid: tick.bid,
+            ask: tick.ask,
+            bid_size: tick.bid_size,
+            ask_size: tick.ask_size,
+        }
+    }
+
+    // Line format: <source time us>,<receive time us>,<bid>,<ask>,<bid size>,<ask size>.
+    // Sizes are empty if the venue did not send them
+    pub fn to_line(&self) -> String {
+        format!(
+            "{},{},{},{},{},{}\n",
+            self.source_time.unix_microseconds,
+            self.receive_time.unix_microseconds,
+            self.bid,
+            self.ask,
+            format_size(self.bid_size),
+            format_size(self.ask_size)
+        )
+    }
+
+    pub fn parse_line(line: &str) -> Option<Self> {
+        let mut parts = line.trim_end().split(',');
+
+        Some(Self {
+            source_time: DateTimeAsMicroseconds::new(parts.next()?.parse().ok()?),
+            receive_time: DateTimeAsMicroseconds::new(parts.next()?.parse().ok()?),
+            bid: parts.next()?.parse().ok()?,
+            ask: parts.next()?.parse().ok()?,
+            bid_size: parts.next()?.parse().ok(),
+            ask_size: parts.next()?.parse().ok(),
+        })
+    }
+}
+
+fn format_size(size: Option<f64>) -> String {
+    match size {
+        Some(size) => size.to_string(),
+        None => String::new(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rust_extensions::date_time::DateTimeAsMicroseconds;
+
+    use super::TickRecord;
+
+    #[test]
+    fn test_line_round_trip() {
+        let record = TickRecord {
+            source_time: DateTimeAsMicroseconds::new(1_714_053_780_123_456),
+            receive_time: DateTimeAsMicroseconds::new(1_714_053_780_125_000),
+            bid: 1.08345,
+            ask: 1.08355,
+            bid_size: Some(1_000_000.0),
+            ask_size: None,
+        };
+
+        let line = record.to_line();
+        assert_eq!(
+            line,
+            "1714053780123456,1714053780125000,1.08345,1.08355,1000000,\n"
+        );
+
+        let parsed = TickRecord::parse_line(line.as_str()).unwrap();
+        assert_eq!(parsed.source_time.unix_microseconds, 1_714_053_780_123_456);
+        assert_eq!(parsed.receive_time.unix_microseconds, 1_714_053_780_125_000);
+        assert_eq!(parsed.bid, 1.08345);
+        assert_eq!(parsed.bid_size, Some(1_000_000.0));
+        assert_eq!(parsed.ask_size, None);
+    }
+}
@@ -0,0 +1,47 @@
+use std::sync::{
+    atomic::{AtomicU64, Ordering},
+    mpsc::{SyncSender, TrySendError},
+    Arc,
+};
+
+use crate::{app::OutboundTick, settings::TickRecorderSettingsModel};
+
+use super::TickRecorderWriter;
+
+// Ticks waiting for the disk. Over it ticks are dropped and counted, publishing never waits
+const QUEUE_CAPACITY: usize = 100_000;
+
+pub struct TickRecorder {
+    sender: SyncSender<Arc<OutboundTick>>,
+    dropped: Arc<AtomicU64>,
+}
+
+impl TickRecorder {
+    pub fn new(settings: TickRecorderSettingsModel) -> Self {
+        let (sender, receiver) = std::sync::mpsc::sync_channel(QUEUE_CAPACITY);
+        let dropped = Arc::new(AtomicU64::new(0));
+
+        let writer_dropped = dropped.clone();
+        std::thread::Builder::new()
+            .name("tick-recorder".to_string())
+            .spawn(move || {
+                let mut writer = TickRecorderWriter::new(settings, writer_dropped);
+                writer.run(receiver);
+            })
+            .unwrap();
+
+        Self { sender, dropped }
+    }
+
+    pub fn write(&self, tick: Arc<OutboundTick>) {
+        match self.sender.try_send(tick) {
+            Ok(_) => {}
+            // Reported by the writer with the next flush
+            Err(TrySendError::Full(_)) => {
+                self.dropped.fetch_add(1, Ordering::Relaxed);
+            }
+            // Writer thread is gone on shutdown
+            Err(TrySendError::Disconnected(_)) => {}
+        }
+    }
+}
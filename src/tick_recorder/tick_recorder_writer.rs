@@ -0,0 +1,510 @@
+use std::{
+    collections::HashMap,
+    fs::{File, OpenOptions},
+    io::{BufWriter, Write},
+    path::PathBuf,
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        mpsc::{Receiver, RecvTimeoutError},
+        Arc,
+    },
+    time::{Duration, Instant},
+};
+
+use flate2::{write::GzEncoder, Compression};
+use rust_extensions::date_time::DateTimeAsMicroseconds;
+use service_sdk::my_logger::LogEventCtx;
+
+use crate::{app::OutboundTick, settings::TickRecorderSettingsModel};
+
+use super::{TickRecord, TICK_RECORD_HEADER};
+
+const FILE_EXTENSION: &'static str = ".csv.gz";
+const FLUSH_INTERVAL: Duration = Duration::from_secs(1);
+
+type TickFile = GzEncoder<BufWriter<File>>;
+
+// Files are <path>/<YYYYMMDD>/<instrument>.<run>.csv.gz by receive time (UTC).
+// The day only moves forward - a late tick from the previous day goes to the current day file.
+// Run is the writer start time in microseconds. The process is not stopped gracefully, so the
+// last run leaves its files without a gzip trailer. A restart never appends to them - it starts
+// new segments, and segments of a day sort by name in the order they were written
+pub struct TickRecorderWriter {
+    settings: TickRecorderSettingsModel,
+    run: i64,
+    day: Option<String>,
+    files: HashMap<String, TickFile>,
+    // Ticks the publishing path could not queue
+    dropped: Arc<AtomicU64>,
+}
+
+impl TickRecorderWriter {
+    pub fn new(settings: TickRecorderSettingsModel, dropped: Arc<AtomicU64>) -> Self {
+        Self {
+            settings,
+            run: DateTimeAsMicroseconds::now().unix_microseconds,
+            day: None,
+            files: HashMap::new(),
+            dropped,
+        }
+    }
+
+    pub fn run(&mut self, receiver: Receiver<Arc<OutboundTick>>) {
+        let mut last_flush = Instant::now();
+
+        loop {
+            match receiver.recv_timeout(FLUSH_INTERVAL) {
+                Ok(tick) => self.write_tick(&tick),
+                Err(RecvTimeoutError::Timeout) => {}
+                Err(RecvTimeoutError::Disconnected) => {
+                    self.finish_files();
+                    return;
+                }
+            }
+
+            // Busy markets never time out, so the interval is checked after every tick
+            if last_flush.elapsed() >= FLUSH_INTERVAL {
+                self.flush();
+                last_flush = Instant::now();
+            }
+        }
+    }
+
+    fn write_tick(&mut self, tick: &OutboundTick) {
+        let tick_day = tick
+            .receive_time
+            .to_chrono_utc()
+            .format("%Y%m%d")
+            .to_string();
+
+        // YYYYMMDD strings compare as dates
+        let is_new_day = match self.day.as_ref() {
+            Some(day) => tick_day > *day,
+            None => true,
+        };
+
+        if is_new_day {
+            self.finish_files();
+            self.remove_expired_days(tick_day.as_str());
+            self.day = Some(tick_day);
+        }
+
+        if !self.files.contains_key(&tick.instrument_id) {
+            let day = self.day.as_deref().unwrap();
+
+            match self.open_file(day, tick.instrument_id.as_str()) {
+                Some(file) => {
+                    self.files.insert(tick.instrument_id.to_string(), file);
+                }
+                None => return,
+            }
+        }
+
+        let line = TickRecord::from_tick(tick).to_line();
+        let file = self.files.get_mut(&tick.instrument_id).unwrap();
+
+        if let Err(err) = file.write_all(line.as_bytes()) {
+            write_error(format!(
+                "Can not write {} tick. Err: {:?}",
+                tick.instrument_id, err
+            ));
+            // Reopened with the next tick
+            self.files.remove(&tick.instrument_id);
+        }
+    }
+
+    fn open_file(&self, day: &str, instrument_id: &str) -> Option<TickFile> {
+        let mut file_path = PathBuf::from(&self.settings.path);
+        file_path.push(day);
+
+        if let Err(err) = std::fs::create_dir_all(&file_path) {
+            write_error(format!(
+                "Can not create tick directory {:?}. Err: {:?}",
+                file_path, err
+            ));
+            return None;
+        }
+
+        file_path.push(format!(
+            "{}.{}{}",
+            to_file_name(instrument_id),
+            self.run,
+            FILE_EXTENSION
+        ));
+
+        // Append - the file is reopened by the same run after a write error only
+        let file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&file_path);
+
+        let file = match file {
+            Ok(file) => file,
+            Err(err) => {
+                write_error(format!(
+                    "Can not open tick file {:?}. Err: {:?}",
+                    file_path, err
+                ));
+                return None;
+            }
+        };
+
+        let is_new = file.metadata().map(|meta| meta.len() == 0).unwrap_or(false);
+        let mut file = GzEncoder::new(BufWriter::new(file), Compression::default());
+
+        if is_new {
+            if let Err(err) = file.write_all(TICK_RECORD_HEADER.as_bytes()) {
+                write_error(format!(
+                    "Can not write header to {:?}. Err: {:?}",
+                    file_path, err
+                ));
+                return None;
+            }
+        }
+
+        Some(file)
+    }
+
+    fn flush(&mut self) {
+        let dropped = self.dropped.swap(0, Ordering::Relaxed);
+
+        if dropped > 0 {
+            write_error(format!(
+                "Tick recorder queue is full. {} ticks are not recorded",
+                dropped
+            ));
+        }
+
+        for (instrument_id, file) in self.files.iter_mut() {
+            if let Err(err) = file.flush() {
+                write_error(format!(
+                    "Can not flush {} ticks. Err: {:?}",
+                    instrument_id, err
+                ));
+            }
+        }
+    }
+
+    // Writes gzip trailers. Files of the day are complete after this
+    fn finish_files(&mut self) {
+        for (instrument_id, file) in self.files.drain() {
+            let result = file.finish().and_then(|mut file| file.flush());
+
+            if let Err(err) = result {
+                write_error(format!(
+                    "Can not finish {} tick file. Err: {:?}",
+                    instrument_id, err
+                ));
+            }
+        }
+    }
+
+    // Current day counts towards retention_days and is never removed
+    fn remove_expired_days(&self, current_day: &str) {
+        let dir = match std::fs::read_dir(&self.settings.path) {
+            Ok(dir) => dir,
+            // Nothing recorded yet
+            Err(_) => return,
+        };
+
+        let mut days: Vec<PathBuf> = dir
+            .filter_map(|entry| entry.ok())
+            .map(|entry| entry.path())
+            .filter(|path| is_day_dir(path))
+            .filter(|path| path.file_name().and_then(|name| name.to_str()) != Some(current_day))
+            .collect();
+
+        // Older days next to the current one. retention_days 0 keeps the current day only
+        let keep = self.settings.retention_days.saturating_sub(1);
+
+        if days.len() <= keep {
+            return;
+        }
+
+        // YYYYMMDD names - the oldest days go first
+        days.sort();
+
+        let to_remove = days.len() - keep;
+
+        for path in days.iter().take(to_remove) {
+            if let Err(err) = std::fs::remove_dir_all(path) {
+                write_error(format!(
+                    "Can not remove tick directory {:?}. Err: {:?}",
+                    path, err
+                ));
+            }
+        }
+    }
+}
+
+fn is_day_dir(path: &PathBuf) -> bool {
+    if !path.is_dir() {
+        return false;
+    }
+
+    match path.file_name().and_then(|name| name.to_str()) {
+        Some(name) => name.len() == 8 && name.chars().all(|c| c.is_ascii_digit()),
+        None => false,
+    }
+}
+
+// Instrument ids are not guaranteed to be valid file names. Other bytes are written as _XX hex,
+// '_' included, so different ids never share a file: EUR/USD -> EUR_2FUSD, EUR_USD -> EUR_5FUSD
+fn to_file_name(instrument_id: &str) -> String {
+    let mut result = String::with_capacity(instrument_id.len());
+
+    for b in instrument_id.bytes() {
+        if b.is_ascii_alphanumeric() || b == b'-' || b == b'.' {
+            result.push(b as char);
+        } else {
+            result.push_str(format!("_{:02X}", b).as_str());
+        }
+    }
+
+    result
+}
+
+fn write_error(message: String) {
+    service_sdk::my_logger::LOGGER.write_error(
+        String::from("TickRecorderWriter"),
+        message,
+        LogEventCtx::new(),
+    );
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        io::Read,
+        path::PathBuf,
+        sync::{atomic::AtomicU64, Arc},
+    };
+
+    use flate2::read::MultiGzDecoder;
+    use rust_extensions::date_time::DateTimeAsMicroseconds;
+
+    use super::{to_file_name, TickRecorderWriter};
+    use crate::{
+        app::OutboundTick, settings::TickRecorderSettingsModel, tick_recorder::TICK_RECORD_HEADER,
+        your_bourse::QuoteTimeSource,
+    };
+
+    fn test_dir() -> PathBuf {
+        let mut path = std::env::temp_dir();
+        path.push(format!("yb-bridge-ticks-{}", uuid::Uuid::new_v4()));
+        path
+    }
+
+    fn writer(path: &PathBuf, retention_days: usize) -> TickRecorderWriter {
+        TickRecorderWriter::new(
+            TickRecorderSettingsModel {
+                path: path.to_str().unwrap().to_string(),
+                retention_days,
+            },
+            Arc::new(AtomicU64::new(0)),
+        )
+    }
+
+    fn tick(receive_time: DateTimeAsMicroseconds, bid: f64) -> OutboundTick {
+        OutboundTick {
+            instrument_id: "EURUSD".to_string(),
+            bid,
+            ask: bid + 0.0001,
+            date: receive_time,
+            date_source: QuoteTimeSource::ReceiveTime,
+            receive_time,
+            bid_size: None,
+            ask_size: None,
+        }
+    }
+
+    fn list_dir(path: &PathBuf) -> Vec<String> {
+        let mut result: Vec<String> = std::fs::read_dir(path)
+            .unwrap()
+            .map(|entry| entry.unwrap().file_name().to_str().unwrap().to_string())
+            .collect();
+        result.sort();
+        result
+    }
+
+    // Segment file names of the instrument in the order they were written
+    fn list_segments(path: &PathBuf, day: &str) -> Vec<PathBuf> {
+        let mut day_path = path.clone();
+        day_path.push(day);
+
+        list_dir(&day_path)
+            .into_iter()
+            .filter(|name| name.starts_with("EURUSD."))
+            .map(|name| {
+                let mut file_path = day_path.clone();
+                file_path.push(name);
+                file_path
+            })
+            .collect()
+    }
+
+    fn read_segment(file_path: &PathBuf) -> String {
+        let mut result = String::new();
+        MultiGzDecoder::new(std::fs::File::open(file_path).unwrap())
+            .read_to_string(&mut result)
+            .unwrap();
+        result
+    }
+
+    fn read_file(path: &PathBuf, day: &str) -> String {
+        let segments = list_segments(path, day);
+        assert_eq!(segments.len(), 1);
+        read_segment(&segments[0])
+    }
+
+    #[test]
+    fn test_day_rollover_finishes_previous_day() {
+        let path = test_dir();
+        let mut writer = writer(&path, 10);
+
+        writer.write_tick(&tick(
+            DateTimeAsMicroseconds::create(2024, 4, 25, 23, 59, 59, 0),
+            1.1,
+        ));
+        writer.write_tick(&tick(
+            DateTimeAsMicroseconds::create(2024, 4, 26, 0, 0, 1, 0),
+            1.2,
+        ));
+
+        // Previous day is complete before the writer stops
+        let content = read_file(&path, "20240425");
+        assert!(content.starts_with(TICK_RECORD_HEADER));
+        assert_eq!(content.lines().count(), 2);
+
+        writer.finish_files();
+
+        let content = read_file(&path, "20240426");
+        assert_eq!(content.lines().count(), 2);
+        assert!(content.contains(",1.2,"));
+
+        std::fs::remove_dir_all(path).unwrap();
+    }
+
+    #[test]
+    fn test_restart_does_not_append_to_unfinished_file() {
+        let path = test_dir();
+        let now = DateTimeAsMicroseconds::create(2024, 4, 25, 10, 0, 0, 0);
+
+        let mut first = writer(&path, 10);
+        first.write_tick(&tick(now, 1.1));
+        first.flush();
+        // Process is killed. Neither finish_files nor drop runs, the gzip trailer is missing
+        std::mem::forget(first);
+
+        let mut second = writer(&path, 10);
+        second.write_tick(&tick(now, 1.2));
+        second.flush();
+        drop(second);
+
+        let segments = list_segments(&path, "20240425");
+        assert_eq!(segments.len(), 2);
+
+        // Unfinished segment is left as it is
+        assert!(std::fs::metadata(&segments[0]).unwrap().len() > 0);
+
+        // The new run is readable as a whole
+        let content = read_segment(&segments[1]);
+        let lines: Vec<&str> = content.lines().collect();
+        assert_eq!(lines.len(), 2);
+        assert_eq!(format!("{}\n", lines[0]), TICK_RECORD_HEADER);
+        assert!(lines[1].contains(",1.2,"));
+
+        std::fs::remove_dir_all(path).unwrap();
+    }
+
+    #[test]
+    fn test_retention_keeps_latest_days() {
+        let path = test_dir();
+
+        for day in ["20240421", "20240422", "20240423", "20240424"] {
+            let mut day_path = path.clone();
+            day_path.push(day);
+            std::fs::create_dir_all(day_path).unwrap();
+        }
+
+        let mut writer = writer(&path, 2);
+        writer.write_tick(&tick(
+            DateTimeAsMicroseconds::create(2024, 4, 25, 10, 0, 0, 0),
+            1.1,
+        ));
+        writer.finish_files();
+
+        assert_eq!(list_dir(&path), vec!["20240424", "20240425"]);
+
+        std::fs::remove_dir_all(path).unwrap();
+    }
+
+    #[test]
+    fn test_late_tick_does_not_move_day_back() {
+        let path = test_dir();
+        let mut writer = writer(&path, 1);
+
+        writer.write_tick(&tick(
+            DateTimeAsMicroseconds::create(2024, 4, 26, 0, 0, 1, 0),
+            1.1,
+        ));
+        writer.write_tick(&tick(
+            DateTimeAsMicroseconds::create(2024, 4, 25, 23, 59, 59, 0),
+            1.2,
+        ));
+        writer.finish_files();
+
+        // Previous day is not reopened, current day is not removed by retention
+        assert_eq!(list_dir(&path), vec!["20240426"]);
+
+        let content = read_file(&path, "20240426");
+        assert_eq!(content.lines().count(), 3);
+        assert!(content.contains(",1.2,"));
+
+        std::fs::remove_dir_all(path).unwrap();
+    }
+
+    #[test]
+    fn test_retention_of_one_day_keeps_current_day() {
+        let path = test_dir();
+        let mut writer = writer(&path, 1);
+
+        writer.write_tick(&tick(
+            DateTimeAsMicroseconds::create(2024, 4, 25, 10, 0, 0, 0),
+            1.1,
+        ));
+        writer.write_tick(&tick(
+            DateTimeAsMicroseconds::create(2024, 4, 26, 10, 0, 0, 0),
+            1.2,
+        ));
+        writer.finish_files();
+
+        assert_eq!(list_dir(&path), vec!["20240426"]);
+        assert!(read_file(&path, "20240426").contains(",1.2,"));
+
+        std::fs::remove_dir_all(path).unwrap();
+    }
+
+    #[test]
+    fn test_instruments_do_not_share_file() {
+        let path = test_dir();
+        let mut writer = writer(&path, 10);
+        let now = DateTimeAsMicroseconds::create(2024, 4, 25, 10, 0, 0, 0);
+
+        for instrument_id in ["EUR/USD", "EUR_USD"] {
+            let mut record = tick(now, 1.1);
+            record.instrument_id = instrument_id.to_string();
+            writer.write_tick(&record);
+        }
+        writer.finish_files();
+
+        assert_ne!(to_file_name("EUR/USD"), to_file_name("EUR_USD"));
+        assert_eq!(to_file_name("EURUSD"), "EURUSD");
+
+        let mut day_path = path.clone();
+        day_path.push("20240425");
+        assert_eq!(list_dir(&day_path).len(), 2);
+
+        std::fs::remove_dir_all(path).unwrap();
+    }
+}
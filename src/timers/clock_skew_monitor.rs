@@ -0,0 +1,66 @@
+use std::sync::{
+    atomic::{AtomicBool, Ordering},
+    Arc,
+};
+
+use rust_extensions::MyTimerTick;
+use service_sdk::my_logger::LogEventCtx;
+
+use crate::app::AppContext;
+
+pub struct ClockSkewMonitorTimer {
+    app: Arc<AppContext>,
+    // Skew was over the threshold on the last tick. Logged on change only
+    over_threshold: AtomicBool,
+}
+
+impl ClockSkewMonitorTimer {
+    pub fn new(app: Arc<AppContext>) -> Self {
+        Self {
+            app,
+            over_threshold: AtomicBool::new(false),
+        }
+    }
+}
+
+#[async_trait::async_trait]
+impl MyTimerTick for ClockSkewMonitorTimer {
+    async fn tick(&self) {
+        let skew_micros = match self.app.clock_skew.get_estimate_micros().await {
+            Some(skew_micros) => skew_micros,
+            None => return,
+        };
+
+        let settings = self.app.settings_reader.get_clock_skew_settings().await;
+
+        let over_threshold = skew_micros.abs() >= settings.warn_threshold_ms * 1000;
+        let was_over_threshold = self.over_threshold.swap(over_threshold, Ordering::Relaxed);
+
+        if over_threshold == was_over_threshold {
+            return;
+        }
+
+        if over_threshold {
+            service_sdk::my_logger::LOGGER.write_warning(
+                String::from("ClockSkewMonitor"),
+                format!(
+                    "Clock skew between YourBourse and bridge host is {} ms (threshold {} ms). Positive - our clock is ahead. Quote time correction enabled: {}",
+                    skew_micros / 1000,
+                    settings.warn_threshold_ms,
+                    settings.correct_quote_time
+                ),
+                LogEventCtx::new(),
+            );
+        } else {
+            service_sdk::my_logger::LOGGER.write_info(
+                String::from("ClockSkewMonitor"),
+                format!(
+                    "Clock skew between YourBourse and bridge host is back to {} ms (threshold {} ms)",
+                    skew_micros / 1000,
+                    settings.warn_threshold_ms
+                ),
+                LogEventCtx::new(),
+            );
+        }
+    }
+}
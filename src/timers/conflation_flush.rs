@@ -0,0 +1,23 @@
+use std::sync::Arc;
+
+use rust_extensions::{date_time::DateTimeAsMicroseconds, MyTimerTick};
+
+use crate::app::AppContext;
+
+pub struct ConflationFlushTimer {
+    app: Arc<AppContext>,
+}
+
+impl ConflationFlushTimer {
+    pub fn new(app: Arc<AppContext>) -> Self {
+        Self { app }
+    }
+}
+
+#[async_trait::async_trait]
+impl MyTimerTick for ConflationFlushTimer {
+    async fn tick(&self) {
+        let mut broadcast_data = self.app.broadcast_data.lock().await;
+        broadcast_data.flush_conflated(DateTimeAsMicroseconds::now());
+    }
+}
@@ -0,0 +1,51 @@
+use std::sync::Arc;
+
+use rust_extensions::MyTimerTick;
+use service_sdk::my_logger::LogEventCtx;
+
+use crate::app::AppContext;
+
+pub struct LatencyReportTimer {
+    app: Arc<AppContext>,
+}
+
+impl LatencyReportTimer {
+    pub fn new(app: Arc<AppContext>) -> Self {
+        Self { app }
+    }
+}
+
+#[async_trait::async_trait]
+impl MyTimerTick for LatencyReportTimer {
+    async fn tick(&self) {
+        let summary = self.app.latency_stats.get_summary_and_reset().await;
+
+        if summary.is_empty() {
+            return;
+        }
+
+        let mut message = String::from("Latency for the last period:");
+
+        for itm in summary.iter() {
+            message.push_str(
+                format!(
+                    "\n{}: venue->receive [{}], entry->receive [{}], receive->publish [{}], venue->publish [{}]",
+                    itm.external_symbol,
+                    itm.sending_to_receive.to_log_string(),
+                    itm.entry_to_receive.to_log_string(),
+                    itm.receive_to_publish.to_log_string(),
+                    itm.sending_to_publish.to_log_string(),
+                )
+                .as_str(),
+            );
+        }
+
+        service_sdk::my_logger::LOGGER.write_info(
+            String::from("LatencyReport"),
+            message,
+            LogEventCtx::new(),
+        );
+
+        *self.app.last_latency_report.lock().await = summary;
+    }
+}
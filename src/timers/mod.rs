@@ -1,2 +1,18 @@
 mod upload_src_prices;
 pub use upload_src_prices::*;
+mod subscription_watchdog;
+pub use subscription_watchdog::*;
+mod session_watchdog;
+pub use session_watchdog::*;
+mod latency_report;
+pub use latency_report::*;
+mod clock_skew_monitor;
+pub use clock_skew_monitor::*;
+mod conflation_flush;
+pub use conflation_flush::*;
+mod slow_consumer;
+pub use slow_consumer::*;
+mod stale_prices;
+pub use stale_prices::*;
+mod upload_candles;
+pub use upload_candles::*;
@@ -0,0 +1,85 @@
+use std::{sync::Arc, time::Duration};
+
+use rust_extensions::{date_time::DateTimeAsMicroseconds, MyTimerTick};
+use service_sdk::my_logger::LogEventCtx;
+
+use crate::{app::AppContext, your_bourse::YbFixContract};
+
+pub struct SessionWatchdogTimer {
+    app: Arc<AppContext>,
+}
+
+impl SessionWatchdogTimer {
+    pub fn new(app: Arc<AppContext>) -> Self {
+        Self { app }
+    }
+}
+
+#[async_trait::async_trait]
+impl MyTimerTick for SessionWatchdogTimer {
+    async fn tick(&self) {
+        let settings = match self
+            .app
+            .settings_reader
+            .get_session_watchdog_settings()
+            .await
+        {
+            Some(settings) => settings,
+            None => return,
+        };
+
+        let now = DateTimeAsMicroseconds::now();
+
+        if !settings.is_trading_time(now) {
+            return;
+        }
+
+        let connection = self.app.fix_connection.lock().await.clone();
+
+        let connection = match connection {
+            Some(connection) => connection,
+            None => return,
+        };
+
+        let last_activity = match self.app.quote_activity.get_last_session_activity().await {
+            Some(last_activity) => last_activity,
+            None => return,
+        };
+
+        // Quotes stop when the market closes. Silence only counts once it is open again
+        let silence_started = match settings.get_silence_started(now, last_activity) {
+            Some(silence_started) => silence_started,
+            None => return,
+        };
+
+        let silence_sec = (now.unix_microseconds - silence_started.unix_microseconds) / 1_000_000;
+
+        if silence_sec < settings.silence_threshold_sec as i64 {
+            return;
+        }
+
+        let reason = format!(
+            "No market data for any symbol during {} sec while in trading hours",
+            silence_sec
+        );
+
+        service_sdk::my_logger::LOGGER.write_error(
+            String::from("SessionWatchdog"),
+            format!("Forcing FIX reconnect. {}", reason),
+            LogEventCtx::new().add("connectionId", connection.id.to_string()),
+        );
+
+        self.app.forced_reconnects.add(reason).await;
+
+        // Clear activity so we do not fire again before the new session subscribes
+        self.app.quote_activity.clear().await;
+
+        // Off the timer thread. Other timers of the group would wait for the sleep
+        tokio::spawn(async move {
+            connection.send(&YbFixContract::Logout).await;
+            // Give Logout a chance to leave the socket before we drop it
+            tokio::time::sleep(Duration::from_millis(500)).await;
+            connection.disconnect().await;
+        });
+    }
+}
@@ -0,0 +1,154 @@
+use std::{collections::HashSet, sync::Arc};
+
+use rust_extensions::{date_time::DateTimeAsMicroseconds, MyTimerTick};
+use service_sdk::my_logger::LogEventCtx;
+use tokio::sync::Mutex;
+
+use crate::app::{AppContext, PriceSubscriberStatus};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum BehindChange {
+    FellBehind,
+    StillBehind,
+    CaughtUp,
+    None,
+}
+
+// Same threshold as conflation in PriceSubscriber::push. The queue of a behind consumer
+// is pinned at the soft limit, it does not grow past it
+fn get_behind_change(
+    was_behind: bool,
+    status: &PriceSubscriberStatus,
+    soft_queue_limit: usize,
+) -> BehindChange {
+    let is_behind = status.queue_len >= soft_queue_limit;
+
+    match (was_behind, is_behind) {
+        (false, true) => BehindChange::FellBehind,
+        (true, true) => BehindChange::StillBehind,
+        (true, false) => BehindChange::CaughtUp,
+        (false, false) => BehindChange::None,
+    }
+}
+
+pub struct SlowConsumerTimer {
+    app: Arc<AppContext>,
+    // Connections over the soft limit on the last tick. Logged on change only
+    behind: Mutex<HashSet<i32>>,
+}
+
+impl SlowConsumerTimer {
+    pub fn new(app: Arc<AppContext>) -> Self {
+        Self {
+            app,
+            behind: Mutex::new(HashSet::new()),
+        }
+    }
+}
+
+#[async_trait::async_trait]
+impl MyTimerTick for SlowConsumerTimer {
+    async fn tick(&self) {
+        let settings = self.app.settings_reader.get_price_clients_settings().await;
+        let subscribers = self.app.broadcast_data.lock().await.get_subscribers();
+        let now = DateTimeAsMicroseconds::now();
+
+        let mut behind = self.behind.lock().await;
+        let mut still_behind = HashSet::new();
+
+        for subscriber in subscribers {
+            if subscriber.close_if_lagging(now, settings.max_lag_ms) {
+                continue;
+            }
+
+            let status = subscriber.get_status(now);
+            let was_behind = behind.contains(&status.id);
+
+            let ctx = LogEventCtx::new()
+                .add("connectionId", status.id.to_string())
+                .add("addr", status.addr.as_str());
+
+            match get_behind_change(was_behind, &status, settings.soft_queue_limit) {
+                BehindChange::FellBehind => {
+                    still_behind.insert(status.id);
+
+                    service_sdk::my_logger::LOGGER.write_warning(
+                        String::from("SlowConsumer"),
+                        format!(
+                            "Connection {} {} is behind. Queue: {}, lag: {} ms, conflated: {}",
+                            status.id,
+                            status.addr,
+                            status.queue_len,
+                            status.lag_ms,
+                            status.conflated
+                        ),
+                        ctx,
+                    );
+                }
+                BehindChange::StillBehind => {
+                    still_behind.insert(status.id);
+                }
+                BehindChange::CaughtUp => {
+                    service_sdk::my_logger::LOGGER.write_info(
+                        String::from("SlowConsumer"),
+                        format!(
+                            "Connection {} {} caught up. Queue: {}, conflated: {}",
+                            status.id, status.addr, status.queue_len, status.conflated
+                        ),
+                        ctx,
+                    );
+                }
+                BehindChange::None => {}
+            }
+        }
+
+        // Disconnected consumers drop out here
+        *behind = still_behind;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rust_extensions::date_time::DateTimeAsMicroseconds;
+
+    use super::{get_behind_change, BehindChange};
+    use crate::app::{test_tick as tick, PriceSubscriber};
+
+    #[test]
+    fn test_reports_subscriber_pinned_at_soft_limit() {
+        let subscriber = PriceSubscriber::new(1, "test".to_string(), 2, 10);
+
+        for i in 0..10 {
+            subscriber.push(tick("EURUSD", 1.1 + i as f64 * 0.0001));
+            subscriber.push(tick("GBPUSD", 1.2 + i as f64 * 0.0001));
+        }
+
+        let status = subscriber.get_status(DateTimeAsMicroseconds::now());
+        assert_eq!(status.queue_len, 2);
+        assert!(status.conflated > 0);
+
+        assert_eq!(
+            get_behind_change(false, &status, 2),
+            BehindChange::FellBehind
+        );
+        assert_eq!(
+            get_behind_change(true, &status, 2),
+            BehindChange::StillBehind
+        );
+    }
+
+    #[tokio::test]
+    async fn test_reports_caught_up_under_soft_limit() {
+        let subscriber = PriceSubscriber::new(1, "test".to_string(), 2, 10);
+
+        subscriber.push(tick("EURUSD", 1.1));
+        subscriber.push(tick("GBPUSD", 1.2));
+        subscriber.next_batch(1).await.unwrap();
+
+        let status = subscriber.get_status(DateTimeAsMicroseconds::now());
+        assert_eq!(status.queue_len, 1);
+
+        assert_eq!(get_behind_change(true, &status, 2), BehindChange::CaughtUp);
+        assert_eq!(get_behind_change(false, &status, 2), BehindChange::None);
+    }
+}
@@ -0,0 +1,54 @@
+use std::sync::Arc;
+
+use rust_extensions::{date_time::DateTimeAsMicroseconds, MyTimerTick};
+use service_sdk::my_logger::LogEventCtx;
+
+use crate::app::AppContext;
+
+pub struct StalePricesTimer {
+    app: Arc<AppContext>,
+}
+
+impl StalePricesTimer {
+    pub fn new(app: Arc<AppContext>) -> Self {
+        Self { app }
+    }
+}
+
+#[async_trait::async_trait]
+impl MyTimerTick for StalePricesTimer {
+    async fn tick(&self) {
+        let settings = self.app.settings_reader.get_stale_prices_settings().await;
+        let now = DateTimeAsMicroseconds::now();
+
+        // Prices are stored under the same lock. A tick can not slip in between
+        let mut broadcast_data = self.app.broadcast_data.lock().await;
+
+        let stale = self
+            .app
+            .last_prices
+            .mark_stale(now, |instrument_id| {
+                settings.get_stale_after_sec(instrument_id)
+            })
+            .await;
+
+        for last_price in stale {
+            broadcast_data.set_stale(
+                last_price.instrument_id.as_str(),
+                last_price.receive_time,
+                now,
+            );
+
+            service_sdk::my_logger::LOGGER.write_warning(
+                String::from("StalePrices"),
+                format!(
+                    "{} has no updates for {} ms. Last update: {}",
+                    last_price.instrument_id,
+                    last_price.get_age_ms(now),
+                    last_price.receive_time.to_rfc3339()
+                ),
+                LogEventCtx::new().add("instrumentId", last_price.instrument_id.as_str()),
+            );
+        }
+    }
+}
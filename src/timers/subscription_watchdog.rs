@@ -0,0 +1,178 @@
+use std::sync::Arc;
+
+use rust_extensions::{date_time::DateTimeAsMicroseconds, MyTimerTick};
+use service_sdk::my_logger::LogEventCtx;
+
+use crate::{
+    app::{AppContext, SilentSymbol},
+    settings::SubscriptionWatchdogSettingsModel,
+    your_bourse::YbFixContract,
+};
+
+pub struct SubscriptionWatchdogTimer {
+    app: Arc<AppContext>,
+}
+
+impl SubscriptionWatchdogTimer {
+    pub fn new(app: Arc<AppContext>) -> Self {
+        Self { app }
+    }
+}
+
+impl SubscriptionWatchdogTimer {
+    // Symbols to resubscribe, counted as resubscribed. Empty while the watchdog is not configured
+    // or outside trading hours
+    async fn take_silent_symbols(
+        &self,
+        now: DateTimeAsMicroseconds,
+    ) -> Option<(SubscriptionWatchdogSettingsModel, Vec<SilentSymbol>)> {
+        let settings = self
+            .app
+            .settings_reader
+            .get_subscription_watchdog_settings()
+            .await?;
+
+        // Silence outside trading hours is expected
+        if !settings.is_trading_time(now) {
+            return None;
+        }
+
+        let silent_symbols = self
+            .app
+            .quote_activity
+            .resubscribe_silent(
+                now,
+                |external_symbol| settings.get_silence_threshold_sec(external_symbol),
+                settings.get_max_resubscribe_interval_sec(),
+            )
+            .await;
+
+        Some((settings, silent_symbols))
+    }
+}
+
+#[async_trait::async_trait]
+impl MyTimerTick for SubscriptionWatchdogTimer {
+    async fn tick(&self) {
+        let connection = self.app.fix_connection.lock().await.clone();
+
+        let connection = match connection {
+            Some(connection) => connection,
+            None => return,
+        };
+
+        let now = DateTimeAsMicroseconds::now();
+
+        let (settings, silent_symbols) = match self.take_silent_symbols(now).await {
+            Some(result) => result,
+            None => return,
+        };
+
+        for silent_symbol in silent_symbols {
+            // The venue may still hold the old request. Drop it before asking again
+            connection
+                .send(&YbFixContract::UnsubscribeFromInstrument(
+                    silent_symbol.external_symbol.clone(),
+                ))
+                .await;
+
+            connection
+                .send(&YbFixContract::SubscribeToInstrument(
+                    silent_symbol.external_symbol.clone(),
+                ))
+                .await;
+
+            let message = format!(
+                "No quotes for {} during {} sec. Resubscribe attempt #{}",
+                silent_symbol.external_symbol,
+                silent_symbol.silence_sec,
+                silent_symbol.resubscribe_attempts
+            );
+
+            let ctx = LogEventCtx::new()
+                .add("externalSymbol", silent_symbol.external_symbol.as_str())
+                .add(
+                    "resubscribeAttempts",
+                    silent_symbol.resubscribe_attempts.to_string(),
+                );
+
+            if silent_symbol.resubscribe_attempts >= settings.alert_after_attempts {
+                service_sdk::my_logger::LOGGER.write_error(
+                    String::from("SubscriptionWatchdog"),
+                    message,
+                    ctx,
+                );
+            } else {
+                service_sdk::my_logger::LOGGER.write_warning(
+                    String::from("SubscriptionWatchdog"),
+                    message,
+                    ctx,
+                );
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{collections::HashMap, sync::Arc};
+
+    use rust_extensions::date_time::DateTimeAsMicroseconds;
+
+    use super::SubscriptionWatchdogTimer;
+    use crate::{
+        app::{AppContext, StaticPriceFeedSource},
+        settings::{SettingsModel, SettingsReader, SubscriptionWatchdogSettingsModel},
+    };
+
+    async fn create_timer(
+        subscription_watchdog: Option<SubscriptionWatchdogSettingsModel>,
+    ) -> SubscriptionWatchdogTimer {
+        let settings_reader = SettingsReader::from_model(SettingsModel {
+            liquidity_provider_id: "TEST".to_string(),
+            subscription_watchdog,
+            ..Default::default()
+        });
+
+        let feed_source = StaticPriceFeedSource {
+            instrument_map: HashMap::new(),
+            yb_settings: None,
+        };
+
+        let app =
+            AppContext::with_feed_source(Arc::new(settings_reader), Arc::new(feed_source)).await;
+        app.quote_activity.subscribed("EUR/USD").await;
+
+        SubscriptionWatchdogTimer::new(Arc::new(app))
+    }
+
+    fn in_one_hour() -> DateTimeAsMicroseconds {
+        DateTimeAsMicroseconds::new(DateTimeAsMicroseconds::now().unix_microseconds + 3_600_000_000)
+    }
+
+    #[tokio::test]
+    async fn test_silent_symbol_is_not_resubscribed_without_settings() {
+        let timer = create_timer(None).await;
+
+        assert!(timer.take_silent_symbols(in_one_hour()).await.is_none());
+
+        let activity = timer.app.quote_activity.get_all().await;
+        assert_eq!(activity["EUR/USD"].resubscribe_attempts, 0);
+    }
+
+    #[tokio::test]
+    async fn test_silent_symbol_is_resubscribed_when_configured() {
+        let timer = create_timer(Some(SubscriptionWatchdogSettingsModel {
+            silence_threshold_sec: 60,
+            instruments_silence_threshold_sec: None,
+            alert_after_attempts: 3,
+            max_resubscribe_interval_sec: None,
+            trading_hours: None,
+        }))
+        .await;
+
+        let (_, silent_symbols) = timer.take_silent_symbols(in_one_hour()).await.unwrap();
+        assert_eq!(silent_symbols.len(), 1);
+        assert_eq!(silent_symbols[0].external_symbol, "EUR/USD");
+    }
+}
@@ -0,0 +1,53 @@
+use std::sync::Arc;
+
+use rust_extensions::{date_time::DateTimeAsMicroseconds, MyTimerTick};
+use service_sdk::my_logger::LogEventCtx;
+
+use crate::{app::AppContext, nosql::BidAskCandleNoSqlEntity};
+
+pub struct UploadCandlesTimer {
+    app: Arc<AppContext>,
+}
+
+impl UploadCandlesTimer {
+    pub fn new(app: Arc<AppContext>) -> Self {
+        Self { app }
+    }
+}
+
+#[async_trait::async_trait]
+impl MyTimerTick for UploadCandlesTimer {
+    async fn tick(&self) {
+        let candles = self
+            .app
+            .candles
+            .take_updates(DateTimeAsMicroseconds::now())
+            .await;
+
+        if candles.is_empty() {
+            return;
+        }
+
+        let to_upload: Vec<_> = candles
+            .iter()
+            .map(|candle| BidAskCandleNoSqlEntity::from_candle(self.app.lp_id.as_str(), candle))
+            .collect();
+
+        let result = self
+            .app
+            .bid_ask_candles
+            .bulk_insert_or_replace(&to_upload)
+            .await;
+
+        if let Err(err) = result {
+            service_sdk::my_logger::LOGGER.write_error(
+                String::from("UploadCandlesTimer"),
+                format!("Can not upload {} candles. Err: {:?}", candles.len(), err),
+                LogEventCtx::new(),
+            );
+
+            // Candles go with the next tick
+            self.app.candles.restore(candles).await;
+        }
+    }
+}
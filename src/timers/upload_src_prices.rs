@@ -1,8 +1,13 @@
 use std::sync::Arc;
 
-use rust_extensions::MyTimerTick;
+use my_nosql_contracts::price_src::BidAskPriceSrc;
+use rust_extensions::{date_time::DateTimeAsMicroseconds, MyTimerTick};
+use service_sdk::my_logger::LogEventCtx;
 
-use crate::app::AppContext;
+use crate::{
+    app::{AppContext, InstrumentLastPrice},
+    nosql::BidAskRawPriceSrcNoSqlEntity,
+};
 
 pub struct UploadSrcPricesTimer {
     app: Arc<AppContext>,
@@ -17,16 +22,148 @@ impl UploadSrcPricesTimer {
 #[async_trait::async_trait]
 impl MyTimerTick for UploadSrcPricesTimer {
     async fn tick(&self) {
-        let prices_to_upload = self.app.prices_cache.get_snapshot().await;
+        let dirty = self.app.last_prices.take_dirty().await;
 
-        if prices_to_upload.len() == 0 {
+        if dirty.is_empty() {
             return;
         }
 
-        self.app
+        let mut prices_to_upload = Vec::with_capacity(dirty.len());
+        let mut raw_prices_to_upload = Vec::with_capacity(dirty.len());
+        let now = DateTimeAsMicroseconds::now();
+
+        for price in &dirty {
+            raw_prices_to_upload.push(to_raw_price_src(self.app.lp_id.as_str(), price, now));
+            prices_to_upload.push(to_price_src(self.app.lp_id.as_str(), price));
+        }
+
+        let result = self
+            .app
             .bid_ask_price_src
             .bulk_insert_or_replace(&prices_to_upload)
-            .await
-            .unwrap();
+            .await;
+
+        if let Err(err) = result {
+            self.upload_failed("BidAskPriceSrc", format!("{:?}", err), &dirty)
+                .await;
+            return;
+        }
+
+        let result = self
+            .app
+            .raw_bid_ask_price_src
+            .bulk_insert_or_replace(&raw_prices_to_upload)
+            .await;
+
+        if let Err(err) = result {
+            self.upload_failed("BidAskRawPriceSrc", format!("{:?}", err), &dirty)
+                .await;
+        }
+    }
+}
+
+fn to_raw_price_src(
+    lp_id: &str,
+    price: &InstrumentLastPrice,
+    now: DateTimeAsMicroseconds,
+) -> BidAskRawPriceSrcNoSqlEntity {
+    BidAskRawPriceSrcNoSqlEntity {
+        partition_key: lp_id.to_string(),
+        row_key: price.instrument_id.clone(),
+        time_stamp: "".to_string(),
+        src_id: price.src_id.clone(),
+        raw_bid: price.raw_bid,
+        raw_ask: price.raw_ask,
+        bid: price.bid,
+        ask: price.ask,
+        dt: price.source_time.to_rfc3339(),
+        is_stale: price.stale_since.is_some(),
+        stale_age_sec: if price.stale_since.is_some() {
+            (price.get_age_ms(now) / 1000) as u64
+        } else {
+            0
+        },
+    }
+}
+
+// Unmarked on purpose. BidAskPriceSrc is a shared contract without a stale flag, and its readers
+// need the last price as a reference even when it is old. Stale row keeps its last update in Dt.
+// Staleness is published by BidAskRawPriceSrc (IsStale, StaleAgeSec) and the TCP STALE message
+fn to_price_src(lp_id: &str, price: &InstrumentLastPrice) -> BidAskPriceSrc {
+    BidAskPriceSrc {
+        partition_key: lp_id.to_string(),
+        row_key: price.instrument_id.clone(),
+        src_id: price.src_id.clone(),
+        time_stamp: "".to_string(),
+        bid: price.bid,
+        ask: price.ask,
+        dt: price.source_time.to_rfc3339(),
+    }
+}
+
+impl UploadSrcPricesTimer {
+    // Prices stay dirty and go with the next tick
+    async fn upload_failed(&self, table: &str, err: String, dirty: &[InstrumentLastPrice]) {
+        service_sdk::my_logger::LOGGER.write_error(
+            String::from("UploadSrcPricesTimer"),
+            format!(
+                "Can not upload {} prices to {}. Err: {}",
+                dirty.len(),
+                table,
+                err
+            ),
+            LogEventCtx::new(),
+        );
+
+        self.app
+            .last_prices
+            .mark_dirty(dirty.iter().map(|price| price.instrument_id.clone()))
+            .await;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rust_extensions::date_time::DateTimeAsMicroseconds;
+
+    use crate::app::InstrumentLastPrice;
+
+    fn stale_price(now: DateTimeAsMicroseconds) -> InstrumentLastPrice {
+        let last_update = DateTimeAsMicroseconds::new(now.unix_microseconds - 90_000_000);
+
+        InstrumentLastPrice {
+            instrument_id: "EURUSD".to_string(),
+            src_id: "EUR/USD".to_string(),
+            raw_bid: 1.1,
+            raw_ask: 1.2,
+            bid: 1.1,
+            ask: 1.2,
+            source_time: last_update,
+            receive_time: last_update,
+            update_count: 1,
+            stale_since: Some(DateTimeAsMicroseconds::new(
+                now.unix_microseconds - 30_000_000,
+            )),
+        }
+    }
+
+    #[test]
+    fn test_stale_price_is_marked_in_raw_price_src() {
+        let now = DateTimeAsMicroseconds::now();
+        let entity = super::to_raw_price_src("LP", &stale_price(now), now);
+
+        assert!(entity.is_stale);
+        assert_eq!(entity.stale_age_sec, 90);
+    }
+
+    #[test]
+    fn test_stale_price_keeps_unmarked_price_src_row() {
+        let now = DateTimeAsMicroseconds::now();
+        let price = stale_price(now);
+        let entity = super::to_price_src("LP", &price);
+
+        assert_eq!(entity.row_key, "EURUSD");
+        assert_eq!((entity.bid, entity.ask), (1.1, 1.2));
+        assert_eq!(entity.dt, price.source_time.to_rfc3339());
     }
 }
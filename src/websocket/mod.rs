@@ -0,0 +1,4 @@
+mod price_ws_contract;
+pub use price_ws_contract::*;
+mod price_ws_server;
+pub use price_ws_server::*;
@@ -0,0 +1,94 @@
+use serde::{Deserialize, Serialize};
+
+use crate::app::OutboundMessage;
+
+// Client to server: {"action":"subscribe","instruments":["EURUSD"]}. "*" - all instruments
+#[derive(Debug, Deserialize)]
+#[serde(tag = "action", rename_all = "snake_case")]
+pub enum PriceWsRequest {
+    Subscribe { instruments: Vec<String> },
+    Unsubscribe { instruments: Vec<String> },
+}
+
+// Server to client. Ticks between snapshot_begin and snapshot_end are last known prices
+#[derive(Debug, Serialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum PriceWsMessage {
+    Tick {
+        instrument_id: String,
+        bid: f64,
+        ask: f64,
+        date: String,
+        // False - date is the bridge receive time
+        venue_time: bool,
+    },
+    SnapshotBegin,
+    SnapshotEnd,
+    Stale {
+        instrument_id: String,
+        age_ms: i64,
+    },
+    Active {
+        instrument_id: String,
+    },
+}
+
+impl PriceWsMessage {
+    pub fn from_outbound(message: &OutboundMessage) -> Self {
+        match message {
+            OutboundMessage::Tick(tick) => Self::Tick {
+                instrument_id: tick.instrument_id.to_string(),
+                bid: tick.bid,
+                ask: tick.ask,
+                date: tick.date.to_rfc3339(),
+                venue_time: tick.date_source.is_venue_time(),
+            },
+            OutboundMessage::SnapshotBegin => Self::SnapshotBegin,
+            OutboundMessage::SnapshotEnd => Self::SnapshotEnd,
+            OutboundMessage::Stale {
+                instrument_id,
+                age_ms,
+            } => Self::Stale {
+                instrument_id: instrument_id.to_string(),
+                age_ms: *age_ms,
+            },
+            OutboundMessage::Active { instrument_id } => Self::Active {
+                instrument_id: instrument_id.to_string(),
+            },
+        }
+    }
+
+    pub fn to_json(&self) -> String {
+        serde_json::to_string(self).unwrap()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{PriceWsMessage, PriceWsRequest};
+
+    #[test]
+    fn test_json_messages() {
+        let request: PriceWsRequest =
+            serde_json::from_str(r#"{"action":"subscribe","instruments":["EURUSD","*"]}"#).unwrap();
+
+        assert!(matches!(
+            request,
+            PriceWsRequest::Subscribe { instruments } if instruments == vec!["EURUSD", "*"]
+        ));
+
+        let message = PriceWsMessage::Stale {
+            instrument_id: "EURUSD".to_string(),
+            age_ms: 31000,
+        };
+
+        assert_eq!(
+            message.to_json(),
+            r#"{"type":"stale","instrument_id":"EURUSD","age_ms":31000}"#
+        );
+        assert_eq!(
+            PriceWsMessage::SnapshotEnd.to_json(),
+            r#"{"type":"snapshot_end"}"#
+        );
+    }
+}
@@ -0,0 +1,185 @@
+use std::{
+    net::SocketAddr,
+    sync::{
+        atomic::{AtomicI32, Ordering},
+        Arc,
+    },
+};
+
+use futures_util::{SinkExt, StreamExt};
+use service_sdk::my_logger::LogEventCtx;
+use tokio::net::{TcpListener, TcpStream};
+use tokio_tungstenite::tungstenite::Message;
+
+use crate::app::{AppContext, PriceSubscriber};
+
+use super::{PriceWsMessage, PriceWsRequest};
+
+const MAX_BATCH_SIZE: usize = 512;
+
+// Negative so WebSocket subscribers never clash with TCP connection ids
+static NEXT_SUBSCRIBER_ID: AtomicI32 = AtomicI32::new(-1);
+
+pub async fn start_price_ws_server(app: Arc<AppContext>, addr: SocketAddr) {
+    let listener = match TcpListener::bind(addr).await {
+        Ok(listener) => listener,
+        Err(err) => {
+            service_sdk::my_logger::LOGGER.write_error(
+                String::from("PriceWsServer"),
+                format!("Can not listen on {}. Err: {:?}", addr, err),
+                LogEventCtx::new(),
+            );
+            return;
+        }
+    };
+
+    service_sdk::my_logger::LOGGER.write_info(
+        String::from("PriceWsServer"),
+        format!("Listening on: {}", addr),
+        LogEventCtx::new(),
+    );
+
+    tokio::spawn(async move {
+        loop {
+            match listener.accept().await {
+                Ok((stream, addr)) => {
+                    tokio::spawn(handle_connection(app.clone(), stream, addr));
+                }
+                Err(err) => {
+                    service_sdk::my_logger::LOGGER.write_warning(
+                        String::from("PriceWsServer"),
+                        format!("Can not accept connection. Err: {:?}", err),
+                        LogEventCtx::new(),
+                    );
+                }
+            }
+        }
+    });
+}
+
+async fn handle_connection(app: Arc<AppContext>, stream: TcpStream, addr: SocketAddr) {
+    let socket = match tokio_tungstenite::accept_async(stream).await {
+        Ok(socket) => socket,
+        Err(err) => {
+            service_sdk::my_logger::LOGGER.write_warning(
+                String::from("PriceWsServer"),
+                format!("Handshake with {} failed. Err: {:?}", addr, err),
+                LogEventCtx::new(),
+            );
+            return;
+        }
+    };
+
+    let id = NEXT_SUBSCRIBER_ID.fetch_sub(1, Ordering::SeqCst);
+    let settings = app.settings_reader.get_price_clients_settings().await;
+
+    let subscriber = Arc::new(PriceSubscriber::new(
+        id,
+        format!("ws://{}", addr),
+        settings.soft_queue_limit,
+        settings.hard_queue_limit,
+    ));
+    // Snapshot and stale messages are part of the JSON protocol from the start
+    subscriber.enable_control_messages();
+
+    service_sdk::my_logger::LOGGER.write_info(
+        String::from("PriceWsServer"),
+        format!("New connection {}. Addr: {}", id, addr),
+        LogEventCtx::new().add("connectionId", id.to_string()),
+    );
+
+    {
+        let mut write_access = app.broadcast_data.lock().await;
+        write_access.add_subscriber(subscriber.clone());
+        // Under the lock so no live tick gets ahead of the snapshot
+        write_access.send_snapshot(id, None);
+    }
+
+    let (mut sink, mut stream) = socket.split();
+
+    let writer_subscriber = subscriber.clone();
+    let mut writer = tokio::spawn(async move {
+        while let Some(batch) = writer_subscriber.next_batch(MAX_BATCH_SIZE).await {
+            for message in batch.iter() {
+                let json = PriceWsMessage::from_outbound(message).to_json();
+
+                if sink.send(Message::text(json)).await.is_err() {
+                    return;
+                }
+            }
+        }
+
+        if let Some(reason) = writer_subscriber.get_close_reason() {
+            service_sdk::my_logger::LOGGER.write_warning(
+                String::from("SlowConsumer"),
+                format!(
+                    "Disconnecting {} {}. {}",
+                    writer_subscriber.id, writer_subscriber.addr, reason
+                ),
+                LogEventCtx::new()
+                    .add("connectionId", writer_subscriber.id.to_string())
+                    .add("addr", writer_subscriber.addr.as_str()),
+            );
+
+            let _ = sink.close().await;
+        }
+    });
+
+    let reader = async {
+        while let Some(Ok(message)) = stream.next().await {
+            match message {
+                Message::Text(text) => handle_request(&app, id, addr, text.as_str()).await,
+                Message::Close(_) => break,
+                // Pings are answered by the library
+                _ => {}
+            }
+        }
+    };
+
+    tokio::select! {
+        _ = reader => {}
+        _ = &mut writer => {}
+    }
+
+    app.broadcast_data.lock().await.remove_subscriber(id);
+
+    service_sdk::my_logger::LOGGER.write_info(
+        String::from("PriceWsServer"),
+        format!("Disconnected {}. Addr: {}", id, addr),
+        LogEventCtx::new().add("connectionId", id.to_string()),
+    );
+}
+
+async fn handle_request(app: &AppContext, id: i32, addr: SocketAddr, text: &str) {
+    let request = match serde_json::from_str::<PriceWsRequest>(text) {
+        Ok(request) => request,
+        Err(err) => {
+            service_sdk::my_logger::LOGGER.write_warning(
+                String::from("PriceWsServer"),
+                format!(
+                    "Unknown message from {} {}: {}. Err: {}",
+                    id, addr, text, err
+                ),
+                LogEventCtx::new().add("connectionId", id.to_string()),
+            );
+            return;
+        }
+    };
+
+    service_sdk::my_logger::LOGGER.write_info(
+        String::from("PriceWsServer"),
+        format!("{:?} {} {}", request, id, addr),
+        LogEventCtx::new().add("connectionId", id.to_string()),
+    );
+
+    let mut write_access = app.broadcast_data.lock().await;
+
+    match request {
+        PriceWsRequest::Subscribe { instruments } => {
+            write_access.subscribe(id, &instruments);
+        }
+        PriceWsRequest::Unsubscribe { instruments } => {
+            write_access.unsubscribe(id, &instruments);
+        }
+    }
+}
@@ -2,12 +2,25 @@ use my_tcp_sockets::TcpContract;
 use rust_extensions::date_time::DateTimeAsMicroseconds;
 use rust_fix::FixMessageReader;
 
+use super::QuoteTimeSource;
+
 #[derive(Debug)]
 pub struct YbMarketData {
     pub instrument_id: String,
+    // Quote time taken from date_source
     pub date: DateTimeAsMicroseconds,
+    pub date_source: QuoteTimeSource,
     pub bid: f64,
     pub ask: f64,
+    // MDEntrySize (271) if the venue sends it
+    pub bid_size: Option<f64>,
+    pub ask_size: Option<f64>,
+    // SendingTime (52)
+    pub sending_time: DateTimeAsMicroseconds,
+    // MDEntryDate/MDEntryTime (272/273) of the first entry if the venue sends them
+    pub entry_time: Option<DateTimeAsMicroseconds>,
+    // Moment the whole FIX frame was read from the socket
+    pub receive_time: DateTimeAsMicroseconds,
 }
 
 #[derive(Debug)]
@@ -16,16 +29,25 @@ pub enum YbFixContract {
     Reject,
     Logout,
     MarketData(YbMarketData),
-    MarketDataReject,
+    // symbol - None if the request is not the live one of its symbol. Set by the serializer
+    MarketDataReject {
+        req_id: String,
+        symbol: Option<String>,
+    },
     Others,
     Ping,
     Pong,
     SubscribeToInstrument(String),
+    UnsubscribeFromInstrument(String),
     Skip(String),
 }
 
 impl YbFixContract {
-    pub fn deserialize(fix_payload: Vec<u8>) -> Self {
+    pub fn deserialize(
+        fix_payload: Vec<u8>,
+        receive_time: DateTimeAsMicroseconds,
+        quote_time_source: QuoteTimeSource,
+    ) -> Self {
         let fix_message_reader = FixMessageReader::from_bytes(&fix_payload);
 
         if std::env::var("DEBUG_FIX").is_ok() {
@@ -35,7 +57,11 @@ impl YbFixContract {
         match fix_message_reader.get_message_type().unwrap() {
             "A" => Self::Logon,
             "W" => {
-                let model = super::model_deserializer::deserialize_market_data(&fix_message_reader);
+                let model = super::model_deserializer::deserialize_market_data(
+                    &fix_message_reader,
+                    receive_time,
+                    quote_time_source,
+                );
                 match model {
                     Ok(model) => match model {
                         Some(model) => Self::MarketData(model),
@@ -46,7 +72,15 @@ impl YbFixContract {
             }
             "V" => Self::Skip("Got V Message".to_string()),
 
-            "Y" => Self::MarketDataReject,
+            "Y" => Self::MarketDataReject {
+                req_id: fix_message_reader
+                    .get_value("262")
+                    .ok()
+                    .flatten()
+                    .unwrap_or_default()
+                    .to_string(),
+                symbol: None,
+            },
             "3" => Self::Reject,
             "5" => Self::Logout,
             _ => Self::Others,
@@ -1,21 +1,30 @@
-use std::sync::Arc;
+use std::sync::{atomic::Ordering, Arc};
 
 use my_tcp_sockets::{
     tcp_connection::TcpSocketConnection, SocketEventCallback, TcpSerializerState,
 };
+use rust_extensions::date_time::DateTimeAsMicroseconds;
 use service_sdk::my_logger::LogEventCtx;
+use tokio::sync::Mutex;
 
-use crate::{AppContext, FixSocketConnection};
+use crate::{app::LogThrottle, AppContext, FixSocketConnection};
 
 use super::{FixMessageSerializer, YbFixContract, YbTcpSate};
 
+// Garbage or a chatty venue skips message after message on the socket read path
+const SKIPPED_LOG_INTERVAL_SEC: i64 = 10;
+
 pub struct FixMessageHandler {
     app: Arc<AppContext>,
+    skipped_log: Mutex<LogThrottle>,
 }
 
 impl FixMessageHandler {
     pub async fn new(app: Arc<AppContext>) -> Self {
-        Self { app }
+        Self {
+            app,
+            skipped_log: Mutex::new(LogThrottle::new(SKIPPED_LOG_INTERVAL_SEC)),
+        }
     }
 }
 
@@ -23,12 +32,25 @@ impl FixMessageHandler {
     async fn send_instrument_subscribe(&self, connection: &Arc<FixSocketConnection>) {
         let maps = self.app.get_map().await;
         println!("Map: {:#?}", maps);
+
+        let mut external_instruments: Vec<String> = maps.keys().cloned().collect();
+
+        for leg in self.app.settings_reader.get_synthetic_legs().await {
+            if !maps.contains_key(&leg) {
+                external_instruments.push(leg);
+            }
+        }
+
         let mut info_message = "Subscribing to ".to_owned();
-        for external_instrument in maps.keys() {
+        for external_instrument in &external_instruments {
             info_message.push_str(format!("{} ", external_instrument).as_str());
             let subscribe_message =
                 YbFixContract::SubscribeToInstrument(external_instrument.to_string());
             connection.send(&subscribe_message).await;
+            self.app
+                .quote_activity
+                .subscribed(external_instrument)
+                .await;
         }
 
         service_sdk::my_logger::LOGGER.write_info(
@@ -47,6 +69,7 @@ impl SocketEventCallback<YbFixContract, FixMessageSerializer, YbTcpSate> for Fix
     ) {
         println!("Connected log");
         connection.send(&YbFixContract::Logon).await;
+        *self.app.fix_connection.lock().await = Some(connection);
     }
 
     async fn disconnected(
@@ -54,6 +77,8 @@ impl SocketEventCallback<YbFixContract, FixMessageSerializer, YbTcpSate> for Fix
         _connection: Arc<TcpSocketConnection<YbFixContract, FixMessageSerializer, YbTcpSate>>,
     ) {
         println!("Disconnected from FIX-Feed");
+        *self.app.fix_connection.lock().await = None;
+        self.app.quote_activity.clear().await;
     }
 
     async fn payload(
@@ -65,18 +90,78 @@ impl SocketEventCallback<YbFixContract, FixMessageSerializer, YbTcpSate> for Fix
             YbFixContract::Logon => {
                 self.send_instrument_subscribe(&connection).await;
             }
-            YbFixContract::Reject => {}
+            YbFixContract::Reject => {
+                self.app
+                    .fix_session_stats
+                    .rejects
+                    .fetch_add(1, Ordering::Relaxed);
+
+                service_sdk::my_logger::LOGGER.write_warning(
+                    String::from("FixMessageHandler"),
+                    String::from("Session level reject from YourBourse"),
+                    LogEventCtx::new(),
+                );
+            }
             YbFixContract::Logout => {}
             YbFixContract::MarketData(market_data) => {
+                self.app
+                    .quote_activity
+                    .quote_received(&market_data.instrument_id)
+                    .await;
                 self.app.broad_cast_bid_ask(market_data).await;
             }
-            YbFixContract::MarketDataReject => {}
+            YbFixContract::MarketDataReject { req_id, symbol } => match symbol {
+                Some(symbol) => {
+                    self.app
+                        .fix_session_stats
+                        .market_data_rejects
+                        .fetch_add(1, Ordering::Relaxed);
+
+                    service_sdk::my_logger::LOGGER.write_warning(
+                        String::from("FixMessageHandler"),
+                        format!(
+                            "Market data request {} for {} rejected by YourBourse",
+                            req_id, symbol
+                        ),
+                        LogEventCtx::new().add("externalSymbol", symbol.as_str()),
+                    );
+                }
+                // Replaced or disabled since. The live request of the symbol is not affected
+                None => {
+                    service_sdk::my_logger::LOGGER.write_info(
+                        String::from("FixMessageHandler"),
+                        format!("Reject of outdated market data request {}. Ignored", req_id),
+                        LogEventCtx::new(),
+                    );
+                }
+            },
             YbFixContract::Others => {}
             YbFixContract::Ping => {}
             YbFixContract::Pong => {}
             YbFixContract::SubscribeToInstrument(_) => {}
+            YbFixContract::UnsubscribeFromInstrument(_) => {}
             YbFixContract::Skip(reason) => {
-                println!("Fixing Fix message: {}", reason);
+                self.app
+                    .fix_session_stats
+                    .skipped
+                    .fetch_add(1, Ordering::Relaxed);
+
+                let not_logged = self
+                    .skipped_log
+                    .lock()
+                    .await
+                    .try_log("skipped", DateTimeAsMicroseconds::now());
+
+                if let Some(not_logged) = not_logged {
+                    service_sdk::my_logger::LOGGER.write_warning(
+                        String::from("FixMessageHandler"),
+                        format!(
+                            "Skipped FIX message: {}. {} more skipped since the last warning",
+                            reason, not_logged
+                        ),
+                        LogEventCtx::new(),
+                    );
+                }
             }
         }
     }
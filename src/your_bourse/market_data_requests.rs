@@ -0,0 +1,71 @@
+use std::collections::HashMap;
+
+// MDReqID (262) of the live market data request per symbol. Every request gets a new id,
+// so a late reject of an old request is never taken for the current one
+pub struct MarketDataRequests {
+    last_seq: u64,
+    // Symbol -> MDReqID
+    current: HashMap<String, String>,
+}
+
+impl MarketDataRequests {
+    pub fn new() -> Self {
+        Self {
+            last_seq: 0,
+            current: HashMap::new(),
+        }
+    }
+
+    // <symbol>-<seq>. Replaces the previous request of the symbol
+    pub fn subscribe(&mut self, symbol: &str) -> String {
+        let req_id = self.next_req_id(symbol);
+        self.current.insert(symbol.to_string(), req_id.clone());
+        req_id
+    }
+
+    // Id of the request to disable. A new one if the symbol has no live request
+    pub fn unsubscribe(&mut self, symbol: &str) -> String {
+        match self.current.remove(symbol) {
+            Some(req_id) => req_id,
+            None => self.next_req_id(symbol),
+        }
+    }
+
+    // Some(symbol) - the rejected request is the live one. It is dropped
+    pub fn rejected(&mut self, req_id: &str) -> Option<String> {
+        let symbol = self
+            .current
+            .iter()
+            .find(|(_, current)| current.as_str() == req_id)
+            .map(|(symbol, _)| symbol.to_string())?;
+
+        self.current.remove(&symbol);
+        Some(symbol)
+    }
+
+    fn next_req_id(&mut self, symbol: &str) -> String {
+        self.last_seq += 1;
+        format!("{}-{}", symbol, self.last_seq)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::MarketDataRequests;
+
+    #[test]
+    fn test_every_request_gets_new_id() {
+        let mut requests = MarketDataRequests::new();
+
+        let first = requests.subscribe("EUR/USD");
+        assert_eq!(requests.unsubscribe("EUR/USD"), first);
+
+        let second = requests.subscribe("EUR/USD");
+        assert_ne!(first, second);
+
+        // Late reject of the old request
+        assert!(requests.rejected(first.as_str()).is_none());
+        assert_eq!(requests.rejected(second.as_str()).unwrap(), "EUR/USD");
+        assert!(requests.rejected(second.as_str()).is_none());
+    }
+}
@@ -11,3 +11,7 @@ mod yb_serializer_factory;
 pub use fix_tcp_events::*;
 pub use yb_serializer_factory::*;
 mod model_deserializer;
+mod quote_time_source;
+pub use quote_time_source::*;
+mod market_data_requests;
+pub use market_data_requests::*;
@@ -1,10 +1,13 @@
+use rust_extensions::date_time::DateTimeAsMicroseconds;
 use rust_fix::FixMessageReader;
 use service_sdk::my_logger::LogEventCtx;
 
-use super::YbMarketData;
+use super::{QuoteTimeSource, YbMarketData};
 
 pub fn deserialize_market_data(
     fix_message: &FixMessageReader<'_>,
+    receive_time: DateTimeAsMicroseconds,
+    quote_time_source: QuoteTimeSource,
 ) -> Result<Option<YbMarketData>, String> {
     // there shall be always no_md_entries in the message
     // skip message if it's not exist
@@ -41,27 +44,61 @@ pub fn deserialize_market_data(
         .map(|x| x.parse::<f64>().unwrap())
         .collect::<Vec<f64>>();
 
-    // I think the clients have to know that we do like this,
-    // this may be a regulatory issue for them if they not aware
-    //let (bid, ask) = match prices[1] > prices[0] {
-    //    true => (prices[0], prices[1]),
-    //    false => (prices[1], prices[0]),
-    //};
-
+    // Crossed quotes are passed as is. What to do with them is decided by
+    // the per-instrument policy in AppContext::broad_cast_bid_ask
     let (bid, ask) = (prices[0], prices[1]);
 
     if bid < 0.00001 || ask < 0.00001 {
         return Err(format!("bid:{} or ask:{} less than 0.00001", bid, ask));
     }
 
+    // Sizes are optional. Used only if both entries have them
+    let sizes = fix_message
+        .get_values("271")
+        .unwrap_or_default()
+        .iter()
+        .filter_map(|x| x.parse::<f64>().ok())
+        .collect::<Vec<f64>>();
+
+    let (bid_size, ask_size) = if sizes.len() == prices.len() {
+        (Some(sizes[0]), Some(sizes[1]))
+    } else {
+        (None, None)
+    };
+
     let external_market = fix_message.get_value("55").unwrap().unwrap();
     let date_time = fix_message.get_value("52").unwrap().unwrap();
+    let sending_time = crate::date_utils::parse_fix_date(date_time);
+
+    let entry_time = match fix_message.get_value("273").unwrap() {
+        Some(entry_time) => crate::date_utils::parse_md_entry_date_time(
+            fix_message.get_value("272").unwrap(),
+            entry_time,
+            sending_time,
+        ),
+        None => None,
+    };
+
+    let (date, date_source) = match quote_time_source {
+        QuoteTimeSource::SendingTime => (sending_time, QuoteTimeSource::SendingTime),
+        QuoteTimeSource::EntryTime => match entry_time {
+            Some(entry_time) => (entry_time, QuoteTimeSource::EntryTime),
+            None => (sending_time, QuoteTimeSource::SendingTime),
+        },
+        QuoteTimeSource::ReceiveTime => (receive_time, QuoteTimeSource::ReceiveTime),
+    };
 
     let result = YbMarketData {
         instrument_id: external_market.to_string(),
-        date: crate::date_utils::parse_fix_date(date_time),
+        date,
+        date_source,
         bid,
         ask,
+        bid_size,
+        ask_size,
+        sending_time,
+        entry_time,
+        receive_time,
     };
 
     Ok(Some(result))
@@ -86,3 +123,74 @@ pub fn deserialize_market_data(
     }
     */
 }
+
+#[cfg(test)]
+mod tests {
+    use rust_extensions::date_time::DateTimeAsMicroseconds;
+    use rust_fix::{FixMessageReader, FixMessageWriter};
+
+    use crate::your_bourse::QuoteTimeSource;
+
+    const SENDING_TIME: &'static str = "20240425-17:28:02.629";
+
+    fn create_market_data(entry_time: Option<&str>) -> Vec<u8> {
+        let mut fix_builder = FixMessageWriter::new("FIX.4.4", "W");
+        fix_builder.with_value("49", "YOURBOURSE");
+        fix_builder.with_value("56", "BRIDGE");
+        fix_builder.with_value("34", "2");
+        fix_builder.with_value("52", SENDING_TIME);
+        fix_builder.with_value("55", "EUR/USD");
+        fix_builder.with_value("268", "2");
+        fix_builder.with_value("269", "0");
+        fix_builder.with_value("270", "1.08345");
+        if let Some(entry_time) = entry_time {
+            fix_builder.with_value("272", "20240425");
+            fix_builder.with_value("273", entry_time);
+        }
+        fix_builder.with_value("269", "1");
+        fix_builder.with_value("270", "1.08355");
+        fix_builder.compile_message()
+    }
+
+    fn get_date(
+        payload: &[u8],
+        quote_time_source: QuoteTimeSource,
+    ) -> (DateTimeAsMicroseconds, QuoteTimeSource) {
+        let fix_message = FixMessageReader::from_bytes(payload);
+        let receive_time = DateTimeAsMicroseconds::create(2024, 4, 25, 17, 28, 3, 0);
+
+        let market_data =
+            super::deserialize_market_data(&fix_message, receive_time, quote_time_source)
+                .unwrap()
+                .unwrap();
+
+        (market_data.date, market_data.date_source)
+    }
+
+    #[test]
+    fn test_quote_time_source_selection() {
+        let sending_time = DateTimeAsMicroseconds::create(2024, 4, 25, 17, 28, 2, 629000);
+        let entry_time = DateTimeAsMicroseconds::create(2024, 4, 25, 17, 28, 1, 123456);
+        let receive_time = DateTimeAsMicroseconds::create(2024, 4, 25, 17, 28, 3, 0);
+
+        let with_entry_time = create_market_data(Some("17:28:01.123456"));
+        let without_entry_time = create_market_data(None);
+
+        let (date, source) = get_date(&with_entry_time, QuoteTimeSource::SendingTime);
+        assert_eq!(date.unix_microseconds, sending_time.unix_microseconds);
+        assert_eq!(source, QuoteTimeSource::SendingTime);
+
+        let (date, source) = get_date(&with_entry_time, QuoteTimeSource::EntryTime);
+        assert_eq!(date.unix_microseconds, entry_time.unix_microseconds);
+        assert_eq!(source, QuoteTimeSource::EntryTime);
+
+        // No 272/273 - falls back to SendingTime and says so
+        let (date, source) = get_date(&without_entry_time, QuoteTimeSource::EntryTime);
+        assert_eq!(date.unix_microseconds, sending_time.unix_microseconds);
+        assert_eq!(source, QuoteTimeSource::SendingTime);
+
+        let (date, source) = get_date(&with_entry_time, QuoteTimeSource::ReceiveTime);
+        assert_eq!(date.unix_microseconds, receive_time.unix_microseconds);
+        assert_eq!(source, QuoteTimeSource::ReceiveTime);
+    }
+}
@@ -32,7 +32,19 @@ pub fn serialize_logon(settings: &YbPriceFeedSettings, count: u64) -> FixMessage
     fix_builder.with_value("554", &settings.pass);
     fix_builder.with_value("98", "0");
 
-    println!("Logon message: {}", fix_builder.to_string());
+    return fix_builder;
+}
+
+pub fn serialize_logout(settings: &YbPriceFeedSettings, count: u64) -> FixMessageWriter {
+    let now = DateTimeAsMicroseconds::now();
+    let date_string = crate::date_utils::to_fix_date_string(now);
+
+    let mut fix_builder = FixMessageWriter::new(OUR_FIX_VERSION, "5");
+    fix_builder.with_value("49", &settings.sender_company_id);
+    fix_builder.with_value("56", &settings.target_company_id);
+    fix_builder.with_value("52", date_string.as_str());
+    fix_builder.with_value("34", count.to_string().as_str());
+
     return fix_builder;
 }
 
@@ -40,21 +52,41 @@ pub fn serialize_instrument_subscribe(
     settings: &YbPriceFeedSettings,
     count: u64,
     instrument: &str,
+    req_id: &str,
+) -> FixMessageWriter {
+    //SubscriptionRequestType 1 = Snapshot + Updates
+    serialize_market_data_request(settings, count, instrument, req_id, "1")
+}
+
+pub fn serialize_instrument_unsubscribe(
+    settings: &YbPriceFeedSettings,
+    count: u64,
+    instrument: &str,
+    req_id: &str,
+) -> FixMessageWriter {
+    //SubscriptionRequestType 2 = Disable previous Snapshot + Updates
+    serialize_market_data_request(settings, count, instrument, req_id, "2")
+}
+
+fn serialize_market_data_request(
+    settings: &YbPriceFeedSettings,
+    count: u64,
+    instrument: &str,
+    req_id: &str,
+    subscription_request_type: &str,
 ) -> FixMessageWriter {
     let now = DateTimeAsMicroseconds::now();
     let date_string = crate::date_utils::to_fix_date_string(now);
 
     let mut fix_builder = FixMessageWriter::new(OUR_FIX_VERSION, "V");
-    let uuid = now.unix_microseconds;
 
     fix_builder.with_value("49", &settings.sender_company_id);
     fix_builder.with_value("52", date_string.as_str());
     fix_builder.with_value("56", &settings.target_company_id);
     fix_builder.with_value("34", count.to_string().as_str());
-    //MDReqID - can be just a symbol name
-    fix_builder.with_value("262", &uuid.to_string());
-    //SubscriptionRequestType 1 = Snapshot + Updates
-    fix_builder.with_value("263", "1");
+    //MDReqID - unique per request. Unsubscribe repeats the id of the request it disables
+    fix_builder.with_value("262", req_id);
+    fix_builder.with_value("263", subscription_request_type);
     //Market Depth 1 = Top of Book
     fix_builder.with_value("264", "1");
     //MDUpdateType
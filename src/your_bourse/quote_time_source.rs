@@ -0,0 +1,21 @@
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum QuoteTimeSource {
+    // SendingTime (52) - the moment the venue sent the message
+    #[default]
+    SendingTime,
+    // MDEntryDate/MDEntryTime (272/273). Falls back to SendingTime if the venue omits them
+    EntryTime,
+    // Local moment the message was read from the socket
+    ReceiveTime,
+}
+
+impl QuoteTimeSource {
+    pub fn is_venue_time(&self) -> bool {
+        match self {
+            Self::SendingTime | Self::EntryTime => true,
+            Self::ReceiveTime => false,
+        }
+    }
+}
@@ -1,12 +1,18 @@
-use std::sync::atomic::AtomicU64;
+use std::sync::{atomic::AtomicU64, Arc};
 
 use my_tcp_sockets::{
     socket_reader::{ReadBuffer, ReadingTcpContractFail, SocketReader},
     TcpSocketSerializer, TcpWriteBuffer,
 };
 
+use rust_extensions::date_time::DateTimeAsMicroseconds;
 use rust_fix::{utils::FIX_DELIMITER, FixMessageItem};
 
+use crate::{
+    app::ClockSkewEstimator,
+    fix_journal::{FixDirection, FixJournal},
+};
+
 use super::yb_tcp_state::YbTcpSate;
 
 use super::YbFixContract;
@@ -15,13 +21,17 @@ const FIX_DELIMITER_AS_ARR: [u8; 1] = [FIX_DELIMITER];
 pub struct FixMessageSerializer {
     message_counter: AtomicU64,
     buffer: ReadBuffer,
+    journal: Arc<FixJournal>,
+    clock_skew: Arc<ClockSkewEstimator>,
 }
 
 impl FixMessageSerializer {
-    pub fn new() -> Self {
+    pub fn new(journal: Arc<FixJournal>, clock_skew: Arc<ClockSkewEstimator>) -> Self {
         Self {
             message_counter: AtomicU64::new(1),
             buffer: ReadBuffer::new(2048 * 24),
+            journal,
+            clock_skew,
         }
     }
 
@@ -80,11 +90,36 @@ impl TcpSocketSerializer<YbFixContract, YbTcpSate> for FixMessageSerializer {
                 state.get_settings(),
                 self.get_next_message_id(),
             ),
+            YbFixContract::Logout => super::models_serializers::serialize_logout(
+                state.get_settings(),
+                self.get_next_message_id(),
+            ),
             YbFixContract::SubscribeToInstrument(instrument) => {
+                let req_id = state
+                    .market_data_requests
+                    .lock()
+                    .unwrap()
+                    .subscribe(instrument);
+
                 super::models_serializers::serialize_instrument_subscribe(
                     state.get_settings(),
                     self.get_next_message_id(),
                     instrument,
+                    req_id.as_str(),
+                )
+            }
+            YbFixContract::UnsubscribeFromInstrument(instrument) => {
+                let req_id = state
+                    .market_data_requests
+                    .lock()
+                    .unwrap()
+                    .unsubscribe(instrument);
+
+                super::models_serializers::serialize_instrument_unsubscribe(
+                    state.get_settings(),
+                    self.get_next_message_id(),
+                    instrument,
+                    req_id.as_str(),
                 )
             }
             _ => {
@@ -96,7 +131,13 @@ impl TcpSocketSerializer<YbFixContract, YbTcpSate> for FixMessageSerializer {
             println!("Out Fix Message: {:?}", fix_message_writer.to_string());
         }
 
-        out.write_slice(fix_message_writer.compile_message().as_slice());
+        let payload = fix_message_writer.compile_message();
+        self.journal.write(
+            FixDirection::Out,
+            payload.as_slice(),
+            DateTimeAsMicroseconds::now(),
+        );
+        out.write_slice(payload.as_slice());
     }
 
     fn get_ping(&self) -> YbFixContract {
@@ -106,10 +147,23 @@ impl TcpSocketSerializer<YbFixContract, YbTcpSate> for FixMessageSerializer {
     async fn deserialize<TSocketReader: Send + Sync + 'static + SocketReader>(
         &mut self,
         socket_reader: &mut TSocketReader,
-        _state: &YbTcpSate,
+        state: &YbTcpSate,
     ) -> Result<YbFixContract, ReadingTcpContractFail> {
         let fix_payload = self.receive_fix_payload(socket_reader).await?;
+        let receive_time = DateTimeAsMicroseconds::now();
+        self.journal
+            .write(FixDirection::In, fix_payload.as_slice(), receive_time);
+        self.clock_skew
+            .add_sample_from_payload(fix_payload.as_slice(), receive_time)
+            .await;
+
+        let mut contract =
+            YbFixContract::deserialize(fix_payload, receive_time, state.quote_time_source);
+
+        if let YbFixContract::MarketDataReject { req_id, symbol } = &mut contract {
+            *symbol = state.market_data_requests.lock().unwrap().rejected(req_id);
+        }
 
-        return Ok(YbFixContract::deserialize(fix_payload));
+        return Ok(contract);
     }
 }
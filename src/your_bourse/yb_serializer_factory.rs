@@ -19,10 +19,11 @@ impl YbSerializerFactory {
 #[async_trait::async_trait]
 impl TcpSerializerFactory<YbFixContract, FixMessageSerializer, YbTcpSate> for YbSerializerFactory {
     async fn create_serializer(&self) -> FixMessageSerializer {
-        FixMessageSerializer::new()
+        FixMessageSerializer::new(self.app.fix_journal.clone(), self.app.clock_skew.clone())
     }
     async fn create_serializer_state(&self) -> YbTcpSate {
         let settings = self.app.get_yb_settings().await;
-        YbTcpSate::new(settings)
+        let quote_time_source = self.app.settings_reader.get_quote_time_source().await;
+        YbTcpSate::new(settings, quote_time_source)
     }
 }
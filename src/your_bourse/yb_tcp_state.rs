@@ -1,15 +1,24 @@
+use std::sync::Mutex;
+
 use my_nosql_contracts::YbPriceFeedSettings;
 use my_tcp_sockets::TcpSerializerState;
 
-use super::YbFixContract;
+use super::{MarketDataRequests, QuoteTimeSource, YbFixContract};
 
 pub struct YbTcpSate {
     pub settings: Option<YbPriceFeedSettings>,
+    pub quote_time_source: QuoteTimeSource,
+    // Lives as long as the FIX session. Written by the serializer, which has shared access only
+    pub market_data_requests: Mutex<MarketDataRequests>,
 }
 
 impl YbTcpSate {
-    pub fn new(settings: Option<YbPriceFeedSettings>) -> Self {
-        Self { settings }
+    pub fn new(settings: Option<YbPriceFeedSettings>, quote_time_source: QuoteTimeSource) -> Self {
+        Self {
+            settings,
+            quote_time_source,
+            market_data_requests: Mutex::new(MarketDataRequests::new()),
+        }
     }
 
     pub fn get_settings(&self) -> &YbPriceFeedSettings {
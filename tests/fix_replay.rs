@@ -0,0 +1,120 @@
+use std::{collections::HashMap, sync::Arc};
+
+use rust_extensions::date_time::DateTimeAsMicroseconds;
+use rust_fix::FixMessageWriter;
+use your_bourse_bridge::{
+    app::{AppContext, StaticPriceFeedSource, SyntheticFormula},
+    fix_journal::{replay_fix_log, FixLogEntry, FixReplaySettings},
+    settings::{
+        SettingsModel, SettingsReader, SyntheticInstrumentSettingsModel, SyntheticLegSettingsModel,
+    },
+};
+
+const SYNTHETIC: &'static str = "EURJPY";
+
+async fn create_app() -> AppContext {
+    let leg = |external_symbol: &str| SyntheticLegSettingsModel {
+        external_symbol: external_symbol.to_string(),
+        transform: None,
+    };
+
+    let mut synthetic_instruments = HashMap::new();
+    synthetic_instruments.insert(
+        SYNTHETIC.to_string(),
+        SyntheticInstrumentSettingsModel {
+            formula: SyntheticFormula::Product,
+            legs: vec![leg("EUR/USD"), leg("USD/JPY")],
+            max_leg_age_ms: 1_000,
+        },
+    );
+
+    let settings_reader = SettingsReader::from_model(SettingsModel {
+        liquidity_provider_id: "YOURBOURSE".to_string(),
+        synthetic_instruments: Some(synthetic_instruments),
+        ..Default::default()
+    });
+
+    let feed_source = StaticPriceFeedSource {
+        instrument_map: HashMap::new(),
+        yb_settings: None,
+    };
+
+    AppContext::with_feed_source(Arc::new(settings_reader), Arc::new(feed_source)).await
+}
+
+// Journal entry of a market data snapshot received at the given millisecond of 2024-04-25 17:28
+fn entry(symbol: &str, bid: f64, ask: f64, received_ms: i64) -> FixLogEntry {
+    let sending_ms = received_ms - 50;
+
+    let mut fix_builder = FixMessageWriter::new("FIX.4.4", "W");
+    fix_builder.with_value("49", "YOURBOURSE");
+    fix_builder.with_value("56", "BRIDGE");
+    fix_builder.with_value("34", "2");
+    fix_builder.with_value(
+        "52",
+        format!(
+            "20240425-17:28:{:02}.{:03}",
+            sending_ms / 1000,
+            sending_ms % 1000
+        )
+        .as_str(),
+    );
+    fix_builder.with_value("55", symbol);
+    fix_builder.with_value("268", "2");
+    fix_builder.with_value("269", "0");
+    fix_builder.with_value("270", bid.to_string().as_str());
+    fix_builder.with_value("269", "1");
+    fix_builder.with_value("270", ask.to_string().as_str());
+
+    let timestamp = DateTimeAsMicroseconds::new(
+        DateTimeAsMicroseconds::create(2024, 4, 25, 17, 28, 0, 0).unix_microseconds
+            + received_ms * 1000,
+    );
+
+    FixLogEntry {
+        timestamp: Some(timestamp),
+        payload: fix_builder.compile_message(),
+    }
+}
+
+async fn replay(app: &AppContext, entries: Vec<FixLogEntry>) -> usize {
+    let settings = FixReplaySettings {
+        speed: 1.0,
+        no_delay: true,
+        identity_map: false,
+    };
+
+    replay_fix_log(app, entries, &settings).await
+}
+
+#[tokio::test]
+async fn test_old_journal_replays_synthetic_instruments() {
+    let app = create_app().await;
+
+    let entries = vec![
+        entry("EUR/USD", 1.08, 1.0802, 2_100),
+        entry("USD/JPY", 155.0, 155.02, 2_400),
+    ];
+
+    assert_eq!(replay(&app, entries).await, 2);
+
+    // Legs are 300 ms apart on the recorded clock, though the journal is years old
+    let synthetic = app.last_prices.get_instrument(SYNTHETIC).await.unwrap();
+    assert!((synthetic.bid - 1.08 * 155.0).abs() < 1e-9);
+    assert!((synthetic.ask - 1.0802 * 155.02).abs() < 1e-9);
+}
+
+#[tokio::test]
+async fn test_replay_keeps_recorded_leg_age() {
+    let app = create_app().await;
+
+    let entries = vec![
+        entry("EUR/USD", 1.08, 1.0802, 2_100),
+        entry("USD/JPY", 155.0, 155.02, 4_100),
+    ];
+
+    assert_eq!(replay(&app, entries).await, 2);
+
+    // EUR/USD is 2 sec old when USD/JPY arrives - over max_leg_age_ms
+    assert!(app.last_prices.get_instrument(SYNTHETIC).await.is_none());
+}
@@ -0,0 +1,479 @@
+use std::{collections::HashMap, net::SocketAddr, sync::Arc, time::Duration};
+
+use futures_util::{SinkExt, StreamExt};
+use my_tcp_sockets::{TcpClient, TcpServer};
+use rust_extensions::AppStates;
+use tokio::{
+    io::{AsyncReadExt, AsyncWriteExt},
+    net::TcpStream,
+};
+use tokio_tungstenite::{tungstenite::Message, MaybeTlsStream, WebSocketStream};
+use your_bourse_bridge::{
+    app::{AppContext, StaticPriceFeedSource},
+    mock_yb::{MockInjection, MockQuote, MockQuoteSource, MockYbAcceptor, MockYbSettings},
+    settings::{
+        FixJournalSettingsModel, PriceWebSocketSettingsModel, SettingsModel, SettingsReader,
+        YbPriceFeedSettingsModel,
+    },
+    tcp::PriceRouterTcpServer,
+    websocket::start_price_ws_server,
+    your_bourse::{FixMessageHandler, YbSerializerFactory},
+};
+
+const EXTERNAL_SYMBOL: &'static str = "EUR/USD";
+const OUR_SYMBOL: &'static str = "EURUSD";
+const SECOND_EXTERNAL_SYMBOL: &'static str = "GBP/USD";
+const SECOND_OUR_SYMBOL: &'static str = "GBPUSD";
+
+struct TestBridge {
+    app: Arc<AppContext>,
+    acceptor: MockYbAcceptor,
+    price_client: TcpStream,
+    price_server_addr: SocketAddr,
+    // Both stop together with the test
+    _price_server: PriceRouterTcpServer,
+    _fix_client: TcpClient,
+}
+
+async fn start_bridge(settings: MockYbSettings) -> TestBridge {
+    let acceptor = MockYbAcceptor::start("127.0.0.1:0", settings)
+        .await
+        .unwrap();
+
+    let price_server_addr = get_free_local_addr();
+
+    let mut journal_path = std::env::temp_dir();
+    journal_path.push(format!(
+        "yb-bridge-test-journal-{}",
+        price_server_addr.port()
+    ));
+
+    let settings_reader = SettingsReader::from_model(SettingsModel {
+        liquidity_provider_id: "YOURBOURSE".to_string(),
+        feed_settings: Some(YbPriceFeedSettingsModel {
+            host_port: acceptor.addr().to_string(),
+            sender_company_id: "BRIDGE".to_string(),
+            target_company_id: "YOURBOURSE".to_string(),
+            user_password: "pass".to_string(),
+        }),
+        fix_journal: Some(FixJournalSettingsModel {
+            path: journal_path.to_str().unwrap().to_string(),
+            ..Default::default()
+        }),
+        ..Default::default()
+    });
+
+    let mut instrument_map = HashMap::new();
+    instrument_map.insert(OUR_SYMBOL.to_string(), EXTERNAL_SYMBOL.to_string());
+    instrument_map.insert(
+        SECOND_OUR_SYMBOL.to_string(),
+        SECOND_EXTERNAL_SYMBOL.to_string(),
+    );
+
+    let feed_source = StaticPriceFeedSource {
+        instrument_map,
+        yb_settings: None,
+    };
+
+    let app = Arc::new(
+        AppContext::with_feed_source(Arc::new(settings_reader), Arc::new(feed_source)).await,
+    );
+
+    let price_server = PriceRouterTcpServer {
+        tcp_server: TcpServer::new("TestPriceServer".to_string(), price_server_addr),
+        app: app.clone(),
+        app_states: Arc::new(AppStates::create_initialized()),
+    };
+    price_server.start().await;
+
+    let price_client = TcpStream::connect(price_server_addr).await.unwrap();
+
+    let fix_client = TcpClient::new("TestYbFixClient".to_string(), app.clone());
+
+    fix_client
+        .start(
+            Arc::new(YbSerializerFactory::new(app.clone())),
+            Arc::new(FixMessageHandler::new(app.clone()).await),
+            service_sdk::my_logger::LOGGER.clone(),
+        )
+        .await;
+
+    TestBridge {
+        app,
+        acceptor,
+        price_client,
+        price_server_addr,
+        _price_server: price_server,
+        _fix_client: fix_client,
+    }
+}
+
+fn get_free_local_addr() -> SocketAddr {
+    let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+    listener.local_addr().unwrap()
+}
+
+async fn wait_for_payload(stream: &mut TcpStream, expected: &[u8]) -> bool {
+    read_until_payload(stream, expected).await.is_some()
+}
+
+// Everything received up to and including the expected bytes
+async fn read_until_payload(stream: &mut TcpStream, expected: &[u8]) -> Option<Vec<u8>> {
+    let deadline = tokio::time::Instant::now() + Duration::from_secs(15);
+    let mut received = Vec::new();
+    let mut chunk = [0u8; 4096];
+
+    loop {
+        match tokio::time::timeout_at(deadline, stream.read(&mut chunk)).await {
+            Ok(Ok(read)) if read > 0 => {
+                received.extend_from_slice(&chunk[..read]);
+
+                if received
+                    .windows(expected.len())
+                    .any(|window| window == expected)
+                {
+                    return Some(received);
+                }
+            }
+            _ => return None,
+        }
+    }
+}
+
+// Everything received within the period
+async fn read_for(stream: &mut TcpStream, period: Duration) -> Vec<u8> {
+    let deadline = tokio::time::Instant::now() + period;
+    let mut received = Vec::new();
+    let mut chunk = [0u8; 4096];
+
+    loop {
+        match tokio::time::timeout_at(deadline, stream.read(&mut chunk)).await {
+            Ok(Ok(read)) if read > 0 => received.extend_from_slice(&chunk[..read]),
+            _ => return received,
+        }
+    }
+}
+
+type WsClient = WebSocketStream<MaybeTlsStream<TcpStream>>;
+
+// JSON messages received within the period
+async fn read_ws_for(client: &mut WsClient, period: Duration) -> Vec<serde_json::Value> {
+    let deadline = tokio::time::Instant::now() + period;
+    let mut received = Vec::new();
+
+    loop {
+        match tokio::time::timeout_at(deadline, client.next()).await {
+            Ok(Some(Ok(Message::Text(text)))) => {
+                received.push(serde_json::from_str(text.as_str()).unwrap())
+            }
+            Ok(Some(Ok(_))) => {}
+            _ => return received,
+        }
+    }
+}
+
+// JSON messages up to and including the first one of the expected type
+async fn read_ws_until(
+    client: &mut WsClient,
+    expected_type: &str,
+) -> Option<Vec<serde_json::Value>> {
+    let deadline = tokio::time::Instant::now() + Duration::from_secs(15);
+    let mut received = Vec::new();
+
+    loop {
+        match tokio::time::timeout_at(deadline, client.next()).await {
+            Ok(Some(Ok(Message::Text(text)))) => {
+                let message: serde_json::Value = serde_json::from_str(text.as_str()).unwrap();
+                let found = message["type"] == expected_type;
+                received.push(message);
+
+                if found {
+                    return Some(received);
+                }
+            }
+            Ok(Some(Ok(_))) => {}
+            _ => return None,
+        }
+    }
+}
+
+async fn wait_until(condition: impl Fn() -> bool) -> bool {
+    let deadline = tokio::time::Instant::now() + Duration::from_secs(15);
+
+    while tokio::time::Instant::now() < deadline {
+        if condition() {
+            return true;
+        }
+        tokio::time::sleep(Duration::from_millis(50)).await;
+    }
+
+    false
+}
+
+fn fast_settings(quote_source: MockQuoteSource) -> MockYbSettings {
+    MockYbSettings {
+        quote_source,
+        quote_interval: Duration::from_millis(100),
+        reject_symbols: Vec::new(),
+    }
+}
+
+#[tokio::test]
+async fn test_scripted_snapshot_reaches_price_tcp_client() {
+    let mut bridge = start_bridge(fast_settings(MockQuoteSource::Scripted(vec![MockQuote {
+        symbol: EXTERNAL_SYMBOL.to_string(),
+        bid: 1.08345,
+        ask: 1.08355,
+    }])))
+    .await;
+
+    assert!(wait_for_payload(&mut bridge.price_client, OUR_SYMBOL.as_bytes()).await);
+    assert!(wait_for_payload(&mut bridge.price_client, b"1.08345").await);
+    // One request per mapped symbol
+    let stats = bridge.acceptor.stats.clone();
+    assert!(wait_until(|| stats.get_market_data_requests() == 2).await);
+}
+
+#[tokio::test]
+async fn test_new_price_client_receives_snapshot() {
+    let mut bridge = start_bridge(fast_settings(MockQuoteSource::Scripted(vec![MockQuote {
+        symbol: EXTERNAL_SYMBOL.to_string(),
+        bid: 1.08345,
+        ask: 1.08355,
+    }])))
+    .await;
+
+    assert!(wait_for_payload(&mut bridge.price_client, b"1.08345").await);
+
+    let mut late_client = TcpStream::connect(bridge.price_server_addr).await.unwrap();
+    // Snapshot framing is only for clients speaking the control protocol
+    late_client.write_all(b"SUBSCRIBE *\r\n").await.unwrap();
+
+    let received = read_until_payload(&mut late_client, b"SNAPSHOT_END")
+        .await
+        .unwrap();
+    let received = String::from_utf8_lossy(&received);
+
+    let snapshot_begin = received.find("SNAPSHOT_BEGIN").unwrap();
+    let price = received.find("1.08345").unwrap();
+    assert!(snapshot_begin < price);
+}
+
+// Legacy clients are exempt from snapshot marking. They get the snapshot as plain feed lines
+#[tokio::test]
+async fn test_legacy_client_gets_unmarked_snapshot() {
+    let mut bridge = start_bridge(fast_settings(MockQuoteSource::Scripted(vec![MockQuote {
+        symbol: EXTERNAL_SYMBOL.to_string(),
+        bid: 1.08345,
+        ask: 1.08355,
+    }])))
+    .await;
+
+    assert!(wait_for_payload(&mut bridge.price_client, b"1.08345").await);
+
+    // Connects after the first price, so its snapshot is not empty
+    let mut legacy_client = TcpStream::connect(bridge.price_server_addr).await.unwrap();
+
+    // Snapshot and a few live ticks
+    let received = read_for(&mut legacy_client, Duration::from_secs(1)).await;
+    let received = String::from_utf8_lossy(&received);
+
+    assert!(received.contains(OUR_SYMBOL));
+    assert!(received.contains("1.08345"));
+
+    for control in ["SNAPSHOT_BEGIN", "SNAPSHOT_END", "STALE ", "ACTIVE "] {
+        assert!(!received.contains(control));
+    }
+}
+
+#[tokio::test]
+async fn test_legacy_client_gets_marked_snapshot_after_first_control_line() {
+    let mut bridge = start_bridge(fast_settings(MockQuoteSource::Scripted(vec![MockQuote {
+        symbol: EXTERNAL_SYMBOL.to_string(),
+        bid: 1.08345,
+        ask: 1.08355,
+    }])))
+    .await;
+
+    assert!(wait_for_payload(&mut bridge.price_client, b"1.08345").await);
+
+    let mut client = TcpStream::connect(bridge.price_server_addr).await.unwrap();
+    assert!(wait_for_payload(&mut client, b"1.08345").await);
+
+    // Drops GBPUSD only. EURUSD stays in the subscription and in the snapshot
+    client
+        .write_all(format!("UNSUBSCRIBE {}\r\n", SECOND_OUR_SYMBOL).as_bytes())
+        .await
+        .unwrap();
+
+    let received = read_until_payload(&mut client, b"SNAPSHOT_END")
+        .await
+        .unwrap();
+    let received = String::from_utf8_lossy(&received);
+
+    let snapshot_begin = received.find("SNAPSHOT_BEGIN").unwrap();
+    let price = snapshot_begin + received[snapshot_begin..].find("1.08345").unwrap();
+    assert!(price < received.find("SNAPSHOT_END").unwrap());
+}
+
+#[tokio::test]
+async fn test_bridge_resubscribes_after_injected_logout() {
+    let mut bridge = start_bridge(fast_settings(MockYbSettings::default().quote_source)).await;
+
+    assert!(wait_for_payload(&mut bridge.price_client, OUR_SYMBOL.as_bytes()).await);
+
+    bridge
+        .acceptor
+        .inject(MockInjection::Logout("Injected logout".to_string()));
+
+    let stats = bridge.acceptor.stats.clone();
+    assert!(wait_until(|| stats.get_logons() >= 2 && stats.get_market_data_requests() >= 4).await);
+    assert!(wait_for_payload(&mut bridge.price_client, OUR_SYMBOL.as_bytes()).await);
+}
+
+#[tokio::test]
+async fn test_prices_keep_flowing_after_garbage_and_sequence_gap() {
+    let mut bridge = start_bridge(fast_settings(MockYbSettings::default().quote_source)).await;
+
+    assert!(wait_for_payload(&mut bridge.price_client, OUR_SYMBOL.as_bytes()).await);
+
+    bridge.acceptor.inject(MockInjection::SequenceGap(10));
+    bridge.acceptor.inject(MockInjection::garbage());
+    bridge
+        .acceptor
+        .inject(MockInjection::Reject("Injected reject".to_string()));
+
+    let quotes_sent = bridge.acceptor.stats.get_quotes_sent();
+    let stats = bridge.acceptor.stats.clone();
+    assert!(wait_until(|| stats.get_quotes_sent() > quotes_sent + 5).await);
+    assert!(wait_for_payload(&mut bridge.price_client, OUR_SYMBOL.as_bytes()).await);
+
+    let app = bridge.app.clone();
+    assert!(wait_until(|| app.fix_session_stats.get_rejects() == 1).await);
+    assert_eq!(app.fix_session_stats.get_skipped(), 1);
+    // Neither frame takes the session down
+    assert_eq!(bridge.acceptor.stats.get_connections(), 1);
+    assert_eq!(bridge.acceptor.stats.get_logons(), 1);
+}
+
+#[tokio::test]
+async fn test_market_data_reject_stops_only_the_rejected_symbol() {
+    let mut bridge = start_bridge(fast_settings(MockYbSettings::default().quote_source)).await;
+
+    assert!(wait_for_payload(&mut bridge.price_client, OUR_SYMBOL.as_bytes()).await);
+
+    bridge
+        .acceptor
+        .inject(MockInjection::MarketDataReject(EXTERNAL_SYMBOL.to_string()));
+
+    let app = bridge.app.clone();
+    assert!(wait_until(|| app.fix_session_stats.get_market_data_rejects() == 1).await);
+
+    // Quotes sent ahead of the reject may still be on their way to the client
+    read_for(&mut bridge.price_client, Duration::from_millis(500)).await;
+
+    let received = read_until_payload(&mut bridge.price_client, SECOND_OUR_SYMBOL.as_bytes())
+        .await
+        .unwrap();
+    let received = String::from_utf8_lossy(&received);
+
+    assert!(!received.contains(OUR_SYMBOL));
+    assert_eq!(bridge.acceptor.stats.get_logons(), 1);
+}
+
+#[tokio::test]
+async fn test_closed_subscriber_connection_is_dropped() {
+    let mut bridge = start_bridge(fast_settings(MockYbSettings::default().quote_source)).await;
+
+    assert!(wait_for_payload(&mut bridge.price_client, OUR_SYMBOL.as_bytes()).await);
+
+    let subscribers = bridge.app.broadcast_data.lock().await.get_subscribers();
+    assert_eq!(subscribers.len(), 1);
+
+    // What the slow consumer policy does to a lagging consumer
+    subscribers[0].close_with_reason("Test".to_string());
+
+    let mut chunk = [0u8; 4096];
+    let closed = tokio::time::timeout(Duration::from_secs(15), async {
+        loop {
+            match bridge.price_client.read(&mut chunk).await {
+                Ok(0) | Err(_) => return,
+                // Ticks written ahead of the close
+                Ok(_) => {}
+            }
+        }
+    })
+    .await;
+    assert!(closed.is_ok());
+
+    let app = bridge.app.clone();
+    assert!(
+        wait_until(|| {
+            app.broadcast_data
+                .try_lock()
+                .map(|broadcast_data| broadcast_data.get_subscribers().is_empty())
+                .unwrap_or(false)
+        })
+        .await
+    );
+}
+
+#[tokio::test]
+async fn test_websocket_snapshot_subscribe_and_tick() {
+    let mut bridge = start_bridge(fast_settings(MockYbSettings::default().quote_source)).await;
+
+    assert!(wait_for_payload(&mut bridge.price_client, OUR_SYMBOL.as_bytes()).await);
+
+    let settings = PriceWebSocketSettingsModel {
+        port: get_free_local_addr().port(),
+        bind_address: None,
+    };
+    let ws_addr = settings.get_addr().unwrap();
+    assert!(ws_addr.ip().is_loopback());
+
+    start_price_ws_server(bridge.app.clone(), ws_addr).await;
+
+    let (mut client, _) = tokio_tungstenite::connect_async(format!("ws://{}", ws_addr))
+        .await
+        .unwrap();
+
+    let snapshot = read_ws_until(&mut client, "snapshot_end").await.unwrap();
+    assert_eq!(snapshot[0]["type"], "snapshot_begin");
+    assert!(snapshot
+        .iter()
+        .any(|message| message["type"] == "tick" && message["instrument_id"] == OUR_SYMBOL));
+
+    client
+        .send(Message::text(format!(
+            r#"{{"action":"subscribe","instruments":["{}"]}}"#,
+            SECOND_OUR_SYMBOL
+        )))
+        .await
+        .unwrap();
+
+    // WebSocket subscribers have negative ids
+    let app = bridge.app.clone();
+    assert!(
+        wait_until(|| {
+            app.broadcast_data
+                .try_lock()
+                .map(|broadcast_data| broadcast_data.subscriptions.keys().any(|id| *id < 0))
+                .unwrap_or(false)
+        })
+        .await
+    );
+
+    // Ticks queued ahead of the subscription
+    read_ws_for(&mut client, Duration::from_millis(500)).await;
+
+    let ticks: Vec<serde_json::Value> = read_ws_for(&mut client, Duration::from_secs(1))
+        .await
+        .into_iter()
+        .filter(|message| message["type"] == "tick")
+        .collect();
+
+    assert!(!ticks.is_empty());
+    assert!(ticks
+        .iter()
+        .all(|tick| tick["instrument_id"] == SECOND_OUR_SYMBOL));
+}